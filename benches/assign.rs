@@ -0,0 +1,85 @@
+use arithmetic_progression_sum::{ap_sum, testing::PerRowStepApSumCircuit, ApSumCircuit};
+use criterion::{criterion_group, criterion_main, Criterion};
+use halo2_proofs::{arithmetic::FieldExt, dev::MockProver, halo2curves::pasta::Fp};
+
+const K: u32 = 21;
+const COUNT: usize = 1 << 20;
+const CHUNK_REGION_ROWS: usize = 1 << 10;
+
+const STEP_CACHE_K: u32 = 19;
+const STEP_CACHE_COUNT: usize = 500_000;
+const STEP_CACHE_STEP: u128 = 3;
+
+/// Witness assignment of a 1M-term table, dominated by the per-row field arithmetic, with
+/// the step cached and with it converted on every row as before.
+fn assign(c: &mut Criterion) {
+    let sum = ap_sum(1, 1, COUNT).unwrap();
+    let instance = vec![Fp::from(1), Fp::from_u128(sum)];
+
+    let mut group = c.benchmark_group("assign");
+    group.sample_size(10);
+    group.bench_function("count 2^20", |b| {
+        b.iter(|| MockProver::run(K, &ApSumCircuit::<1, COUNT>, vec![instance.clone()]).unwrap())
+    });
+    group.bench_function("count 2^20, step converted per row", |b| {
+        b.iter(|| {
+            MockProver::run(
+                K,
+                &PerRowStepApSumCircuit::<1, COUNT>,
+                vec![instance.clone()],
+            )
+            .unwrap()
+        })
+    });
+    group.finish();
+}
+
+/// Witness assignment of the 1M-term table in one region and split into regions of
+/// [`CHUNK_REGION_ROWS`] rows, whose cells share the buffers of the whole table.
+fn chunked_assignment(c: &mut Criterion) {
+    let sum = ap_sum(1, 1, COUNT).unwrap();
+    let instance = vec![Fp::from(1), Fp::from_u128(sum)];
+
+    let mut group = c.benchmark_group("chunked assignment");
+    group.sample_size(10);
+    group.bench_function("one region 2^20", |b| {
+        b.iter(|| MockProver::run(K, &ApSumCircuit::<1, COUNT>, vec![instance.clone()]).unwrap())
+    });
+    group.bench_function("1024-row regions 2^20", |b| {
+        b.iter(|| {
+            let circuit = ApSumCircuit::<1, COUNT, CHUNK_REGION_ROWS>;
+            MockProver::run(K, &circuit, vec![instance.clone()]).unwrap()
+        })
+    });
+    group.finish();
+}
+
+/// The chip's assignment of a 500k-term table with the step converted in the loop and
+/// cached outside it; the two timings side by side give the saving. The per-row path
+/// passes the step through `black_box`, so the conversion stays in the loop.
+fn step_caching(c: &mut Criterion) {
+    let sum = ap_sum(1, STEP_CACHE_STEP, STEP_CACHE_COUNT).unwrap();
+    let instance = vec![Fp::from(1), Fp::from_u128(sum)];
+    let per_row = PerRowStepApSumCircuit::<STEP_CACHE_STEP, STEP_CACHE_COUNT>;
+    let cached = ApSumCircuit::<STEP_CACHE_STEP, STEP_CACHE_COUNT>;
+    // Both paths reach the same final sum.
+    MockProver::run(STEP_CACHE_K, &per_row, vec![instance.clone()])
+        .unwrap()
+        .assert_satisfied();
+    MockProver::run(STEP_CACHE_K, &cached, vec![instance.clone()])
+        .unwrap()
+        .assert_satisfied();
+
+    let mut group = c.benchmark_group("step caching");
+    group.sample_size(10);
+    group.bench_function("from_u128 per row 500k", |b| {
+        b.iter(|| MockProver::run(STEP_CACHE_K, &per_row, vec![instance.clone()]).unwrap())
+    });
+    group.bench_function("cached step 500k", |b| {
+        b.iter(|| MockProver::run(STEP_CACHE_K, &cached, vec![instance.clone()]).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, assign, chunked_assignment, step_caching);
+criterion_main!(benches);
@@ -0,0 +1,146 @@
+//! Proof that the AP sum equals an affine combination of public values.
+//!
+//! The circuit computes the sum as [`crate::ApSumCircuit`] does, then checks
+//! `sum == alpha * x + beta * y` in a single row against public `alpha`, `x`, `beta` and
+//! `y`, so the sum itself is never published.
+//!
+//! Instance layout: `[first, alpha, x, beta, y]`.
+
+use crate::{ApSumChip, ApSumConfig};
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+
+#[derive(Clone, Debug)]
+pub struct AffineApSumConfig {
+    ap_sum: ApSumConfig,
+    // [sum, alpha, x, beta, y]
+    advice: [Column<Advice>; 5],
+    selector: Selector,
+    instance: Column<Instance>,
+}
+
+impl AffineApSumConfig {
+    fn configure<F: FieldExt>(
+        meta: &mut ConstraintSystem<F>,
+        ap_sum: ApSumConfig,
+        advice: [Column<Advice>; 5],
+        instance: Column<Instance>,
+    ) -> Self {
+        let selector = meta.selector();
+
+        for column in advice {
+            meta.enable_equality(column);
+        }
+        meta.enable_equality(instance);
+
+        // | advice[0] | advice[1] | advice[2] | advice[3] | advice[4] | selector
+        // -----------------------------------------------------------------------
+        // |    sum    |   alpha   |     x     |   beta    |     y     |    s
+        meta.create_gate("affine combination", |meta| {
+            let [sum, alpha, x, beta, y] =
+                advice.map(|column| meta.query_advice(column, Rotation::cur()));
+            let s = meta.query_selector(selector);
+            // sum == alpha * x + beta * y
+            vec![s * (alpha * x + beta * y - sum)]
+        });
+
+        Self {
+            ap_sum,
+            advice,
+            selector,
+            instance,
+        }
+    }
+
+    /// Constrains `sum` to the affine combination of instance rows 1 to 4.
+    fn assign<F: FieldExt>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        sum: &AssignedCell<F, F>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "affine combination",
+            |mut region| {
+                self.selector.enable(&mut region, 0)?;
+                sum.copy_advice(|| "sum", &mut region, self.advice[0], 0)?;
+                for (row, (name, column)) in ["alpha", "x", "beta", "y"]
+                    .into_iter()
+                    .zip(&self.advice[1..])
+                    .enumerate()
+                {
+                    region.assign_advice_from_instance(
+                        || name,
+                        self.instance,
+                        row + 1,
+                        *column,
+                        0,
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+#[derive(Default)]
+pub struct AffineApSumCircuit<const STEP: u128, const COUNT: usize>;
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> Circuit<F>
+    for AffineApSumCircuit<STEP, COUNT>
+{
+    type Config = AffineApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let table = [meta.advice_column(), meta.advice_column()];
+        let advice = [(); 5].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+
+        let ap_sum = ApSumChip::<_, STEP, COUNT>::configure(meta, table, instance);
+        AffineApSumConfig::configure(meta, ap_sum, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = ApSumChip::<_, STEP, COUNT>::construct(config.ap_sum.clone());
+        let cells = chip.assign(layouter.namespace(|| "AP sum table"))?;
+        config.assign(
+            layouter.namespace(|| "affine combination"),
+            cells.sum.assigned(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{
+        dev::{MockProver, VerifyFailure},
+        halo2curves::pasta::Fp,
+    };
+
+    fn run(alpha: u64, x: u64, beta: u64, y: u64) -> Result<(), Vec<VerifyFailure>> {
+        let k = 4;
+        let instance = [1, alpha, x, beta, y].map(Fp::from).to_vec();
+        // 1 + 2 + 3 + 4 + 5 = 15
+        MockProver::run(k, &AffineApSumCircuit::<1, 5>, vec![instance])
+            .unwrap()
+            .verify()
+    }
+
+    #[test]
+    fn sum_fifteen_is_one_times_ten_plus_one_times_five() {
+        assert_eq!(run(1, 10, 1, 5), Ok(()));
+    }
+
+    #[test]
+    fn unrelated_combination_is_rejected() {
+        assert!(run(1, 10, 1, 6).is_err());
+    }
+}
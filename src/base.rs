@@ -0,0 +1,197 @@
+//! Sum of an arithmetic progression accumulated onto a base value.
+//!
+//! The running sum starts at `base + a_0` instead of `a_0`, so the exposed value is
+//! `base + sum_n a_n`; `a_0` is still the first term. The base is written to a fixed
+//! column that a first-row gate reads, so it is part of the key and public like `STEP`.
+//! For base `100`, step `1` and count `5`, the circuit computes `100 + 15 = 115`.
+//!
+//! Instance layout: `[first, sum]`.
+
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+use std::marker::PhantomData;
+
+#[derive(Clone, Debug)]
+pub struct BaseApSumConfig {
+    // [a_n, sum_n]
+    advice: [Column<Advice>; 2],
+    base: Column<Fixed>,
+    s_first: Selector,
+    s_next: Selector,
+    instance: Column<Instance>,
+}
+
+struct BaseApSumChip<F, const STEP: u128, const COUNT: usize> {
+    config: BaseApSumConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> BaseApSumChip<F, STEP, COUNT> {
+    fn construct(config: BaseApSumConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 2],
+        instance: Column<Instance>,
+    ) -> BaseApSumConfig {
+        let base = meta.fixed_column();
+        let s_first = meta.selector();
+        let s_next = meta.selector();
+
+        meta.enable_equality(advice[0]);
+        meta.enable_equality(advice[1]);
+        meta.enable_equality(instance);
+
+        // |  advice[0]   |    advice[1]   |  base  | s_first | s_next
+        // ------------------------------------------------------------
+        // |     a_0      |     sum_0      |   B    |    s    |
+        // |     a_1      |     sum_1      |        |         |   s
+        // |     a_2      |     sum_2      |        |         |   s
+        // |     ...      |     ...        |        |         |   s
+        meta.create_gate("based first term", |meta| {
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let sum = meta.query_advice(advice[1], Rotation::cur());
+            let base = meta.query_fixed(base, Rotation::cur());
+            let s = meta.query_selector(s_first);
+            // sum_0 == base + a_0
+            vec![s * (base + a - sum)]
+        });
+
+        meta.create_gate("step and sum", |meta| {
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let sum = meta.query_advice(advice[1], Rotation::cur());
+            let prev_a = meta.query_advice(advice[0], Rotation::prev());
+            let prev_sum = meta.query_advice(advice[1], Rotation::prev());
+            let s = meta.query_selector(s_next);
+            vec![
+                // sum == a + prev_sum
+                s.clone() * (a.clone() + prev_sum - sum),
+                // a == prev_a + STEP
+                s * (a - prev_a - Expression::Constant(F::from_u128(STEP))),
+            ]
+        });
+
+        BaseApSumConfig {
+            advice,
+            base,
+            s_first,
+            s_next,
+            instance,
+        }
+    }
+
+    fn assign(&self, mut layouter: impl Layouter<F>, base: F) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "based AP sum table",
+            |mut region| {
+                let a_column = self.config.advice[0];
+                let sum_column = self.config.advice[1];
+                let step = F::from_u128(STEP);
+
+                self.config.s_first.enable(&mut region, 0)?;
+                region.assign_fixed(|| "base", self.config.base, 0, || Value::known(base))?;
+                let mut a_cell = region.assign_advice_from_instance(
+                    || "a",
+                    self.config.instance,
+                    0,
+                    a_column,
+                    0,
+                )?;
+                let mut sum_cell = region.assign_advice(
+                    || "sum",
+                    sum_column,
+                    0,
+                    || a_cell.value().map(|a| base + a),
+                )?;
+
+                for row in 1..COUNT {
+                    self.config.s_next.enable(&mut region, row)?;
+
+                    let new_a_val = a_cell.value().map(|a| *a + step);
+                    a_cell = region.assign_advice(|| "a", a_column, row, || new_a_val)?;
+
+                    let new_sum = sum_cell.value().copied() + new_a_val;
+                    sum_cell = region.assign_advice(|| "sum", sum_column, row, || new_sum)?;
+                }
+
+                Ok(sum_cell)
+            },
+        )
+    }
+
+    fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct BaseApSumCircuit<F: FieldExt, const STEP: u128, const COUNT: usize> {
+    /// The value the running sum starts from, before the first term is added.
+    pub base: F,
+}
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> Circuit<F>
+    for BaseApSumCircuit<F, STEP, COUNT>
+{
+    type Config = BaseApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        // The base fills the fixed column, not the witness.
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+
+        BaseApSumChip::<_, STEP, COUNT>::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = BaseApSumChip::<_, STEP, COUNT>::construct(config);
+        let sum_cell = chip.assign(layouter.namespace(|| "based AP sum table"), self.base)?;
+        chip.expose_public(layouter.namespace(|| "output"), &sum_cell, 1)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, halo2curves::pasta::Fp};
+
+    #[test]
+    fn base_one_hundred_step_one_count_five_works() {
+        let k = 4;
+        let circuit = BaseApSumCircuit::<_, 1, 5> {
+            base: Fp::from(100),
+        };
+        // 100 + 1 + 2 + 3 + 4 + 5 = 115
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(1), Fp::from(115)]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn sum_without_the_base_is_rejected() {
+        let k = 4;
+        let circuit = BaseApSumCircuit::<_, 1, 5> {
+            base: Fp::from(100),
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(1), Fp::from(15)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
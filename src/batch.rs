@@ -0,0 +1,158 @@
+//! Proving many progressions under shared params, with throughput figures.
+//!
+//! [`BatchProver`] proves `(first, step, count)` tasks as [`ApSumCircuitDyn`] circuits
+//! under one set of params. The step and count shape the key, so keys are generated once
+//! per distinct `(step, count)` and reused by later tasks of the same shape. The
+//! [`BatchReport`] records the time spent on each task and on the whole batch, along with
+//! each proof's size.
+
+use crate::{
+    dynamic::ApSumCircuitDyn,
+    proof::{actual_proof_size, prove, Params, Proof},
+    sizing::{check_row, reserved_rows},
+    ApSumError,
+};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    halo2curves::pasta::{EqAffine, Fp},
+    plonk::{keygen_pk, keygen_vk, ProvingKey, VerifyingKey},
+    poly::commitment::Params as _,
+};
+use rand_core::{CryptoRng, RngCore};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+/// One proved task of a batch.
+#[derive(Clone, Debug)]
+pub struct BatchEntry {
+    pub first: u128,
+    pub step: u128,
+    pub count: usize,
+    /// The `[first, sum]` instance the proof verifies against.
+    pub instance: Vec<Fp>,
+    pub proof: Proof,
+    /// Verifying key of the task's `(step, count)` shape.
+    pub vk: VerifyingKey<EqAffine>,
+    /// Key generation time, negligible when an earlier task of the same shape paid for it.
+    pub keygen: Duration,
+    /// Proof creation time.
+    pub prove: Duration,
+    /// Size of the proof in bytes.
+    pub proof_size: usize,
+}
+
+/// Timings and sizes of a proved batch.
+#[derive(Clone, Debug)]
+pub struct BatchReport {
+    /// Wall-clock time of the whole batch, key generation included.
+    pub total: Duration,
+    /// One entry per task, in the order the tasks were added.
+    pub entries: Vec<BatchEntry>,
+}
+
+/// Collects `(first, step, count)` tasks and proves them under shared params.
+pub struct BatchProver<'a> {
+    params: &'a Params,
+    tasks: Vec<(u128, u128, usize)>,
+}
+
+impl<'a> BatchProver<'a> {
+    /// Starts an empty batch proved under `params`, which must fit the largest count.
+    pub fn new(params: &'a Params) -> Self {
+        Self {
+            params,
+            tasks: vec![],
+        }
+    }
+
+    /// Adds the `count`-term progression from `first` with step `step`.
+    pub fn task(mut self, first: u128, step: u128, count: usize) -> Self {
+        self.tasks.push((first, step, count));
+        self
+    }
+
+    /// Proves every task in order, drawing blinding factors from `rng`.
+    ///
+    /// Fails with [`ApSumError::RowOutOfBounds`] on the first task whose table does not fit
+    /// `params`, and with [`ApSumError::Proving`] on the first that cannot be proved.
+    pub fn prove(&self, mut rng: impl RngCore + CryptoRng) -> Result<BatchReport, ApSumError> {
+        let start = Instant::now();
+        let mut keys: HashMap<(u128, usize), ProvingKey<EqAffine>> = HashMap::new();
+        let mut entries = Vec::with_capacity(self.tasks.len());
+        let reserved = reserved_rows::<Fp, ApSumCircuitDyn<Fp>>();
+
+        for &(first, step, count) in &self.tasks {
+            check_row(count.saturating_sub(1), self.params.k(), reserved)?;
+            let circuit = ApSumCircuitDyn {
+                first: Fp::from_u128(first),
+                step: Fp::from_u128(step),
+                count,
+            };
+
+            let keygen_start = Instant::now();
+            if !keys.contains_key(&(step, count)) {
+                let vk = keygen_vk(self.params, &circuit).map_err(ApSumError::Proving)?;
+                let pk = keygen_pk(self.params, vk, &circuit).map_err(ApSumError::Proving)?;
+                keys.insert((step, count), pk);
+            }
+            let pk = &keys[&(step, count)];
+            let keygen = keygen_start.elapsed();
+
+            let instance = circuit.instance();
+            let prove_start = Instant::now();
+            let proof = prove(self.params, pk, circuit, &instance, &mut rng)
+                .map_err(ApSumError::Proving)?;
+            let prove = prove_start.elapsed();
+
+            entries.push(BatchEntry {
+                first,
+                step,
+                count,
+                instance,
+                proof_size: actual_proof_size(&proof),
+                proof,
+                vk: pk.get_vk().clone(),
+                keygen,
+                prove,
+            });
+        }
+
+        Ok(BatchReport {
+            total: start.elapsed(),
+            entries,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proof::verify;
+    use halo2_proofs::poly::commitment::ParamsProver;
+    use rand_core::OsRng;
+
+    #[test]
+    fn batch_of_three_tasks_is_reported_and_verifies() {
+        let params = Params::new(5);
+        let report = BatchProver::new(&params)
+            .task(1, 1, 5)
+            .task(2, 3, 4)
+            .task(7, 1, 5)
+            .prove(OsRng)
+            .unwrap();
+
+        assert_eq!(report.entries.len(), 3);
+        for entry in &report.entries {
+            assert!(entry.prove > Duration::ZERO);
+            assert!(entry.proof_size > 0);
+            assert!(verify(&params, &entry.vk, &entry.proof, &entry.instance));
+        }
+        // 2 + 5 + 8 + 11 = 26
+        assert_eq!(report.entries[1].instance, [Fp::from(2), Fp::from(26)]);
+        // The third task reuses the keys of the first.
+        assert!(report.entries[2].keygen < report.entries[0].keygen);
+        assert!(report.total >= report.entries.iter().map(|entry| entry.prove).sum());
+    }
+}
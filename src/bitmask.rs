@@ -0,0 +1,219 @@
+//! Sum of the terms of an arithmetic progression selected by a public bitmask.
+//!
+//! The `a` column advances by `STEP` on every row as in [`crate::ApSumCircuit`]. Each row
+//! copies its bit from the instance, constrains it boolean and adds `bit * a` to the
+//! running sum, so the verifier chooses an arbitrary subset of indices. This generalises
+//! [`crate::parity`], whose subsets are fixed at key generation. For the mask `10110`,
+//! read from index `0`, over the terms `1..=5` the circuit computes `1 + 3 + 4 = 8`.
+//!
+//! Instance layout: `[first, sum, bit_0, ..., bit_{COUNT-1}]`.
+
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+use std::marker::PhantomData;
+
+/// Instance row of `bit_0`.
+const FIRST_BIT_ROW: usize = 2;
+
+/// Builds the `[first, sum, bit_0, ...]` instance for `mask`.
+#[must_use]
+pub fn bitmask_instance<F: FieldExt>(first: F, sum: F, mask: &[bool]) -> Vec<F> {
+    [first, sum]
+        .into_iter()
+        .chain(mask.iter().map(|bit| F::from(u64::from(*bit))))
+        .collect()
+}
+
+#[derive(Clone, Debug)]
+pub struct BitmaskApSumConfig {
+    // [a_n, sum_n, bit_n]
+    advice: [Column<Advice>; 3],
+    s_first: Selector,
+    s_next: Selector,
+    instance: Column<Instance>,
+}
+
+struct BitmaskApSumChip<F, const STEP: u128, const COUNT: usize> {
+    config: BitmaskApSumConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> BitmaskApSumChip<F, STEP, COUNT> {
+    fn construct(config: BitmaskApSumConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 3],
+        instance: Column<Instance>,
+    ) -> BitmaskApSumConfig {
+        let s_first = meta.selector();
+        let s_next = meta.selector();
+
+        for column in advice {
+            meta.enable_equality(column);
+        }
+        meta.enable_equality(instance);
+
+        // |  advice[0]   |    advice[1]   | advice[2] | s_first | s_next
+        // ---------------------------------------------------------------
+        // |     a_0      |     sum_0      |   bit_0   |    s    |
+        // |     a_1      |     sum_1      |   bit_1   |         |   s
+        // |     a_2      |     sum_2      |   bit_2   |         |   s
+        // |     ...      |     ...        |   ...     |         |   s
+        meta.create_gate("masked first term", |meta| {
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let sum = meta.query_advice(advice[1], Rotation::cur());
+            let bit = meta.query_advice(advice[2], Rotation::cur());
+            let s = meta.query_selector(s_first);
+            vec![
+                // bit is boolean
+                s.clone() * bit.clone() * (Expression::Constant(F::one()) - bit.clone()),
+                // sum_0 == bit_0 * a_0
+                s * (bit * a - sum),
+            ]
+        });
+
+        meta.create_gate("step and masked sum", |meta| {
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let sum = meta.query_advice(advice[1], Rotation::cur());
+            let bit = meta.query_advice(advice[2], Rotation::cur());
+            let prev_a = meta.query_advice(advice[0], Rotation::prev());
+            let prev_sum = meta.query_advice(advice[1], Rotation::prev());
+            let s = meta.query_selector(s_next);
+            vec![
+                // bit is boolean
+                s.clone() * bit.clone() * (Expression::Constant(F::one()) - bit.clone()),
+                // sum == prev_sum + bit * a
+                s.clone() * (bit * a.clone() + prev_sum - sum),
+                // a == prev_a + STEP
+                s * (a - prev_a - Expression::Constant(F::from_u128(STEP))),
+            ]
+        });
+
+        BitmaskApSumConfig {
+            advice,
+            s_first,
+            s_next,
+            instance,
+        }
+    }
+
+    fn assign(&self, mut layouter: impl Layouter<F>) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "bitmask AP sum table",
+            |mut region| {
+                let [a_column, sum_column, bit_column] = self.config.advice;
+                let step = F::from_u128(STEP);
+                let copy_bit = |region: &mut Region<'_, F>, row: usize| {
+                    region.assign_advice_from_instance(
+                        || "bit",
+                        self.config.instance,
+                        FIRST_BIT_ROW + row,
+                        bit_column,
+                        row,
+                    )
+                };
+
+                self.config.s_first.enable(&mut region, 0)?;
+                let mut a_cell = region.assign_advice_from_instance(
+                    || "a",
+                    self.config.instance,
+                    0,
+                    a_column,
+                    0,
+                )?;
+                let bit = copy_bit(&mut region, 0)?;
+                let mut sum_cell = region.assign_advice(
+                    || "sum",
+                    sum_column,
+                    0,
+                    || bit.value().copied() * a_cell.value().copied(),
+                )?;
+
+                for row in 1..COUNT {
+                    self.config.s_next.enable(&mut region, row)?;
+                    let bit = copy_bit(&mut region, row)?;
+
+                    let new_a_val = a_cell.value().map(|a| *a + step);
+                    a_cell = region.assign_advice(|| "a", a_column, row, || new_a_val)?;
+
+                    let new_sum = sum_cell.value().copied() + bit.value().copied() * new_a_val;
+                    sum_cell = region.assign_advice(|| "sum", sum_column, row, || new_sum)?;
+                }
+
+                Ok(sum_cell)
+            },
+        )
+    }
+
+    fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+#[derive(Default)]
+pub struct BitmaskApSumCircuit<const STEP: u128, const COUNT: usize>;
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> Circuit<F>
+    for BitmaskApSumCircuit<STEP, COUNT>
+{
+    type Config = BitmaskApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [(); 3].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+
+        BitmaskApSumChip::<_, STEP, COUNT>::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = BitmaskApSumChip::<_, STEP, COUNT>::construct(config);
+        let sum_cell = chip.assign(layouter.namespace(|| "bitmask AP sum table"))?;
+        chip.expose_public(layouter.namespace(|| "output"), &sum_cell, 1)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, halo2curves::pasta::Fp};
+
+    const MASK: [bool; 5] = [true, false, true, true, false];
+
+    #[test]
+    fn mask_10110_selects_one_three_and_four() {
+        let k = 4;
+        // 1 + 3 + 4 = 8
+        let instance = bitmask_instance(Fp::from(1), Fp::from(8), &MASK);
+        let prover = MockProver::run(k, &BitmaskApSumCircuit::<1, 5>, vec![instance]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn wrong_masked_sum_is_rejected() {
+        let k = 4;
+        // 1 + 2 + 3 + 4 + 5 = 15 ignores the mask
+        let instance = bitmask_instance(Fp::from(1), Fp::from(15), &MASK);
+        let prover = MockProver::run(k, &BitmaskApSumCircuit::<1, 5>, vec![instance]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,231 @@
+//! Sum of the first `count` terms of a `COUNT_MAX`-row table, with `count` public.
+//!
+//! The prover marks the rows that contribute to the sum in an `active` column. The flags
+//! must start at `1` and may only fall from `1` to `0`, so the active rows are a prefix of
+//! the table. A running `count` column adds up the flags and its last value is constrained
+//! to the instance, so the exposed count is exactly the number of terms summed.
+//!
+//! Instance layout: `[first, sum, count]`.
+
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+use std::marker::PhantomData;
+
+#[derive(Clone, Debug)]
+pub struct BoundedCountApSumConfig {
+    // [a_n, sum_n, active_n, count_n]
+    advice: [Column<Advice>; 4],
+    s_first: Selector,
+    s_next: Selector,
+    instance: Column<Instance>,
+}
+
+struct BoundedCountApSumChip<F, const STEP: u128, const COUNT_MAX: usize> {
+    config: BoundedCountApSumConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const STEP: u128, const COUNT_MAX: usize>
+    BoundedCountApSumChip<F, STEP, COUNT_MAX>
+{
+    fn construct(config: BoundedCountApSumConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 4],
+        instance: Column<Instance>,
+    ) -> BoundedCountApSumConfig {
+        let s_first = meta.selector();
+        let s_next = meta.selector();
+
+        meta.enable_equality(advice[0]);
+        meta.enable_equality(advice[1]);
+        meta.enable_equality(advice[3]);
+        meta.enable_equality(instance);
+
+        // | advice[0] | advice[1] | advice[2] | advice[3] |
+        // |    a_n    |   sum_n   | active_n  |  count_n  |
+        // -------------------------------------------------
+        // row 0: active == 1, count == 1                        (s_first)
+        // row n: sum += active * a, count += active,
+        //        active may only fall from 1 to 0               (s_next)
+        meta.create_gate("first row", |meta| {
+            let active = meta.query_advice(advice[2], Rotation::cur());
+            let count = meta.query_advice(advice[3], Rotation::cur());
+            let s = meta.query_selector(s_first);
+            vec![
+                s.clone() * (active - Expression::Constant(F::one())),
+                s * (count - Expression::Constant(F::one())),
+            ]
+        });
+
+        meta.create_gate("step, sum and count", |meta| {
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let sum = meta.query_advice(advice[1], Rotation::cur());
+            let active = meta.query_advice(advice[2], Rotation::cur());
+            let count = meta.query_advice(advice[3], Rotation::cur());
+            let prev_a = meta.query_advice(advice[0], Rotation::prev());
+            let prev_sum = meta.query_advice(advice[1], Rotation::prev());
+            let prev_active = meta.query_advice(advice[2], Rotation::prev());
+            let prev_count = meta.query_advice(advice[3], Rotation::prev());
+            let s = meta.query_selector(s_next);
+            vec![
+                // sum == prev_sum + active * a
+                s.clone() * (prev_sum + active.clone() * a.clone() - sum),
+                // a == prev_a + STEP
+                s.clone() * (a - prev_a - Expression::Constant(F::from_u128(STEP))),
+                // active is boolean
+                s.clone() * active.clone() * (Expression::Constant(F::one()) - active.clone()),
+                // active == 1 implies prev_active == 1
+                s.clone() * active.clone() * (Expression::Constant(F::one()) - prev_active),
+                // count == prev_count + active
+                s * (prev_count + active - count),
+            ]
+        });
+
+        BoundedCountApSumConfig {
+            advice,
+            s_first,
+            s_next,
+            instance,
+        }
+    }
+
+    /// Assigns the table with the first `count` rows active, returning the final
+    /// `(sum, count)` cells.
+    fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        count: Value<usize>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        layouter.assign_region(
+            || "bounded count AP sum table",
+            |mut region| {
+                let [a_column, sum_column, active_column, count_column] = self.config.advice;
+                let active_value = |row: usize| count.map(|k| F::from(u64::from(row < k)));
+                let step = F::from_u128(STEP);
+
+                let mut a_cell = region.assign_advice_from_instance(
+                    || "a",
+                    self.config.instance,
+                    0,
+                    a_column,
+                    0,
+                )?;
+                let mut sum_cell = region.assign_advice_from_instance(
+                    || "sum",
+                    self.config.instance,
+                    0,
+                    sum_column,
+                    0,
+                )?;
+                region.assign_advice(|| "active", active_column, 0, || active_value(0))?;
+                let mut count_cell =
+                    region.assign_advice(|| "count", count_column, 0, || Value::known(F::one()))?;
+                self.config.s_first.enable(&mut region, 0)?;
+
+                for row in 1..COUNT_MAX {
+                    self.config.s_next.enable(&mut region, row)?;
+
+                    let new_a_val = a_cell.value().map(|a| *a + step);
+                    a_cell = region.assign_advice(|| "a", a_column, row, || new_a_val)?;
+
+                    let active = active_value(row);
+                    region.assign_advice(|| "active", active_column, row, || active)?;
+
+                    let new_sum = sum_cell.value().copied() + active * new_a_val;
+                    sum_cell = region.assign_advice(|| "sum", sum_column, row, || new_sum)?;
+                    let new_count = count_cell.value().copied() + active;
+                    count_cell =
+                        region.assign_advice(|| "count", count_column, row, || new_count)?;
+                }
+
+                Ok((sum_cell, count_cell))
+            },
+        )
+    }
+
+    fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+#[derive(Default)]
+pub struct BoundedCountApSumCircuit<const STEP: u128, const COUNT_MAX: usize> {
+    /// The number of active rows, between `1` and `COUNT_MAX`.
+    pub count: Value<usize>,
+}
+
+impl<F: FieldExt, const STEP: u128, const COUNT_MAX: usize> Circuit<F>
+    for BoundedCountApSumCircuit<STEP, COUNT_MAX>
+{
+    type Config = BoundedCountApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [(); 4].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+
+        BoundedCountApSumChip::<_, STEP, COUNT_MAX>::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = BoundedCountApSumChip::<_, STEP, COUNT_MAX>::construct(config);
+        let (sum, count) = chip.assign(
+            layouter.namespace(|| "bounded count AP sum table"),
+            self.count,
+        )?;
+        chip.expose_public(layouter.namespace(|| "sum"), &sum, 1)?;
+        chip.expose_public(layouter.namespace(|| "count"), &count, 2)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{
+        dev::{MockProver, VerifyFailure},
+        halo2curves::pasta::Fp,
+    };
+
+    fn run(active: usize, claimed: u64) -> Result<(), Vec<VerifyFailure>> {
+        let k = 5;
+        let circuit = BoundedCountApSumCircuit::<1, 10> {
+            count: Value::known(active),
+        };
+        // 1 + 2 + 3 + 4 + 5 = 15
+        let instance = vec![Fp::from(1), Fp::from(15), Fp::from(claimed)];
+        MockProver::run(k, &circuit, vec![instance])
+            .unwrap()
+            .verify()
+    }
+
+    #[test]
+    fn five_active_rows_expose_count_five() {
+        assert_eq!(run(5, 5), Ok(()));
+    }
+
+    #[test]
+    fn claimed_count_other_than_active_rows_is_rejected() {
+        assert!(run(5, 6).is_err());
+        assert!(run(5, 4).is_err());
+    }
+}
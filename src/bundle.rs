@@ -0,0 +1,202 @@
+//! Self-describing proof bundles for distribution.
+//!
+//! A [`ProofBundle`] carries everything a verifier needs besides the params and the
+//! verifying key: the proof, its instance and the progression parameters the key is
+//! chosen by. The params are referenced by their [`params_fingerprint`], so
+//! [`verify_bundle`] can reject a bundle made with other params before running the
+//! verifier.
+//!
+//! The encoding is `version` (4 bytes), `params_fingerprint` (32 bytes), `step` and
+//! `count` as in [`encode_params`], the instance length (4 bytes) followed by one 32-byte
+//! element per row, then the proof length (4 bytes) followed by the proof. All integers
+//! are little-endian.
+
+use crate::{
+    bytes::{decode_params, encode_params, sum_from_bytes, sum_to_bytes, Endianness},
+    proof::{params_fingerprint, verify_with_fingerprint, Params, Proof},
+    ApSumError,
+};
+use halo2_proofs::{
+    halo2curves::pasta::{EqAffine, Fp},
+    plonk::VerifyingKey,
+};
+use std::io;
+
+/// Current [`ProofBundle::version`].
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// Bytes of an encoded field element.
+const ELEMENT_LEN: usize = 32;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProofBundle {
+    pub version: u32,
+    pub params_fingerprint: [u8; 32],
+    pub proof: Proof,
+    pub instance: Vec<Fp>,
+    pub step: u128,
+    pub count: usize,
+}
+
+impl Proof {
+    /// Bundles the proof with its `instance` and the hash of the `params` it was made
+    /// with.
+    ///
+    /// Fails with the error of [`params_fingerprint`].
+    pub fn bundle(
+        self,
+        params: &Params,
+        instance: &[Fp],
+        step: u128,
+        count: usize,
+    ) -> Result<ProofBundle, ApSumError> {
+        Ok(ProofBundle {
+            version: BUNDLE_VERSION,
+            params_fingerprint: params_fingerprint(params)?,
+            proof: self,
+            instance: instance.to_vec(),
+            step,
+            count,
+        })
+    }
+}
+
+impl ProofBundle {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.version.to_le_bytes().to_vec();
+        bytes.extend(self.params_fingerprint);
+        bytes.extend(encode_params(
+            self.step,
+            self.count as u64,
+            Endianness::Little,
+        ));
+        bytes.extend((self.instance.len() as u32).to_le_bytes());
+        for value in &self.instance {
+            bytes.extend(sum_to_bytes(*value, Endianness::Little));
+        }
+        bytes.extend((self.proof.as_bytes().len() as u32).to_le_bytes());
+        bytes.extend(self.proof.as_bytes());
+        bytes
+    }
+
+    /// Decodes a bundle encoded by [`ProofBundle::to_bytes`].
+    ///
+    /// Returns [`ApSumError::Io`] if the bytes are truncated, have trailing data, hold a
+    /// non-canonical field element or use an unknown version.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ApSumError> {
+        let mut reader = Reader(bytes);
+        let version = u32::from_le_bytes(reader.array()?);
+        if version != BUNDLE_VERSION {
+            return Err(invalid("unknown bundle version"));
+        }
+        let params_fingerprint = reader.array()?;
+        let (step, count) = decode_params(&reader.array()?, Endianness::Little);
+        let rows = u32::from_le_bytes(reader.array()?) as usize;
+        let instance = (0..rows)
+            .map(|_| {
+                sum_from_bytes(reader.take(ELEMENT_LEN)?, Endianness::Little)
+                    .ok_or_else(|| invalid("instance element is not canonical"))
+            })
+            .collect::<Result<_, _>>()?;
+        let proof_len = u32::from_le_bytes(reader.array()?) as usize;
+        let proof = Proof::from_bytes(reader.take(proof_len)?.to_vec());
+        if !reader.0.is_empty() {
+            return Err(invalid("trailing bytes after bundle"));
+        }
+
+        Ok(Self {
+            version,
+            params_fingerprint,
+            proof,
+            instance,
+            step,
+            count: count as usize,
+        })
+    }
+}
+
+/// Verifies `bundle` with `params` and `vk`, first checking that the bundle was made with
+/// `params`.
+#[must_use = "the proof is only valid if this returns `Ok(true)`"]
+pub fn verify_bundle(
+    bundle: &ProofBundle,
+    params: &Params,
+    vk: &VerifyingKey<EqAffine>,
+) -> Result<bool, ApSumError> {
+    verify_with_fingerprint(
+        params,
+        &bundle.params_fingerprint,
+        vk,
+        &bundle.proof,
+        &bundle.instance,
+    )
+}
+
+fn invalid(message: &str) -> ApSumError {
+    ApSumError::Io(io::Error::new(io::ErrorKind::InvalidData, message))
+}
+
+/// Splits fields off the front of an encoded bundle.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], ApSumError> {
+        if self.0.len() < len {
+            return Err(ApSumError::Io(io::ErrorKind::UnexpectedEof.into()));
+        }
+        let (head, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], ApSumError> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        proof::{prove, setup},
+        ApSumCircuit,
+    };
+    use halo2_proofs::poly::commitment::ParamsProver;
+    use rand_core::OsRng;
+
+    fn bundle() -> (Params, VerifyingKey<EqAffine>, ProofBundle) {
+        let (params, pk) = setup(5, &ApSumCircuit::<1, 5>).unwrap();
+        // 1 + 2 + 3 + 4 + 5 = 15
+        let instance = [Fp::from(1), Fp::from(15)];
+        let proof = prove(&params, &pk, ApSumCircuit::<1, 5>, &instance, OsRng).unwrap();
+        let bundle = proof.bundle(&params, &instance, 1, 5).unwrap();
+        (params, pk.get_vk().clone(), bundle)
+    }
+
+    #[test]
+    fn bundle_round_trips_and_verifies() {
+        let (params, vk, bundle) = bundle();
+        let decoded = ProofBundle::from_bytes(&bundle.to_bytes()).unwrap();
+        assert_eq!(decoded, bundle);
+        assert!(verify_bundle(&decoded, &params, &vk).unwrap());
+
+        let bytes = bundle.to_bytes();
+        assert!(matches!(
+            ProofBundle::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(ApSumError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn bundle_for_other_params_is_rejected() {
+        let (_, vk, bundle) = bundle();
+        // IPA params are deterministic in k, so only a different size gives other params.
+        let other = Params::new(6);
+        assert!(matches!(
+            verify_bundle(&bundle, &other, &vk),
+            Err(ApSumError::ParamsFingerprintMismatch)
+        ));
+    }
+}
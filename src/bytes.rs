@@ -0,0 +1,100 @@
+//! Byte encodings of sums and circuit parameters.
+//!
+//! Field elements are stored in the field's canonical representation, which is
+//! little-endian for the Pasta fields; [`Endianness::Big`] reverses it for consumers that
+//! expect big-endian integers. Parameters are encoded as `step` (16 bytes) followed by
+//! `count` (8 bytes), each in the chosen byte order.
+
+use halo2_proofs::arithmetic::FieldExt;
+
+/// Byte order of an encoding.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Endianness {
+    /// Least significant byte first, the canonical order for Pasta.
+    #[default]
+    Little,
+    Big,
+}
+
+/// Length of [`encode_params`] output.
+pub const PARAMS_LEN: usize = 16 + 8;
+
+/// Encodes `sum` in the byte order `endianness`.
+#[must_use]
+pub fn sum_to_bytes<F: FieldExt>(sum: F, endianness: Endianness) -> Vec<u8> {
+    let mut bytes = sum.to_repr().as_ref().to_vec();
+    if endianness == Endianness::Big {
+        bytes.reverse();
+    }
+    bytes
+}
+
+/// Decodes a sum encoded by [`sum_to_bytes`].
+///
+/// Returns `None` if `bytes` has the wrong length or is not a canonical field element.
+#[must_use]
+pub fn sum_from_bytes<F: FieldExt>(bytes: &[u8], endianness: Endianness) -> Option<F> {
+    let mut repr = F::Repr::default();
+    if repr.as_ref().len() != bytes.len() {
+        return None;
+    }
+    repr.as_mut().copy_from_slice(bytes);
+    if endianness == Endianness::Big {
+        repr.as_mut().reverse();
+    }
+    Option::from(F::from_repr(repr))
+}
+
+/// Encodes the progression parameters `step` and `count`.
+#[must_use]
+pub fn encode_params(step: u128, count: u64, endianness: Endianness) -> [u8; PARAMS_LEN] {
+    let (step, count) = match endianness {
+        Endianness::Little => (step.to_le_bytes(), count.to_le_bytes()),
+        Endianness::Big => (step.to_be_bytes(), count.to_be_bytes()),
+    };
+    let mut bytes = [0; PARAMS_LEN];
+    bytes[..16].copy_from_slice(&step);
+    bytes[16..].copy_from_slice(&count);
+    bytes
+}
+
+/// Decodes parameters encoded by [`encode_params`] into `(step, count)`.
+#[must_use]
+pub fn decode_params(bytes: &[u8; PARAMS_LEN], endianness: Endianness) -> (u128, u64) {
+    let mut step = [0; 16];
+    let mut count = [0; 8];
+    step.copy_from_slice(&bytes[..16]);
+    count.copy_from_slice(&bytes[16..]);
+    match endianness {
+        Endianness::Little => (u128::from_le_bytes(step), u64::from_le_bytes(count)),
+        Endianness::Big => (u128::from_be_bytes(step), u64::from_be_bytes(count)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ap_sum;
+    use halo2_proofs::halo2curves::pasta::Fp;
+
+    #[test]
+    fn sum_round_trips_in_both_byte_orders() {
+        // 1 + 2 + 3 + 4 + 5 = 15
+        let sum = Fp::from_u128(ap_sum(1, 1, 5).unwrap());
+        for endianness in [Endianness::Little, Endianness::Big] {
+            let bytes = sum_to_bytes(sum, endianness);
+            assert_eq!(sum_from_bytes::<Fp>(&bytes, endianness), Some(Fp::from(15)));
+        }
+
+        assert_eq!(sum_to_bytes(sum, Endianness::default())[0], 15);
+        assert_eq!(sum_to_bytes(sum, Endianness::Big)[31], 15);
+    }
+
+    #[test]
+    fn params_round_trip_in_both_byte_orders() {
+        for endianness in [Endianness::Little, Endianness::Big] {
+            let bytes = encode_params(1, 5, endianness);
+            assert_eq!(decode_params(&bytes, endianness), (1, 5));
+        }
+    }
+}
@@ -0,0 +1,132 @@
+//! Proving that can be aborted from another thread.
+//!
+//! [`CancellableApSumCircuit`] lays out the same table as [`crate::ApSumCircuit`] but
+//! checks a shared flag while assigning the witness, so a proving key generated for
+//! `ApSumCircuit<STEP, COUNT>` also proves it.
+//!
+//! Instance layout: `[first, sum]`.
+
+use crate::{
+    proof::{prove, Params, Proof},
+    ApSumChip, ApSumConfig, ApSumError,
+};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::*,
+    halo2curves::pasta::{EqAffine, Fp},
+    plonk::*,
+};
+use rand_core::{CryptoRng, RngCore};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+#[derive(Default)]
+pub struct CancellableApSumCircuit<const STEP: u128, const COUNT: usize> {
+    pub cancel: Arc<AtomicBool>,
+}
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> Circuit<F>
+    for CancellableApSumCircuit<STEP, COUNT>
+{
+    type Config = ApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            cancel: self.cancel.clone(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+
+        ApSumChip::<_, STEP, COUNT>::configure_sum_only(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = ApSumChip::<_, STEP, COUNT>::construct(config);
+        let cells = chip.assign_until_cancelled(
+            layouter.namespace(|| "AP sum table"),
+            Some(self.cancel.as_ref()),
+        )?;
+        chip.expose_public(layouter.namespace(|| "output"), cells.sum.assigned(), 1)?;
+        Ok(())
+    }
+}
+
+/// Proves `ApSumCircuit<STEP, COUNT>` against `instance`, returning
+/// [`ApSumError::Cancelled`] if `cancel` is set before witness assignment completes.
+///
+/// Blinding factors are drawn from `rng`, as in [`prove`].
+pub fn prove_cancellable<const STEP: u128, const COUNT: usize>(
+    params: &Params,
+    pk: &ProvingKey<EqAffine>,
+    instance: &[Fp],
+    cancel: Arc<AtomicBool>,
+    rng: impl RngCore + CryptoRng,
+) -> Result<Proof, ApSumError> {
+    let circuit = CancellableApSumCircuit::<STEP, COUNT> {
+        cancel: cancel.clone(),
+    };
+    prove(params, pk, circuit, instance, rng).map_err(|e| {
+        if cancel.load(Ordering::Relaxed) {
+            ApSumError::Cancelled
+        } else {
+            ApSumError::Proving(e)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        proof::{setup, verify},
+        ApSumCircuit,
+    };
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+    use rand_core::OsRng;
+
+    const K: u32 = 5;
+
+    fn instance() -> Vec<Fp> {
+        // 1 + 2 + 3 + 4 + 5 = 15
+        vec![Fp::from(1), Fp::from(15)]
+    }
+
+    #[test]
+    fn uncancelled_proof_verifies() {
+        let (params, pk) = setup(K, &ApSumCircuit::<1, 5>).unwrap();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let proof = prove_cancellable::<1, 5>(&params, &pk, &instance(), cancel, OsRng).unwrap();
+        assert!(verify(&params, pk.get_vk(), &proof, &instance()));
+    }
+
+    #[test]
+    fn seeded_rng_reproduces_the_proof() {
+        let (params, pk) = setup(K, &ApSumCircuit::<1, 5>).unwrap();
+        let run = || {
+            let cancel = Arc::new(AtomicBool::new(false));
+            let rng = ChaCha20Rng::seed_from_u64(7);
+            prove_cancellable::<1, 5>(&params, &pk, &instance(), cancel, rng).unwrap()
+        };
+        assert_eq!(run(), run());
+    }
+
+    #[test]
+    fn cancel_before_assignment_is_reported() {
+        let (params, pk) = setup(K, &ApSumCircuit::<1, 5>).unwrap();
+        let cancel = Arc::new(AtomicBool::new(true));
+        assert!(matches!(
+            prove_cancellable::<1, 5>(&params, &pk, &instance(), cancel, OsRng),
+            Err(ApSumError::Cancelled)
+        ));
+    }
+}
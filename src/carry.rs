@@ -0,0 +1,544 @@
+//! Proof reconciling the field sum with the true integer sum.
+//!
+//! The table computes the sum in the field, i.e. modulo `p`, while the integer sum of the
+//! terms `first + n * STEP`, with `first` read as its canonical integer, may exceed `p`.
+//! The circuit exposes the integer sum as two 128-bit limbs together with the number of
+//! wraps `k` and proves `integer_sum == field_sum + k * p` over the integers. The integer
+//! sum itself is tied to `first` by decomposing it into canonical limbs and proving
+//! `integer_sum == COUNT * first + STEP * COUNT * (COUNT - 1) / 2` over those limbs. All
+//! limbs are bounded by byte-decomposition range checks, so no side of the limb equations
+//! can wrap around the modulus itself.
+//!
+//! Only the Pasta `Fp` modulus is supported. `k` must fit in a byte, and first terms and
+//! field sums of `2^254` or more (a `2^-128` fraction of the field) cannot be proven.
+//!
+//! Instance layout: `[first, integer_sum_lo, integer_sum_hi, k]`.
+
+use crate::{ap_sum, range::RangeCheckConfig, ApSumChip, ApSumConfig};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::*,
+    halo2curves::{
+        group::ff::{Field, PrimeField},
+        pasta::Fp,
+    },
+    plonk::*,
+    poly::Rotation,
+};
+
+/// Low 128 bits of the Pasta `Fp` modulus.
+pub const MODULUS_LO: u128 = 0x224698fc094cf91b992d30ed00000001;
+/// High bits of the Pasta `Fp` modulus, `p >> 128`.
+pub const MODULUS_HI: u128 = 1 << 126;
+
+/// Bytes in a 128-bit limb.
+const LIMB_BYTES: usize = 16;
+/// Bytes of the carry out of `COUNT * first_lo + steps`, which is below `2^64 + 1`.
+const FIRST_CARRY_BYTES: usize = 9;
+
+#[derive(Clone, Debug)]
+pub struct CarryApSumConfig {
+    ap_sum: ApSumConfig,
+    // [field_sum, field_lo, field_hi, field_hi_x4, integer_lo, integer_hi, k, carry]
+    advice: [Column<Advice>; 8],
+    s_carry: Selector,
+    s_integer: Selector,
+    s_first_byte: Selector,
+    s_next_byte: Selector,
+    range: RangeCheckConfig<8>,
+    instance: Column<Instance>,
+}
+
+struct CarryCells {
+    field_lo: AssignedCell<Fp, Fp>,
+    field_hi: AssignedCell<Fp, Fp>,
+    field_hi_x4: AssignedCell<Fp, Fp>,
+    integer_lo: AssignedCell<Fp, Fp>,
+    integer_hi: AssignedCell<Fp, Fp>,
+    wraps: AssignedCell<Fp, Fp>,
+    carry: AssignedCell<Fp, Fp>,
+    first_lo: AssignedCell<Fp, Fp>,
+    first_hi: AssignedCell<Fp, Fp>,
+    first_hi_x4: AssignedCell<Fp, Fp>,
+    first_carry: AssignedCell<Fp, Fp>,
+}
+
+impl CarryApSumConfig {
+    /// Configures the limb equations for a sum of `count` terms whose part not scaled by
+    /// `first` is `steps`.
+    fn configure(
+        meta: &mut ConstraintSystem<Fp>,
+        ap_sum: ApSumConfig,
+        advice: [Column<Advice>; 8],
+        instance: Column<Instance>,
+        count: u64,
+        steps: u128,
+    ) -> Self {
+        let s_carry = meta.selector();
+        let s_integer = meta.selector();
+        let s_first_byte = meta.selector();
+        let s_next_byte = meta.selector();
+
+        for column in advice {
+            meta.enable_equality(column);
+        }
+        meta.enable_equality(instance);
+
+        let two_128 = || Expression::Constant(Fp::from_u128(1 << 64).square());
+
+        // One row over [field_sum, field_lo, field_hi, field_hi_x4, int_lo, int_hi, k, carry]
+        // with s_carry; the limb equations are exact because both sides stay below p.
+        meta.create_gate("carry", |meta| {
+            let [field_sum, field_lo, field_hi, field_hi_x4, integer_lo, integer_hi, k, carry] =
+                advice.map(|column| meta.query_advice(column, Rotation::cur()));
+            let s = meta.query_selector(s_carry);
+            vec![
+                // field_sum == field_lo + field_hi * 2^128
+                s.clone() * (field_lo.clone() + field_hi.clone() * two_128() - field_sum),
+                // field_hi_x4 == 4 * field_hi
+                s.clone() * (field_hi.clone() * Expression::Constant(Fp::from(4)) - field_hi_x4),
+                // integer_lo + carry * 2^128 == field_lo + k * p_lo
+                s.clone()
+                    * (field_lo + k.clone() * Expression::Constant(Fp::from_u128(MODULUS_LO))
+                        - integer_lo
+                        - carry.clone() * two_128()),
+                // integer_hi == field_hi + k * p_hi + carry
+                s * (field_hi + k * Expression::Constant(Fp::from_u128(MODULUS_HI)) + carry
+                    - integer_hi),
+            ]
+        });
+
+        // The next row holds the limbs of `first` under s_integer and ties them to the
+        // integer limbs of the row above, again exactly:
+        // | advice[0] | advice[1] | advice[2] |  advice[3]  | [4]    | [5]    | [7]         |
+        // | field_sum | field_lo  | field_hi  | field_hi_x4 | int_lo | int_hi | carry       | s_carry
+        // |   first   | first_lo  | first_hi  | first_hi_x4 |        |        | first_carry | s_integer
+        meta.create_gate("integer sum", |meta| {
+            let first = meta.query_advice(advice[0], Rotation::cur());
+            let first_lo = meta.query_advice(advice[1], Rotation::cur());
+            let first_hi = meta.query_advice(advice[2], Rotation::cur());
+            let first_hi_x4 = meta.query_advice(advice[3], Rotation::cur());
+            let first_carry = meta.query_advice(advice[7], Rotation::cur());
+            let integer_lo = meta.query_advice(advice[4], Rotation::prev());
+            let integer_hi = meta.query_advice(advice[5], Rotation::prev());
+            let s = meta.query_selector(s_integer);
+            let count_constant = || Expression::Constant(Fp::from(count));
+            vec![
+                // first == first_lo + first_hi * 2^128
+                s.clone() * (first_lo.clone() + first_hi.clone() * two_128() - first),
+                // first_hi_x4 == 4 * first_hi
+                s.clone() * (first_hi.clone() * Expression::Constant(Fp::from(4)) - first_hi_x4),
+                // integer_lo + first_carry * 2^128 == COUNT * first_lo + steps
+                s.clone()
+                    * (first_lo * count_constant() + Expression::Constant(Fp::from_u128(steps))
+                        - integer_lo
+                        - first_carry.clone() * two_128()),
+                // integer_hi == COUNT * first_hi + first_carry
+                s * (first_hi * count_constant() + first_carry - integer_hi),
+            ]
+        });
+
+        // Byte decomposition, most significant byte first:
+        // | advice[0] | advice[1] |
+        // |  byte_0   |   acc_0   |  s_first_byte: acc == byte
+        // |  byte_n   |   acc_n   |  s_next_byte:  acc == prev_acc * 256 + byte
+        let range = RangeCheckConfig::configure(meta, advice[0]);
+        meta.create_gate("byte decomposition", |meta| {
+            let byte = meta.query_advice(advice[0], Rotation::cur());
+            let acc = meta.query_advice(advice[1], Rotation::cur());
+            let prev_acc = meta.query_advice(advice[1], Rotation::prev());
+            let s_first = meta.query_selector(s_first_byte);
+            let s_next = meta.query_selector(s_next_byte);
+            vec![
+                s_first * (byte.clone() - acc.clone()),
+                s_next * (prev_acc * Expression::Constant(Fp::from(256)) + byte - acc),
+            ]
+        });
+
+        Self {
+            ap_sum,
+            advice,
+            s_carry,
+            s_integer,
+            s_first_byte,
+            s_next_byte,
+            range,
+            instance,
+        }
+    }
+
+    fn assign_carry(
+        &self,
+        mut layouter: impl Layouter<Fp>,
+        sum: &AssignedCell<Fp, Fp>,
+        first: &AssignedCell<Fp, Fp>,
+        witness: Value<CarryWitness>,
+    ) -> Result<CarryCells, Error> {
+        layouter.assign_region(
+            || "carry",
+            |mut region| {
+                self.s_carry.enable(&mut region, 0)?;
+                self.s_integer.enable(&mut region, 1)?;
+                sum.copy_advice(|| "field sum", &mut region, self.advice[0], 0)?;
+                first.copy_advice(|| "first", &mut region, self.advice[0], 1)?;
+
+                let mut assign = |name: &'static str,
+                                  column: usize,
+                                  row: usize,
+                                  value: fn(&CarryWitness) -> Fp| {
+                    region.assign_advice(
+                        || name,
+                        self.advice[column],
+                        row,
+                        || witness.map(|w| value(&w)),
+                    )
+                };
+                Ok(CarryCells {
+                    field_lo: assign("field lo", 1, 0, |w| w.field_lo)?,
+                    field_hi: assign("field hi", 2, 0, |w| w.field_hi)?,
+                    field_hi_x4: assign("field hi x4", 3, 0, |w| w.field_hi * Fp::from(4))?,
+                    integer_lo: assign("integer lo", 4, 0, |w| w.integer_lo)?,
+                    integer_hi: assign("integer hi", 5, 0, |w| w.integer_hi)?,
+                    wraps: assign("k", 6, 0, |w| w.wraps)?,
+                    carry: assign("carry", 7, 0, |w| w.carry)?,
+                    first_lo: assign("first lo", 1, 1, |w| w.first_lo)?,
+                    first_hi: assign("first hi", 2, 1, |w| w.first_hi)?,
+                    first_hi_x4: assign("first hi x4", 3, 1, |w| w.first_hi * Fp::from(4))?,
+                    first_carry: assign("first carry", 7, 1, |w| w.first_carry)?,
+                })
+            },
+        )
+    }
+
+    /// Constrains `cell` to `[0, 2^(8 * bytes))` by decomposing it into bytes.
+    fn range_check(
+        &self,
+        mut layouter: impl Layouter<Fp>,
+        cell: &AssignedCell<Fp, Fp>,
+        bytes: usize,
+    ) -> Result<(), Error> {
+        let repr = cell.value().map(|value| value.to_repr());
+        layouter.assign_region(
+            || "byte decomposition",
+            |mut region| {
+                let byte_at =
+                    |row: usize| repr.map(|repr| Fp::from(u64::from(repr[bytes - 1 - row])));
+
+                let mut acc = byte_at(0);
+                self.range.assign(&mut region, 0, acc)?;
+                self.s_first_byte.enable(&mut region, 0)?;
+                let mut acc_cell = region.assign_advice(|| "acc", self.advice[1], 0, || acc)?;
+
+                for row in 1..bytes {
+                    let byte = byte_at(row);
+                    self.range.assign(&mut region, row, byte)?;
+                    self.s_next_byte.enable(&mut region, row)?;
+                    acc = acc.map(|acc| acc * Fp::from(256)) + byte;
+                    acc_cell = region.assign_advice(|| "acc", self.advice[1], row, || acc)?;
+                }
+
+                region.constrain_equal(acc_cell.cell(), cell.cell())
+            },
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct CarryWitness {
+    field_lo: Fp,
+    field_hi: Fp,
+    integer_lo: Fp,
+    integer_hi: Fp,
+    wraps: Fp,
+    carry: Fp,
+    first_lo: Fp,
+    first_hi: Fp,
+    first_carry: Fp,
+}
+
+/// Little-endian 64-bit limbs, wide enough for `count * first` with a 64-bit count.
+type Wide = [u64; 5];
+
+fn wide(lo: u128, hi: u128) -> Wide {
+    [
+        lo as u64,
+        (lo >> 64) as u64,
+        hi as u64,
+        (hi >> 64) as u64,
+        0,
+    ]
+}
+
+fn wide_from_field(value: Fp) -> Wide {
+    let mut limbs = [0; 5];
+    for (limb, chunk) in limbs.iter_mut().zip(value.to_repr().chunks_exact(8)) {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(chunk);
+        *limb = u64::from_le_bytes(bytes);
+    }
+    limbs
+}
+
+fn wide_add(a: &Wide, b: &Wide) -> Wide {
+    let mut out = [0; 5];
+    let mut carry = 0;
+    for ((out, a), b) in out.iter_mut().zip(a).zip(b) {
+        let t = u128::from(*a) + u128::from(*b) + carry;
+        *out = t as u64;
+        carry = t >> 64;
+    }
+    out
+}
+
+fn wide_sub(a: &Wide, b: &Wide) -> Wide {
+    let mut out = [0; 5];
+    let mut borrow = false;
+    for ((out, a), b) in out.iter_mut().zip(a).zip(b) {
+        let (d, under) = a.overflowing_sub(*b);
+        let (d, under_borrow) = d.overflowing_sub(u64::from(borrow));
+        *out = d;
+        borrow = under || under_borrow;
+    }
+    out
+}
+
+fn wide_mul(a: &Wide, m: u64) -> Wide {
+    let mut out = [0; 5];
+    let mut carry = 0;
+    for (out, a) in out.iter_mut().zip(a) {
+        let t = u128::from(*a) * u128::from(m) + carry;
+        *out = t as u64;
+        carry = t >> 64;
+    }
+    out
+}
+
+fn wide_lt(a: &Wide, b: &Wide) -> bool {
+    a.iter().rev().lt(b.iter().rev())
+}
+
+/// Packs little-endian 64-bit limbs into a field element.
+fn wide_to_field(limbs: &[u64]) -> Fp {
+    limbs.iter().rev().fold(Fp::zero(), |acc, limb| {
+        acc * Fp::from_u128(1 << 64) + Fp::from(*limb)
+    })
+}
+
+/// Splits `count * first + steps` into the canonical field sum and its wraps.
+fn carry_witness(first: Fp, count: u64, steps: u128) -> CarryWitness {
+    let modulus = wide(MODULUS_LO, MODULUS_HI);
+    let first = wide_from_field(first);
+    let integer = wide_add(&wide_mul(&first, count), &wide(steps, 0));
+    // count * first_lo + steps, whose high limbs are the carry into integer_hi
+    let first_low = wide_add(
+        &wide_mul(&[first[0], first[1], 0, 0, 0], count),
+        &wide(steps, 0),
+    );
+
+    let mut field_sum = integer;
+    let mut wraps = 0;
+    while !wide_lt(&field_sum, &modulus) {
+        field_sum = wide_sub(&field_sum, &modulus);
+        wraps += 1;
+    }
+    // field_lo + k * p_lo, whose high limbs are the carry into integer_hi
+    let low = wide_add(
+        &wide(
+            u128::from(field_sum[0]) | (u128::from(field_sum[1]) << 64),
+            0,
+        ),
+        &wide_mul(&wide(MODULUS_LO, 0), wraps),
+    );
+
+    CarryWitness {
+        field_lo: wide_to_field(&field_sum[..2]),
+        field_hi: wide_to_field(&field_sum[2..]),
+        integer_lo: wide_to_field(&integer[..2]),
+        integer_hi: wide_to_field(&integer[2..]),
+        wraps: Fp::from(wraps),
+        carry: wide_to_field(&low[2..]),
+        first_lo: wide_to_field(&first[..2]),
+        first_hi: wide_to_field(&first[2..]),
+        first_carry: wide_to_field(&first_low[2..]),
+    }
+}
+
+#[derive(Default)]
+pub struct CarryApSumCircuit<const STEP: u128, const COUNT: usize>;
+
+impl<const STEP: u128, const COUNT: usize> Circuit<Fp> for CarryApSumCircuit<STEP, COUNT> {
+    type Config = CarryApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        let table = [meta.advice_column(), meta.advice_column()];
+        let advice = [(); 8].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+
+        let ap_sum = ApSumChip::<_, STEP, COUNT>::configure(meta, table, instance);
+        // An overflowing `steps` is rejected by synthesis before the gate is relied on.
+        let steps = ap_sum_steps::<STEP, COUNT>().unwrap_or_default();
+        CarryApSumConfig::configure(meta, ap_sum, advice, instance, COUNT as u64, steps)
+    }
+
+    fn synthesize(&self, config: Self::Config, layouter: impl Layouter<Fp>) -> Result<(), Error> {
+        synthesize_carry::<STEP, COUNT>(config, layouter, carry_witness)
+    }
+}
+
+/// STEP * COUNT * (COUNT - 1) / 2, the part of the sum not scaled by `first`.
+fn ap_sum_steps<const STEP: u128, const COUNT: usize>() -> Option<u128> {
+    ap_sum(0, STEP, COUNT).ok()
+}
+
+/// Synthesizes [`CarryApSumCircuit`] with the carry witness computed by `witness_of` from
+/// `first`, `COUNT` and the steps.
+fn synthesize_carry<const STEP: u128, const COUNT: usize>(
+    config: CarryApSumConfig,
+    mut layouter: impl Layouter<Fp>,
+    witness_of: impl Fn(Fp, u64, u128) -> CarryWitness,
+) -> Result<(), Error> {
+    let steps = ap_sum_steps::<STEP, COUNT>().ok_or(Error::Synthesis)?;
+
+    config
+        .range
+        .load_table(layouter.namespace(|| "range table"))?;
+    let chip = ApSumChip::<_, STEP, COUNT>::construct(config.ap_sum.clone());
+    let cells = chip.assign(layouter.namespace(|| "AP sum table"))?;
+
+    let first = &cells.terms[0];
+    let witness = first
+        .value()
+        .map(|first| witness_of(*first, COUNT as u64, steps));
+    let carry = config.assign_carry(
+        layouter.namespace(|| "carry"),
+        cells.sum.assigned(),
+        first,
+        witness,
+    )?;
+
+    // Bounding both hi and 4 * hi by 2^128 keeps hi below 2^126, so the field sum and
+    // first are canonical. integer_hi is pinned by its gates to range-checked values
+    // and needs no check of its own.
+    for (name, cell, bytes) in [
+        ("field lo", &carry.field_lo, LIMB_BYTES),
+        ("field hi", &carry.field_hi, LIMB_BYTES),
+        ("field hi x4", &carry.field_hi_x4, LIMB_BYTES),
+        ("integer lo", &carry.integer_lo, LIMB_BYTES),
+        ("k", &carry.wraps, 1),
+        ("carry", &carry.carry, 1),
+        ("first lo", &carry.first_lo, LIMB_BYTES),
+        ("first hi", &carry.first_hi, LIMB_BYTES),
+        ("first hi x4", &carry.first_hi_x4, LIMB_BYTES),
+        ("first carry", &carry.first_carry, FIRST_CARRY_BYTES),
+    ] {
+        config.range_check(layouter.namespace(|| name), cell, bytes)?;
+    }
+
+    for (name, cell, row) in [
+        ("integer lo", &carry.integer_lo, 1),
+        ("integer hi", &carry.integer_hi, 2),
+        ("k", &carry.wraps, 3),
+    ] {
+        layouter
+            .namespace(|| name)
+            .constrain_instance(cell.cell(), config.instance, row)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::dev::MockProver;
+
+    fn half() -> Fp {
+        // (p + 1) / 2, so that half + (half + 1) = p + 2
+        Fp::from(2).invert().unwrap()
+    }
+
+    #[test]
+    fn modulus_limbs_match_field() {
+        let two_128 = Fp::from_u128(1 << 64).square();
+        assert_eq!(
+            Fp::from_u128(MODULUS_HI) * two_128 + Fp::from_u128(MODULUS_LO),
+            Fp::zero()
+        );
+    }
+
+    #[test]
+    fn single_wrap_exposes_integer_sum_and_k_one() {
+        let instance = vec![
+            half(),
+            Fp::from_u128(MODULUS_LO + 2),
+            Fp::from_u128(MODULUS_HI),
+            Fp::one(),
+        ];
+        let prover = MockProver::run(9, &CarryApSumCircuit::<1, 2>, vec![instance]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn unwrapped_claim_is_rejected() {
+        // the field sum 2 with no wraps
+        let instance = vec![half(), Fp::from(2), Fp::zero(), Fp::zero()];
+        let prover = MockProver::run(9, &CarryApSumCircuit::<1, 2>, vec![instance]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    /// [`CarryApSumCircuit`] with a forged carry witness.
+    struct ForgedCarryCircuit(fn(Fp, u64, u128) -> CarryWitness);
+
+    impl Circuit<Fp> for ForgedCarryCircuit {
+        type Config = CarryApSumConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self(self.0)
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            <CarryApSumCircuit<1, 2> as Circuit<Fp>>::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            synthesize_carry::<1, 2>(config, layouter, self.0)
+        }
+    }
+
+    #[test]
+    fn forged_unwrapped_witness_is_rejected() {
+        // The honest first limbs with the field sum 2 claimed as the integer sum and no
+        // wraps, which satisfies the carry gate on its own.
+        let forged = |first: Fp, count: u64, steps: u128| CarryWitness {
+            field_lo: Fp::from(2),
+            field_hi: Fp::zero(),
+            integer_lo: Fp::from(2),
+            integer_hi: Fp::zero(),
+            wraps: Fp::zero(),
+            carry: Fp::zero(),
+            ..carry_witness(first, count, steps)
+        };
+        let instance = vec![half(), Fp::from(2), Fp::zero(), Fp::zero()];
+        let prover = MockProver::run(9, &ForgedCarryCircuit(forged), vec![instance]).unwrap();
+        assert!(prover.verify().is_err());
+
+        let instance = vec![
+            half(),
+            Fp::from_u128(MODULUS_LO + 2),
+            Fp::from_u128(MODULUS_HI),
+            Fp::one(),
+        ];
+        let honest = ForgedCarryCircuit(carry_witness);
+        let prover = MockProver::run(9, &honest, vec![instance]).unwrap();
+        prover.assert_satisfied();
+    }
+}
@@ -0,0 +1,88 @@
+//! Two progressions chained in one circuit.
+//!
+//! The sum of the first progression is copied into the first term of the second, so for
+//! `STEP_1 = 1, COUNT_1 = 3` from `1` and `STEP_2 = 1, COUNT_2 = 2` the first sum is `6`
+//! and the circuit computes `6 + 7 = 13`.
+//!
+//! Instance layout: `[first, final_sum]`.
+
+use crate::{ApSumChip, ApSumConfig};
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*};
+
+#[derive(Clone, Debug)]
+pub struct ChainedApSumConfig {
+    first: ApSumConfig,
+    second: ApSumConfig,
+}
+
+#[derive(Default)]
+pub struct ChainedApSumCircuit<
+    const STEP_1: u128,
+    const COUNT_1: usize,
+    const STEP_2: u128,
+    const COUNT_2: usize,
+>;
+
+impl<
+        F: FieldExt,
+        const STEP_1: u128,
+        const COUNT_1: usize,
+        const STEP_2: u128,
+        const COUNT_2: usize,
+    > Circuit<F> for ChainedApSumCircuit<STEP_1, COUNT_1, STEP_2, COUNT_2>
+{
+    type Config = ChainedApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+
+        // Both tables share the columns; each gets its own selector and step constant.
+        ChainedApSumConfig {
+            first: ApSumChip::<_, STEP_1, COUNT_1>::configure(meta, advice, instance),
+            second: ApSumChip::<_, STEP_2, COUNT_2>::configure(meta, advice, instance),
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let first = ApSumChip::<_, STEP_1, COUNT_1>::construct(config.first);
+        let second = ApSumChip::<_, STEP_2, COUNT_2>::construct(config.second);
+        let cells = first.assign(layouter.namespace(|| "first AP sum table"))?;
+        let cells = cells.chain(&second, layouter.namespace(|| "second AP sum table"))?;
+        second.expose_public(layouter.namespace(|| "output"), cells.sum.assigned(), 1)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, halo2curves::pasta::Fp};
+
+    #[test]
+    fn sum_six_starts_second_progression() {
+        let k = 4;
+        // 1 + 2 + 3 = 6, then 6 + 7 = 13
+        let circuit = ChainedApSumCircuit::<1, 3, 1, 2>;
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(1), Fp::from(13)]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn unchained_sum_is_rejected() {
+        let k = 4;
+        let circuit = ChainedApSumCircuit::<1, 3, 1, 2>;
+        // 1 + 2 would hold if the second progression restarted from the first term
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(1), Fp::from(3)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,791 @@
+//! The AP sum chip: its configuration, the cells it assigns and the [`Number`] wrapper
+//! passed between chips.
+
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+use std::{
+    marker::PhantomData,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+#[derive(Clone, Debug)]
+pub struct ApSumConfig {
+    // [a_n, sum_n]
+    pub(crate) advice: [Column<Advice>; 2],
+    enable: RowEnable,
+    /// Ties `a_0` to `sum_0` when the `a` column has no equality enabled.
+    first_term: Option<Selector>,
+    pub(crate) instance: Column<Instance>,
+    max_region_rows: usize,
+}
+
+/// Rows per region of the AP sum table unless overridden with
+/// [`ApSumConfig::with_max_region_rows`]: the whole table goes in one region.
+pub const DEFAULT_MAX_REGION_ROWS: usize = usize::MAX;
+
+/// The smallest region that still advances a split sum-only table by one term.
+pub(crate) const MIN_REGION_ROWS: usize = 3;
+
+/// Rows each region after the first carries over from the previous one in a sum-only
+/// table.
+pub(crate) const SUM_ONLY_BOUNDARY_ROWS: usize = 2;
+
+impl ApSumConfig {
+    /// Splits the table into regions of at most `rows` rows, at least `3`.
+    ///
+    /// Each region after the first starts with copies of the previous region's last rows.
+    /// Where the `a` column has equality enabled, one row carries both the last term and
+    /// running sum. Sum-only tables copy the last two running sums instead and re-derive
+    /// the last term as their difference with the sum constraint, so they carry two rows
+    /// per boundary.
+    #[must_use]
+    pub fn with_max_region_rows(mut self, rows: usize) -> Self {
+        self.max_region_rows = rows.max(MIN_REGION_ROWS);
+        self
+    }
+}
+
+/// What turns the step and sum gate on for a row.
+#[derive(Clone, Copy, Debug)]
+enum RowEnable {
+    /// Separate selectors for the sum and the step constraint, so a row can take an
+    /// injected term that only the sum constraint checks.
+    Selectors { sum: Selector, step: Selector },
+    /// A fixed column holding `1` on active rows and `0`, by being left unassigned,
+    /// everywhere else. It always enables both constraints.
+    Fixed(Column<Fixed>),
+}
+
+impl RowEnable {
+    /// Queries the `[sum, step]` enable expressions.
+    fn query<F: FieldExt>(&self, meta: &mut VirtualCells<'_, F>) -> [Expression<F>; 2] {
+        match *self {
+            RowEnable::Selectors { sum, step } => {
+                [meta.query_selector(sum), meta.query_selector(step)]
+            }
+            RowEnable::Fixed(column) => {
+                let enable = meta.query_fixed(column, Rotation::cur());
+                [enable.clone(), enable]
+            }
+        }
+    }
+
+    fn enable<F: FieldExt>(&self, region: &mut Region<'_, F>, row: usize) -> Result<(), Error> {
+        match *self {
+            RowEnable::Selectors { sum, step } => {
+                sum.enable(region, row)?;
+                step.enable(region, row)
+            }
+            RowEnable::Fixed(column) => region
+                .assign_fixed(|| "enable", column, row, || Value::known(F::one()))
+                .map(|_| ()),
+        }
+    }
+
+    /// Enables only the sum constraint on `row`, failing with [`Error::Synthesis`] for a
+    /// fixed column.
+    fn enable_sum<F: FieldExt>(&self, region: &mut Region<'_, F>, row: usize) -> Result<(), Error> {
+        match *self {
+            RowEnable::Selectors { sum, .. } => sum.enable(region, row),
+            RowEnable::Fixed(_) => Err(Error::Synthesis),
+        }
+    }
+}
+
+/// An assigned field element, as passed between chips.
+#[derive(Clone, Debug)]
+pub struct Number<F: FieldExt>(AssignedCell<F, F>);
+
+impl<F: FieldExt> Number<F> {
+    pub fn value(&self) -> Value<&F> {
+        self.0.value()
+    }
+
+    pub fn cell(&self) -> Cell {
+        self.0.cell()
+    }
+
+    pub fn assigned(&self) -> &AssignedCell<F, F> {
+        &self.0
+    }
+
+    pub fn into_inner(self) -> AssignedCell<F, F> {
+        self.0
+    }
+
+    /// Copies the number into `column` at `offset` of `region`, constrained equal to it.
+    pub fn copy_advice<A, AR>(
+        &self,
+        annotation: A,
+        region: &mut Region<'_, F>,
+        column: Column<Advice>,
+        offset: usize,
+    ) -> Result<Self, Error>
+    where
+        A: Fn() -> AR,
+        AR: Into<String>,
+    {
+        self.0
+            .copy_advice(annotation, region, column, offset)
+            .map(Number)
+    }
+
+    /// Constrains `self` and `other` to hold the same value. Both must be in columns with
+    /// equality enabled.
+    pub fn constrain_equal(
+        &self,
+        mut layouter: impl Layouter<F>,
+        other: &Self,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "constrain equal",
+            |mut region| region.constrain_equal(self.cell(), other.cell()),
+        )
+    }
+}
+
+impl<F: FieldExt> From<AssignedCell<F, F>> for Number<F> {
+    fn from(cell: AssignedCell<F, F>) -> Self {
+        Number(cell)
+    }
+}
+
+/// Cells of an assigned AP sum table.
+#[must_use = "the table is unconstrained until its cells are exposed or copied"]
+pub(crate) struct ApSumCells<F: FieldExt> {
+    pub(crate) last_term: Number<F>,
+    pub(crate) sum: Number<F>,
+    // a_0, ..., a_{COUNT-1}
+    pub(crate) terms: Vec<AssignedCell<F, F>>,
+    // sum_0, ..., sum_{COUNT-1}
+    pub(crate) partial_sums: Vec<AssignedCell<F, F>>,
+}
+
+impl<F: FieldExt> ApSumCells<F> {
+    /// Starts the progression of `chip` from this table's sum, copy-constrained, and
+    /// returns the new table.
+    ///
+    /// The next step and count are the const parameters of `chip`, which must be
+    /// configured in the same circuit.
+    pub(crate) fn chain<const NEXT_STEP: u128, const NEXT_COUNT: usize>(
+        &self,
+        chip: &ApSumChip<F, NEXT_STEP, NEXT_COUNT>,
+        layouter: impl Layouter<F>,
+    ) -> Result<ApSumCells<F>, Error> {
+        chip.assign_from(layouter, self.sum.assigned())
+    }
+}
+
+/// Rows assigned per chunk. Cancel flags and progress callbacks are checked at chunk
+/// boundaries.
+const CHUNK_ROWS: usize = 1 << 10;
+
+pub(crate) struct ApSumChip<F, const STEP: u128, const COUNT: usize> {
+    pub(crate) config: ApSumConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> ApSumChip<F, STEP, COUNT> {
+    pub(crate) fn construct(config: ApSumConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub(crate) fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 2],
+        instance: Column<Instance>,
+    ) -> ApSumConfig {
+        let enable = RowEnable::Selectors {
+            sum: meta.selector(),
+            step: meta.selector(),
+        };
+        Self::configure_with_enable(meta, advice, instance, enable, None)
+    }
+
+    /// Configures the gate with equality enabled only on the sum and instance columns, for
+    /// circuits that copy nothing but running sums.
+    ///
+    /// `a_0` is tied to `sum_0` by a first-row gate rather than copied from the instance,
+    /// which saves a permutation column. The terms of the table cannot be exposed or
+    /// copied elsewhere.
+    pub(crate) fn configure_sum_only(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 2],
+        instance: Column<Instance>,
+    ) -> ApSumConfig {
+        let enable = RowEnable::Selectors {
+            sum: meta.selector(),
+            step: meta.selector(),
+        };
+        let first_term = meta.selector();
+        Self::configure_with_enable(meta, advice, instance, enable, Some(first_term))
+    }
+
+    /// Configures the gate with a fixed column instead of a selector, for circuits that
+    /// budget fixed columns themselves. The assigned table is the same.
+    pub(crate) fn configure_with_fixed(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 2],
+        instance: Column<Instance>,
+    ) -> ApSumConfig {
+        let fixed = meta.fixed_column();
+        Self::configure_with_enable(meta, advice, instance, RowEnable::Fixed(fixed), None)
+    }
+
+    fn configure_with_enable(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 2],
+        instance: Column<Instance>,
+        enable: RowEnable,
+        first_term: Option<Selector>,
+    ) -> ApSumConfig {
+        if first_term.is_none() {
+            meta.enable_equality(advice[0]);
+        }
+        meta.enable_equality(advice[1]);
+        meta.enable_equality(instance);
+
+        // |  advice[0]   |    advice[1]   | sum_selector | step_selector
+        // -------------------------------------------------------------
+        // |     a_0      |     sum_0      |              |
+        // |     a_1      |     sum_1      |      s       |      s
+        // |     a_2      |     sum_2      |      s       |      s
+        // |     ...      |     ...        |      s       |      s
+        meta.create_gate("step and sum", |meta| {
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let sum = meta.query_advice(advice[1], Rotation::cur());
+            let prev_a = meta.query_advice(advice[0], Rotation::prev());
+            let prev_sum = meta.query_advice(advice[1], Rotation::prev());
+            let [s_sum, s_step] = enable.query(meta);
+            vec![
+                // sum == a + prev_sum
+                s_sum * (a.clone() + prev_sum - sum),
+                // a == prev_a + STEP
+                s_step * (a - prev_a - Expression::Constant(F::from_u128(STEP))),
+            ]
+        });
+
+        if let Some(first_term) = first_term {
+            meta.create_gate("first term", |meta| {
+                let a = meta.query_advice(advice[0], Rotation::cur());
+                let sum = meta.query_advice(advice[1], Rotation::cur());
+                let s = meta.query_selector(first_term);
+                // a_0 == sum_0
+                vec![s * (a - sum)]
+            });
+        }
+
+        ApSumConfig {
+            advice,
+            enable,
+            first_term,
+            instance,
+            max_region_rows: DEFAULT_MAX_REGION_ROWS,
+        }
+    }
+
+    pub(crate) fn assign(&self, layouter: impl Layouter<F>) -> Result<ApSumCells<F>, Error> {
+        self.assign_until_cancelled(layouter, None)
+    }
+
+    /// Assigns the table as [`ApSumChip::assign`] does, checking `cancel` every
+    /// [`CHUNK_ROWS`] rows and failing with [`Error::Synthesis`] once it is set.
+    pub(crate) fn assign_until_cancelled(
+        &self,
+        layouter: impl Layouter<F>,
+        cancel: Option<&AtomicBool>,
+    ) -> Result<ApSumCells<F>, Error> {
+        self.assign_rows(layouter, None, cancel, None, Self::cached_step())
+    }
+
+    /// Assigns the table as [`ApSumChip::assign`] does, calling `on_progress` with the
+    /// fraction of rows assigned at every chunk boundary and with `1.0` once done.
+    ///
+    /// The callback only fires while witnesses are known, so the floor planner's
+    /// measurement pass and key generation don't report progress.
+    pub(crate) fn assign_with_progress(
+        &self,
+        layouter: impl Layouter<F>,
+        on_progress: &dyn Fn(f32),
+    ) -> Result<ApSumCells<F>, Error> {
+        self.assign_rows(layouter, None, None, Some(on_progress), Self::cached_step())
+    }
+
+    /// Assigns the table as [`ApSumChip::assign`] does, but converts `STEP` to a field
+    /// element on every row, as the chip once did, for benchmarking the cached step
+    /// against it. The step is passed through [`black_box`](std::hint::black_box) so the
+    /// conversion is not hoisted out of the loop.
+    #[cfg(any(test, feature = "testing"))]
+    pub(crate) fn assign_converting_step_per_row(
+        &self,
+        layouter: impl Layouter<F>,
+    ) -> Result<ApSumCells<F>, Error> {
+        let step = || F::from_u128(std::hint::black_box(STEP));
+        self.assign_rows(layouter, None, None, None, step)
+    }
+
+    /// Returns the step converted to a field element once, for every row of a table.
+    fn cached_step() -> impl Fn() -> F {
+        let step = F::from_u128(STEP);
+        move || step
+    }
+
+    /// Assigns the table starting from a copy of `first` rather than the first instance
+    /// row.
+    pub(crate) fn assign_from(
+        &self,
+        layouter: impl Layouter<F>,
+        first: &AssignedCell<F, F>,
+    ) -> Result<ApSumCells<F>, Error> {
+        self.assign_rows(layouter, Some(first), None, None, Self::cached_step())
+    }
+
+    /// Assigns the table with `sum_0` copied from `first`, or from the first instance row
+    /// if `first` is `None`. `a_0` is copied from the same cell, or set equal to `sum_0` by
+    /// the first-term gate if the chip was configured with
+    /// [`ApSumChip::configure_sum_only`].
+    ///
+    /// The table is split into regions of `max_region_rows` rows as described on
+    /// [`ApSumConfig::with_max_region_rows`]. Each row adds `step()` to the previous term.
+    fn assign_rows(
+        &self,
+        mut layouter: impl Layouter<F>,
+        first: Option<&AssignedCell<F, F>>,
+        cancel: Option<&AtomicBool>,
+        progress: Option<&dyn Fn(f32)>,
+        step: impl Fn() -> F,
+    ) -> Result<ApSumCells<F>, Error> {
+        let cancelled = || cancel.map_or(false, |flag| flag.load(Ordering::Relaxed));
+        let report = |cell: &AssignedCell<F, F>, fraction: f32| {
+            if let Some(on_progress) = progress {
+                cell.value().map(|_| on_progress(fraction));
+            }
+        };
+        let a_column = self.config.advice[0];
+        let sum_column = self.config.advice[1];
+        let region_rows = self.config.max_region_rows;
+        let boundary_rows = match self.config.first_term {
+            Some(_) => SUM_ONLY_BOUNDARY_ROWS,
+            None => 1,
+        };
+
+        // Every region pushes its cells onto the same buffers, sized for the whole table
+        // once, so splitting the table allocates nothing per region.
+        let mut terms: Vec<AssignedCell<F, F>> = Vec::with_capacity(COUNT);
+        let mut partial_sums: Vec<AssignedCell<F, F>> = Vec::with_capacity(COUNT);
+        let mut start = 0;
+        loop {
+            // Every region after the first opens with copies of the previous region's
+            // last rows, which take some of its rows.
+            let assigned = terms.len();
+            let carried = if assigned == 0 { 0 } else { boundary_rows };
+            let end = match carried {
+                0 => region_rows,
+                _ => start.saturating_add(region_rows - carried),
+            }
+            .min(COUNT);
+
+            layouter.assign_region(
+                || "AP sum table",
+                |mut region| {
+                    // The floor planner may run this more than once; drop the cells of an
+                    // earlier run.
+                    terms.truncate(assigned);
+                    partial_sums.truncate(assigned);
+                    let (mut a_cell, mut sum_cell, first_row) = match carried {
+                        0 => {
+                            // Copy the first term into sum_0, and into a_0 unless the
+                            // first-term gate ties it to sum_0. The step and sum gate is
+                            // not enabled on the first row.
+                            let copy_first =
+                                |region: &mut Region<'_, F>,
+                                 name: &'static str,
+                                 column: Column<Advice>| {
+                                    match first {
+                                        Some(first) => {
+                                            first.copy_advice(|| name, region, column, 0)
+                                        }
+                                        None => region.assign_advice_from_instance(
+                                            || name,
+                                            self.config.instance,
+                                            0,
+                                            column,
+                                            0,
+                                        ),
+                                    }
+                                };
+                            let sum_cell = copy_first(&mut region, "sum", sum_column)?;
+                            let a_cell = match self.config.first_term {
+                                Some(first_term) => {
+                                    first_term.enable(&mut region, 0)?;
+                                    let first = sum_cell.value().copied();
+                                    region.assign_advice(|| "a", a_column, 0, || first)?
+                                }
+                                None => copy_first(&mut region, "a", a_column)?,
+                            };
+                            terms.push(a_cell.clone());
+                            partial_sums.push(sum_cell.clone());
+                            (a_cell, sum_cell, 1)
+                        }
+                        _ => {
+                            // Without equality on `a`, the carried term is only a witness
+                            // until the sum constraint on the second row ties it to the
+                            // difference of the copied sums.
+                            let mut last = None;
+                            for offset in 0..carried {
+                                let row = assigned - carried + offset;
+                                let (a, sum) = (&terms[row], &partial_sums[row]);
+                                let sum =
+                                    sum.copy_advice(|| "sum", &mut region, sum_column, offset)?;
+                                let a = match self.config.first_term {
+                                    None => a.copy_advice(|| "a", &mut region, a_column, offset)?,
+                                    Some(_) => {
+                                        if offset > 0 {
+                                            self.config.enable.enable_sum(&mut region, offset)?;
+                                        }
+                                        let term = a.value().copied();
+                                        region.assign_advice(|| "a", a_column, offset, || term)?
+                                    }
+                                };
+                                last = Some((a, sum));
+                            }
+                            let (a_cell, sum_cell) = last.ok_or(Error::Synthesis)?;
+                            (a_cell, sum_cell, start)
+                        }
+                    };
+
+                    for row in first_row..end {
+                        if row % CHUNK_ROWS == 1 {
+                            if cancelled() {
+                                return Err(Error::Synthesis);
+                            }
+                            report(&a_cell, (row - 1) as f32 / (COUNT - 1) as f32);
+                        }
+                        let offset = row - start + carried;
+                        self.config.enable.enable(&mut region, offset)?;
+
+                        let new_a_val = a_cell.value().map(|a| *a + step());
+                        a_cell = region.assign_advice(|| "a", a_column, offset, || new_a_val)?;
+
+                        let new_sum = sum_cell
+                            .value()
+                            .and_then(|sum| new_a_val.map(|new_a| new_a + sum));
+                        sum_cell =
+                            region.assign_advice(|| "sum", sum_column, offset, || new_sum)?;
+                        terms.push(a_cell.clone());
+                        partial_sums.push(sum_cell.clone());
+                    }
+
+                    Ok(())
+                },
+            )?;
+
+            start = end.max(1);
+            if start >= COUNT {
+                break;
+            }
+        }
+
+        let a_cell = terms.last().cloned().ok_or(Error::Synthesis)?;
+        let sum_cell = partial_sums.last().cloned().ok_or(Error::Synthesis)?;
+        report(&a_cell, 1.0);
+        Ok(ApSumCells {
+            last_term: Number(a_cell),
+            sum: Number(sum_cell),
+            terms,
+            partial_sums,
+        })
+    }
+
+    /// Assigns the given terms instead of deriving them from the first term and `STEP`.
+    ///
+    /// The running sums are computed from `values` and the gates are enabled as in
+    /// [`ApSumChip::assign`], so an inconsistent sequence fails the step constraint. `sum_0`
+    /// is still constrained to the first instance row and `a_0` to `sum_0`.
+    pub(crate) fn assign_with_values(
+        &self,
+        layouter: impl Layouter<F>,
+        values: &[Value<F>],
+    ) -> Result<ApSumCells<F>, Error> {
+        self.assign_with_injected(layouter, values, &[])
+    }
+
+    /// Assigns the given terms as [`ApSumChip::assign_with_values`] does, but enables
+    /// only the sum constraint on the `injected` rows, whose terms are free.
+    pub(crate) fn assign_with_injected(
+        &self,
+        mut layouter: impl Layouter<F>,
+        values: &[Value<F>],
+        injected: &[usize],
+    ) -> Result<ApSumCells<F>, Error> {
+        let sums: Vec<_> = values
+            .iter()
+            .scan(Value::known(F::zero()), |sum, value| {
+                let next = *sum + *value;
+                *sum = next;
+                Some(next)
+            })
+            .collect();
+        self.assign_table(layouter, values, &sums, injected)
+    }
+
+    /// Assigns `terms` and running `sums` verbatim, enabling the gates as
+    /// [`ApSumChip::assign_with_injected`] does.
+    ///
+    /// Only the gates tie the sums to the terms, so tests can tamper with either column.
+    /// `sum_0` is constrained to the first instance row, and `a_0` to `sum_0` by a copy or,
+    /// for [`ApSumChip::configure_sum_only`], by the first-term gate.
+    pub(crate) fn assign_table(
+        &self,
+        mut layouter: impl Layouter<F>,
+        terms: &[Value<F>],
+        sums: &[Value<F>],
+        injected: &[usize],
+    ) -> Result<ApSumCells<F>, Error> {
+        if COUNT == 0 || terms.len() != COUNT || sums.len() != COUNT {
+            return Err(Error::Synthesis);
+        }
+
+        let cells = layouter.assign_region(
+            || "AP sum table",
+            |mut region| {
+                let a_column = self.config.advice[0];
+                let sum_column = self.config.advice[1];
+
+                let mut a_cell = region.assign_advice(|| "a", a_column, 0, || terms[0])?;
+                let mut sum_cell = region.assign_advice(|| "sum", sum_column, 0, || sums[0])?;
+                // Without this, shifting every term by the same constant keeps the step and
+                // sum gates satisfied and the sum free.
+                match self.config.first_term {
+                    Some(first_term) => first_term.enable(&mut region, 0)?,
+                    None => region.constrain_equal(a_cell.cell(), sum_cell.cell())?,
+                }
+                let mut term_cells = Vec::with_capacity(COUNT);
+                let mut partial_sums = Vec::with_capacity(COUNT);
+                term_cells.push(a_cell.clone());
+                partial_sums.push(sum_cell.clone());
+
+                for (row, (term, sum)) in terms.iter().zip(sums).enumerate().skip(1) {
+                    if injected.contains(&row) {
+                        self.config.enable.enable_sum(&mut region, row)?;
+                    } else {
+                        self.config.enable.enable(&mut region, row)?;
+                    }
+
+                    a_cell = region.assign_advice(|| "a", a_column, row, || *term)?;
+                    sum_cell = region.assign_advice(|| "sum", sum_column, row, || *sum)?;
+                    term_cells.push(a_cell.clone());
+                    partial_sums.push(sum_cell.clone());
+                }
+
+                Ok(ApSumCells {
+                    last_term: Number(a_cell),
+                    sum: Number(sum_cell),
+                    terms: term_cells,
+                    partial_sums,
+                })
+            },
+        )?;
+
+        let first_sum = &cells.partial_sums[0];
+        layouter.constrain_instance(first_sum.cell(), self.config.instance, 0)?;
+        Ok(cells)
+    }
+
+    pub(crate) fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+
+    /// Constrains `sum_index` to `instance_row` for each `(index, instance_row)`.
+    ///
+    /// Fails with [`Error::Synthesis`] if an index is not below `COUNT`.
+    pub(crate) fn expose_checkpoints(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cells: &ApSumCells<F>,
+        checkpoints: &[(usize, usize)],
+    ) -> Result<(), Error> {
+        for &(index, instance_row) in checkpoints {
+            let cell = cells.partial_sums.get(index).ok_or(Error::Synthesis)?;
+            self.expose_public(layouter.namespace(|| "checkpoint"), cell, instance_row)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, halo2curves::pasta::Fp};
+
+    /// Two progressions over shared columns whose sums are tied together.
+    #[derive(Default)]
+    struct EqualSumsCircuit<
+        const STEP_1: u128,
+        const COUNT_1: usize,
+        const STEP_2: u128,
+        const COUNT_2: usize,
+    >;
+
+    impl<const STEP_1: u128, const COUNT_1: usize, const STEP_2: u128, const COUNT_2: usize>
+        Circuit<Fp> for EqualSumsCircuit<STEP_1, COUNT_1, STEP_2, COUNT_2>
+    {
+        type Config = (ApSumConfig, ApSumConfig);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let advice = [meta.advice_column(), meta.advice_column()];
+            let instance = meta.instance_column();
+            (
+                ApSumChip::<_, STEP_1, COUNT_1>::configure(meta, advice, instance),
+                ApSumChip::<_, STEP_2, COUNT_2>::configure(meta, advice, instance),
+            )
+        }
+
+        fn synthesize(
+            &self,
+            (first, second): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let first = ApSumChip::<_, STEP_1, COUNT_1>::construct(first);
+            let second = ApSumChip::<_, STEP_2, COUNT_2>::construct(second);
+            let first_sum = first.assign(layouter.namespace(|| "first table"))?.sum;
+            let second_sum = second.assign(layouter.namespace(|| "second table"))?.sum;
+            first_sum.constrain_equal(layouter.namespace(|| "equal sums"), &second_sum)?;
+            first.expose_public(layouter.namespace(|| "output"), first_sum.assigned(), 1)
+        }
+    }
+
+    /// The table from `first = 1` with every term shifted by `shift`, assigned verbatim with
+    /// `sum_0 = first` and running sums over the shifted terms.
+    struct ShiftedTableCircuit {
+        shift: u64,
+    }
+
+    impl Circuit<Fp> for ShiftedTableCircuit {
+        type Config = ApSumConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self { shift: self.shift }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let advice = [meta.advice_column(), meta.advice_column()];
+            let instance = meta.instance_column();
+            ApSumChip::<_, 1, 4>::configure(meta, advice, instance)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let terms: Vec<_> = (0..4).map(|n| Fp::from(1 + self.shift + n)).collect();
+            let mut sums = vec![Fp::one()];
+            for term in &terms[1..] {
+                sums.push(sums[sums.len() - 1] + term);
+            }
+
+            let known =
+                |values: &[Fp]| -> Vec<_> { values.iter().copied().map(Value::known).collect() };
+            let chip = ApSumChip::<_, 1, 4>::construct(config);
+            let cells = chip.assign_table(
+                layouter.namespace(|| "AP sum table"),
+                &known(&terms),
+                &known(&sums),
+                &[],
+            )?;
+            chip.expose_public(layouter.namespace(|| "output"), cells.sum.assigned(), 1)
+        }
+    }
+
+    #[test]
+    fn shifted_terms_are_rejected() {
+        let k = 4;
+        // 1 + 2 + 3 + 4 = 10
+        let circuit = ShiftedTableCircuit { shift: 0 };
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(1), Fp::from(10)]]).unwrap();
+        prover.assert_satisfied();
+
+        // 1 + 7 + 8 + 9 = 25 satisfies every step and sum gate
+        let circuit = ShiftedTableCircuit { shift: 5 };
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(1), Fp::from(25)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    /// A single table assigned with at most `MAX_ROWS` rows per region.
+    #[derive(Default)]
+    struct SplitCircuit<const MAX_ROWS: usize>;
+
+    impl<const MAX_ROWS: usize> Circuit<Fp> for SplitCircuit<MAX_ROWS> {
+        type Config = ApSumConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let advice = [meta.advice_column(), meta.advice_column()];
+            let instance = meta.instance_column();
+            ApSumChip::<_, 1, 10>::configure(meta, advice, instance).with_max_region_rows(MAX_ROWS)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let chip = ApSumChip::<_, 1, 10>::construct(config);
+            let cells = chip.assign(layouter.namespace(|| "AP sum table"))?;
+            chip.expose_public(layouter.namespace(|| "output"), cells.sum.assigned(), 1)
+        }
+    }
+
+    #[test]
+    fn split_table_matches_single_region() {
+        let k = 5;
+        // 1 + 2 + ... + 10 = 55
+        let instance = vec![Fp::from(1), Fp::from(55)];
+        let prover = MockProver::run(
+            k,
+            &SplitCircuit::<DEFAULT_MAX_REGION_ROWS>,
+            vec![instance.clone()],
+        )
+        .unwrap();
+        prover.assert_satisfied();
+        let prover = MockProver::run(k, &SplitCircuit::<3>, vec![instance]).unwrap();
+        prover.assert_satisfied();
+
+        let instance = vec![Fp::from(1), Fp::from(56)];
+        let prover = MockProver::run(k, &SplitCircuit::<3>, vec![instance]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn constrain_equal_ties_two_sums() {
+        let k = 4;
+        let instance = vec![Fp::from(1), Fp::from(10)];
+        // 1 + 2 + 3 + 4 = 10 = 1 + 9
+        let circuit = EqualSumsCircuit::<1, 4, 8, 2>;
+        let prover = MockProver::run(k, &circuit, vec![instance.clone()]).unwrap();
+        prover.assert_satisfied();
+
+        // 1 + 8 = 9 differs from the exposed 10
+        let circuit = EqualSumsCircuit::<1, 4, 7, 2>;
+        let prover = MockProver::run(k, &circuit, vec![instance]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
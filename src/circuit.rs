@@ -0,0 +1,623 @@
+//! [`ApSumCircuit`] and the variants that differ only in which cells they expose.
+
+use crate::{
+    chip::{ApSumChip, ApSumConfig, Number},
+    sizing::{check_row, reserved_rows, suggest_k, suggest_k_with_max_region_rows, table_rows},
+    ApSumError, DEFAULT_MAX_REGION_ROWS,
+};
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, halo2curves::pasta::Fp, plonk::*};
+
+/// Proves the sum of `COUNT` terms stepping by `STEP`, laid out in regions of at most
+/// `MAX_REGION_ROWS` rows as set by [`ApSumCircuit::with_max_region_rows`].
+#[derive(Default)]
+pub struct ApSumCircuit<
+    const STEP: u128,
+    const COUNT: usize,
+    const MAX_REGION_ROWS: usize = DEFAULT_MAX_REGION_ROWS,
+>;
+
+impl<const STEP: u128, const COUNT: usize, const MAX_REGION_ROWS: usize>
+    ApSumCircuit<STEP, COUNT, MAX_REGION_ROWS>
+{
+    /// Returns the same circuit with its table split into regions of at most `ROWS`
+    /// rows, as [`ApSumConfig::with_max_region_rows`].
+    #[must_use]
+    pub fn with_max_region_rows<const ROWS: usize>(self) -> ApSumCircuit<STEP, COUNT, ROWS> {
+        ApSumCircuit::<STEP, COUNT, ROWS>
+    }
+
+    /// Rows assigned in each of the `a` and `sum` columns: one per term, plus the rows
+    /// carried over at each region boundary.
+    pub const fn assigned_row_count() -> usize {
+        table_rows(COUNT, MAX_REGION_ROWS)
+    }
+
+    /// Returns the tightest `k` for this circuit, counting its region boundaries.
+    #[must_use]
+    pub fn suggested_k() -> u32 {
+        suggest_k_with_max_region_rows(COUNT, MAX_REGION_ROWS)
+    }
+
+    /// Checks that the table fits a circuit of size `2^k`, whose step and sum gate is
+    /// enabled up to its last assigned row.
+    ///
+    /// Fails with [`ApSumError::RowOutOfBounds`] where synthesis would fail with halo2's
+    /// less specific [`Error::NotEnoughRowsAvailable`].
+    pub fn check_rows(k: u32) -> Result<(), ApSumError> {
+        let last_row = Self::assigned_row_count().saturating_sub(1);
+        check_row(last_row, k, reserved_rows::<Fp, Self>())
+    }
+
+    /// Configures the AP sum over columns allocated by a parent circuit.
+    ///
+    /// `advice` is `[a, sum]`. The chip enables equality on the sum column and on
+    /// `instance`, and adds its own selectors and gates, so the columns may be shared
+    /// with other chips of the parent: each table is laid out in its own region. Chips
+    /// sharing `instance` must be given disjoint instance rows in
+    /// [`ApSumCircuit::synthesize_at`].
+    pub fn configure_with_columns<F: FieldExt>(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 2],
+        instance: Column<Instance>,
+    ) -> ApSumConfig {
+        ApSumChip::<_, STEP, COUNT>::configure_sum_only(meta, advice, instance)
+            .with_max_region_rows(MAX_REGION_ROWS)
+    }
+
+    /// Assigns the table configured by [`ApSumCircuit::configure_with_columns`], reading
+    /// the first term from instance row `first_row` and exposing the sum at `sum_row`.
+    ///
+    /// Returns the sum, so the parent can constrain it further.
+    pub fn synthesize_at<F: FieldExt>(
+        config: ApSumConfig,
+        mut layouter: impl Layouter<F>,
+        first_row: usize,
+        sum_row: usize,
+    ) -> Result<Number<F>, Error> {
+        let chip = ApSumChip::<_, STEP, COUNT>::construct(config);
+        let cells = if first_row == 0 {
+            chip.assign(layouter.namespace(|| "AP sum table"))?
+        } else {
+            let first = layouter.assign_region(
+                || "first term",
+                |mut region| {
+                    region.assign_advice_from_instance(
+                        || "first",
+                        chip.config.instance,
+                        first_row,
+                        chip.config.advice[1],
+                        0,
+                    )
+                },
+            )?;
+            chip.assign_from(layouter.namespace(|| "AP sum table"), &first)?
+        };
+        chip.expose_public(
+            layouter.namespace(|| "output"),
+            cells.sum.assigned(),
+            sum_row,
+        )?;
+        Ok(cells.sum)
+    }
+}
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize, const MAX_REGION_ROWS: usize> Circuit<F>
+    for ApSumCircuit<STEP, COUNT, MAX_REGION_ROWS>
+{
+    type Config = ApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+
+        Self::configure_with_columns(meta, advice, instance)
+    }
+
+    fn synthesize(&self, config: Self::Config, layouter: impl Layouter<F>) -> Result<(), Error> {
+        Self::synthesize_at(config, layouter, 0, 1).map(|_| ())
+    }
+}
+
+/// Like [`ApSumCircuit`], but additionally exposes the last term `a_{COUNT-1}`.
+///
+/// Instance layout: `[first, sum, last_term]`.
+#[derive(Default)]
+pub struct ApSumLastTermCircuit<const STEP: u128, const COUNT: usize>;
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> Circuit<F>
+    for ApSumLastTermCircuit<STEP, COUNT>
+{
+    type Config = ApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+
+        ApSumChip::<_, STEP, COUNT>::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = ApSumChip::<_, STEP, COUNT>::construct(config);
+        let cells = chip.assign(layouter.namespace(|| "AP sum table"))?;
+        chip.expose_public(layouter.namespace(|| "sum"), cells.sum.assigned(), 1)?;
+        let last_term = cells.last_term.assigned();
+        chip.expose_public(layouter.namespace(|| "last term"), last_term, 2)?;
+        Ok(())
+    }
+}
+
+/// Like [`ApSumCircuit`], but with every term supplied by the prover.
+///
+/// Meant for testing the constraints against malicious or edge-case witnesses: a sequence
+/// that is not an arithmetic progression with step `STEP` is rejected.
+///
+/// Instance layout: `[first, sum]`.
+pub struct ApSumWitnessCircuit<F: FieldExt, const STEP: u128, const COUNT: usize> {
+    pub terms: Vec<Value<F>>,
+}
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> Circuit<F>
+    for ApSumWitnessCircuit<F, STEP, COUNT>
+{
+    type Config = ApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            terms: vec![Value::unknown(); COUNT],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+
+        ApSumChip::<_, STEP, COUNT>::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = ApSumChip::<_, STEP, COUNT>::construct(config);
+        let cells = chip.assign_with_values(layouter.namespace(|| "AP sum table"), &self.terms)?;
+        chip.expose_public(layouter.namespace(|| "output"), cells.sum.assigned(), 1)?;
+        Ok(())
+    }
+}
+
+/// Like [`ApSumCircuit`], but exposes every running sum.
+///
+/// Instance layout: `[first, sum_0, ..., sum_{COUNT-1}]`.
+#[derive(Default)]
+pub struct ApSumPartialSumsCircuit<const STEP: u128, const COUNT: usize>;
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> Circuit<F>
+    for ApSumPartialSumsCircuit<STEP, COUNT>
+{
+    type Config = ApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+
+        ApSumChip::<_, STEP, COUNT>::configure_sum_only(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = ApSumChip::<_, STEP, COUNT>::construct(config);
+        let cells = chip.assign(layouter.namespace(|| "AP sum table"))?;
+        for (index, cell) in cells.partial_sums.iter().enumerate() {
+            chip.expose_public(layouter.namespace(|| "partial sum"), cell, index + 1)?;
+        }
+        Ok(())
+    }
+}
+
+/// Like [`ApSumCircuit`], but also exposes the running sums at chosen indices only.
+///
+/// Instance layout: `[first, sum, sum_{checkpoints[0]}, sum_{checkpoints[1]}, ...]`.
+#[derive(Clone, Default)]
+pub struct ApSumCheckpointsCircuit<const STEP: u128, const COUNT: usize> {
+    /// Indices `n` of the running sums `sum_n` to expose, in instance order.
+    pub checkpoints: Vec<usize>,
+}
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> Circuit<F>
+    for ApSumCheckpointsCircuit<STEP, COUNT>
+{
+    type Config = ApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        // The checkpoints shape the copy constraints, not the witness.
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+
+        ApSumChip::<_, STEP, COUNT>::configure_sum_only(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = ApSumChip::<_, STEP, COUNT>::construct(config);
+        let cells = chip.assign(layouter.namespace(|| "AP sum table"))?;
+        chip.expose_public(layouter.namespace(|| "output"), cells.sum.assigned(), 1)?;
+        let checkpoints: Vec<_> = self
+            .checkpoints
+            .iter()
+            .enumerate()
+            .map(|(i, index)| (*index, i + 2))
+            .collect();
+        chip.expose_checkpoints(layouter.namespace(|| "checkpoints"), &cells, &checkpoints)
+    }
+}
+
+/// Proves the running sum `sum_{PREFIX-1}` of the `COUNT`-term progression while laying
+/// out only its first `PREFIX` rows.
+///
+/// The later terms never enter the table, so the circuit size depends on `PREFIX` alone;
+/// see [`ApSumPrefixCircuit::suggested_k`]. Synthesis fails with [`Error::Synthesis`]
+/// unless `1 <= PREFIX <= COUNT`.
+///
+/// Instance layout: `[first, sum_{PREFIX-1}]`.
+#[derive(Default)]
+pub struct ApSumPrefixCircuit<const STEP: u128, const COUNT: usize, const PREFIX: usize>;
+
+impl<const STEP: u128, const COUNT: usize, const PREFIX: usize>
+    ApSumPrefixCircuit<STEP, COUNT, PREFIX>
+{
+    /// Returns the tightest `k` for the `PREFIX` rows the table occupies.
+    pub fn suggested_k() -> u32 {
+        suggest_k(PREFIX)
+    }
+}
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize, const PREFIX: usize> Circuit<F>
+    for ApSumPrefixCircuit<STEP, COUNT, PREFIX>
+{
+    type Config = ApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+
+        ApSumCircuit::<STEP, PREFIX>::configure_with_columns(meta, advice, instance)
+    }
+
+    fn synthesize(&self, config: Self::Config, layouter: impl Layouter<F>) -> Result<(), Error> {
+        if PREFIX == 0 || PREFIX > COUNT {
+            return Err(Error::Synthesis);
+        }
+        ApSumCircuit::<STEP, PREFIX>::synthesize_at(config, layouter, 0, 1).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ap_sum, ap_sum_field, chain, dynamic::ApSumCircuitDyn, instance,
+        sizing::minimum_k_reserving, testing,
+    };
+    use halo2_proofs::{
+        dev::{metadata, FailureLocation, MockProver, VerifyFailure},
+        halo2curves::{bn256::Fr, pasta::Fp},
+    };
+
+    /// Runs the progression `first, first + step, ...` of `count` terms through
+    /// `MockProver` over `F` and asserts whether it is satisfied.
+    ///
+    /// The instance carries the true sum when `expect_sat` is set and the sum plus one
+    /// otherwise, so a single table of cases covers both outcomes for every field.
+    fn run_case<F: FieldExt>(first: u64, step: u64, count: usize, expect_sat: bool) {
+        let circuit = ApSumCircuitDyn {
+            first: F::from(first),
+            step: F::from(step),
+            count,
+        };
+        let mut instance = circuit.instance();
+        if !expect_sat {
+            instance[1] += F::one();
+        }
+        let k = minimum_k_reserving(count, reserved_rows::<F, ApSumCircuitDyn<F>>());
+        let prover = MockProver::run(k, &circuit, vec![instance]).unwrap();
+        assert_eq!(
+            prover.verify().is_ok(),
+            expect_sat,
+            "first {first}, step {step}, count {count}"
+        );
+    }
+
+    /// Like [`run_case`], but runs the const-generic [`ApSumCircuit`], whose step is
+    /// converted once per table rather than once per row.
+    fn run_const_case<F: FieldExt, const STEP: u128, const COUNT: usize>(
+        first: u64,
+        expect_sat: bool,
+    ) {
+        let first = F::from(first);
+        let mut sum = ap_sum_field(first, F::from_u128(STEP), COUNT);
+        if !expect_sat {
+            sum += F::one();
+        }
+        let k = minimum_k_reserving(
+            ApSumCircuit::<STEP, COUNT>::assigned_row_count(),
+            reserved_rows::<F, ApSumCircuit<STEP, COUNT>>(),
+        );
+        let prover =
+            MockProver::run(k, &ApSumCircuit::<STEP, COUNT>, vec![vec![first, sum]]).unwrap();
+        assert_eq!(
+            prover.verify().is_ok(),
+            expect_sat,
+            "step {STEP}, count {COUNT}"
+        );
+    }
+
+    const CASES: [(u64, u64, usize); 4] = [(1, 1, 5), (1, 3, 4), (7, 0, 3), (3, 7, 200)];
+
+    #[test]
+    fn cases_hold_over_pasta_fp() {
+        for (first, step, count) in CASES {
+            run_case::<Fp>(first, step, count, true);
+            run_case::<Fp>(first, step, count, false);
+        }
+    }
+
+    #[test]
+    fn cases_hold_over_bn256_fr() {
+        for (first, step, count) in CASES {
+            run_case::<Fr>(first, step, count, true);
+            run_case::<Fr>(first, step, count, false);
+        }
+    }
+
+    #[test]
+    fn split_table_is_satisfied_only_by_the_true_sum() {
+        // Three-row regions carry two rows each, so the ten terms take 24 rows.
+        let circuit = ApSumCircuit::<1, 10>.with_max_region_rows::<3>();
+        let k = ApSumCircuit::<1, 10, 3>::suggested_k();
+        assert_eq!(k, 5);
+        MockProver::run(k, &circuit, vec![vec![Fp::from(1), Fp::from(55)]])
+            .unwrap()
+            .assert_satisfied();
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(1), Fp::from(56)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn ap_sum_step_one_count_five_works() {
+        // 1 + 2 + 3 + 4 + 5 = 15
+        for expect_sat in [true, false] {
+            run_const_case::<Fp, 1, 5>(1, expect_sat);
+            run_const_case::<Fr, 1, 5>(1, expect_sat);
+        }
+
+        render_layout("ap-sum-layout.png", 5, &ApSumCircuit::<1, 5>, 5);
+    }
+
+    #[test]
+    fn ap_sum_step_three_count_four_works() {
+        // 1 + 4 + 7 + 10 = 22
+        for expect_sat in [true, false] {
+            run_const_case::<Fp, 3, 4>(1, expect_sat);
+            run_const_case::<Fr, 3, 4>(1, expect_sat);
+        }
+    }
+
+    #[test]
+    fn ap_sum_large_count_matches_integer_sum() {
+        let sum = ap_sum(3, 7, 200).unwrap();
+        assert_eq!(
+            ap_sum_field(Fp::from(3), Fp::from(7), 200),
+            Fp::from_u128(sum)
+        );
+        for expect_sat in [true, false] {
+            run_const_case::<Fp, 7, 200>(3, expect_sat);
+            run_const_case::<Fr, 7, 200>(3, expect_sat);
+        }
+    }
+
+    /// Renders the layout of `circuit` to `path` with the `dev-graph` feature, and does
+    /// nothing without it, so tests never reference plotters directly.
+    fn render_layout<C: Circuit<Fp>>(path: &str, k: u32, circuit: &C, rows: usize) {
+        #[cfg(feature = "dev-graph")]
+        crate::render::render_to_file::<Fp, _>(path, k, circuit, rows).unwrap();
+        #[cfg(not(feature = "dev-graph"))]
+        let _ = (path, k, circuit, rows);
+    }
+
+    fn witness_circuit(terms: &[u64]) -> ApSumWitnessCircuit<Fp, 1, 5> {
+        ApSumWitnessCircuit {
+            terms: terms.iter().map(|t| Value::known(Fp::from(*t))).collect(),
+        }
+    }
+
+    #[test]
+    fn explicit_terms_of_an_ap_work() {
+        let k = 5;
+        let circuit = witness_circuit(&[1, 2, 3, 4, 5]);
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(1), Fp::from(15)]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn explicit_terms_breaking_the_step_are_rejected() {
+        let k = 5;
+        // same sum, but a_2 - a_1 != STEP
+        let circuit = witness_circuit(&[1, 2, 4, 3, 5]);
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(1), Fp::from(15)]]).unwrap();
+        let step_constraint: metadata::Constraint = ((0, "step and sum").into(), 1, "").into();
+        let failures = prover.verify().unwrap_err();
+        assert!(!failures.is_empty());
+        assert!(failures.iter().all(|failure| matches!(
+            failure,
+            VerifyFailure::ConstraintNotSatisfied { constraint, .. } if *constraint == step_constraint
+        )));
+    }
+
+    #[test]
+    fn explicit_terms_breaking_the_step_fail_on_the_broken_rows() {
+        // a_2 - a_1, a_3 - a_2 and a_4 - a_3 all differ from STEP
+        let failures = testing::analyze(
+            &witness_circuit(&[1, 2, 4, 3, 5]),
+            vec![Fp::from(1), Fp::from(15)],
+        );
+        let rows: Vec<_> = failures
+            .iter()
+            .filter_map(|failure| match failure {
+                VerifyFailure::ConstraintNotSatisfied {
+                    location: FailureLocation::InRegion { offset, .. },
+                    ..
+                } => Some(*offset),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(rows, [2, 3, 4]);
+        assert_eq!(failures.len(), rows.len());
+    }
+
+    #[test]
+    fn checkpoints_match_published_schedule() {
+        let k = 5;
+        // sum_2 = 1 + 2 + 3 = 6 and sum_5 = 1 + ... + 6 = 21, after 3 and 6 terms
+        let circuit = ApSumCheckpointsCircuit::<1, 10> {
+            checkpoints: vec![2, 5],
+        };
+        let instance = vec![Fp::from(1), Fp::from(55), Fp::from(6), Fp::from(21)];
+        let prover = MockProver::run(k, &circuit, vec![instance]).unwrap();
+        prover.assert_satisfied();
+
+        let instance = vec![Fp::from(1), Fp::from(55), Fp::from(6), Fp::from(20)];
+        let prover = MockProver::run(k, &circuit, vec![instance]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn prefix_of_a_long_progression_lays_out_only_its_rows() {
+        type Prefix = ApSumPrefixCircuit<1, 1000, 5>;
+        let k = Prefix::suggested_k();
+        assert_eq!(k, 4);
+        // 1 + 2 + 3 + 4 + 5 = 15
+        let instance = vec![Fp::from(1), Fp::from(15)];
+        let prover = MockProver::run(k, &Prefix::default(), vec![instance.clone()]).unwrap();
+        prover.assert_satisfied();
+        assert_eq!(
+            testing::advice_assignments(&Prefix::default(), instance),
+            [5, 5]
+        );
+    }
+
+    #[test]
+    fn large_table_partial_sums_match_integer_sums() {
+        let k = 7;
+        let partial_sums: Vec<_> = (1..=100)
+            .map(|count| Fp::from_u128(ap_sum(1, 1, count).unwrap()))
+            .collect();
+        let instance = instance::InstanceBuilder::new()
+            .first(Fp::from(1))
+            .partial_sums(&partial_sums)
+            .build();
+        let prover =
+            MockProver::run(k, &ApSumPartialSumsCircuit::<1, 100>, vec![instance]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    fn permutation_columns<C: Circuit<Fp>>() -> usize {
+        let mut meta = ConstraintSystem::<Fp>::default();
+        C::configure(&mut meta);
+        meta.permutation().get_columns().len()
+    }
+
+    #[test]
+    fn term_column_takes_part_in_copies_only_when_terms_are_copied() {
+        // sum and instance
+        assert_eq!(permutation_columns::<ApSumCircuit<1, 5>>(), 2);
+        assert_eq!(permutation_columns::<ApSumPartialSumsCircuit<1, 5>>(), 2);
+        // a, sum and instance
+        assert_eq!(permutation_columns::<ApSumLastTermCircuit<1, 5>>(), 3);
+        assert_eq!(
+            permutation_columns::<chain::ChainedApSumCircuit<1, 3, 2, 2>>(),
+            3
+        );
+    }
+
+    /// Two AP sums embedded in one parent, sharing its columns and instance.
+    #[derive(Default)]
+    struct SharedInstanceCircuit;
+
+    impl Circuit<Fp> for SharedInstanceCircuit {
+        type Config = (ApSumConfig, ApSumConfig);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let advice = [meta.advice_column(), meta.advice_column()];
+            let instance = meta.instance_column();
+            (
+                ApSumCircuit::<1, 5>::configure_with_columns(meta, advice, instance),
+                ApSumCircuit::<2, 3>::configure_with_columns(meta, advice, instance),
+            )
+        }
+
+        fn synthesize(
+            &self,
+            (first, second): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            ApSumCircuit::<1, 5>::synthesize_at(first, layouter.namespace(|| "first"), 0, 1)?;
+            ApSumCircuit::<2, 3>::synthesize_at(second, layouter.namespace(|| "second"), 2, 3)?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn two_chips_share_one_instance_column() {
+        let k = 5;
+        // 1 + 2 + 3 + 4 + 5 = 15, 2 + 4 + 6 = 12
+        let instance = vec![Fp::from(1), Fp::from(15), Fp::from(2), Fp::from(12)];
+        let prover = MockProver::run(k, &SharedInstanceCircuit, vec![instance]).unwrap();
+        prover.assert_satisfied();
+
+        let swapped = vec![Fp::from(1), Fp::from(12), Fp::from(2), Fp::from(15)];
+        let prover = MockProver::run(k, &SharedInstanceCircuit, vec![swapped]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,196 @@
+//! Instance column counts of the circuit variants.
+//!
+//! [`InstanceColumns::N_INSTANCE`] states how many instance columns a variant's
+//! `configure` allocates. The variants in the crate root keep every output in the one
+//! column described in [`crate::instance`]; [`SplitOutputApSumCircuit`] instead gives
+//! each output a column of its own, so verifiers can read an output without knowing the
+//! others' positions.
+//!
+//! Only [`SplitOutputApSumCircuit`] allocates its columns from the constant. For the
+//! root variants it is descriptive, and the tests check it against their `configure`.
+//! The other variants do not implement the trait; their module docs give their instance
+//! layout.
+//!
+//! Instance layout of [`SplitOutputApSumCircuit`]: `[[first], [sum], [last_term]]`.
+
+use crate::{
+    ApSumCheckpointsCircuit, ApSumChip, ApSumCircuit, ApSumConfig, ApSumLastTermCircuit,
+    ApSumPartialSumsCircuit,
+};
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*};
+
+pub trait InstanceColumns {
+    /// Number of instance columns the circuit's `configure` allocates.
+    const N_INSTANCE: usize;
+}
+
+impl<const STEP: u128, const COUNT: usize, const MAX_REGION_ROWS: usize> InstanceColumns
+    for ApSumCircuit<STEP, COUNT, MAX_REGION_ROWS>
+{
+    const N_INSTANCE: usize = 1;
+}
+
+impl<const STEP: u128, const COUNT: usize> InstanceColumns for ApSumLastTermCircuit<STEP, COUNT> {
+    const N_INSTANCE: usize = 1;
+}
+
+impl<const STEP: u128, const COUNT: usize> InstanceColumns
+    for ApSumPartialSumsCircuit<STEP, COUNT>
+{
+    const N_INSTANCE: usize = 1;
+}
+
+impl<const STEP: u128, const COUNT: usize> InstanceColumns
+    for ApSumCheckpointsCircuit<STEP, COUNT>
+{
+    const N_INSTANCE: usize = 1;
+}
+
+/// An output of [`SplitOutputApSumCircuit`], each in its own instance column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Output {
+    First,
+    Sum,
+    LastTerm,
+}
+
+impl Output {
+    /// Returns the `(column, row)` the output is exposed at.
+    pub fn position(self) -> (usize, usize) {
+        match self {
+            Output::First => (0, 0),
+            Output::Sum => (1, 0),
+            Output::LastTerm => (2, 0),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SplitOutputApSumConfig {
+    ap_sum: ApSumConfig,
+    instance: Vec<Column<Instance>>,
+}
+
+impl SplitOutputApSumConfig {
+    fn expose_public<F: FieldExt>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        output: Output,
+    ) -> Result<(), Error> {
+        let (column, row) = output.position();
+        layouter.constrain_instance(cell.cell(), self.instance[column], row)
+    }
+}
+
+/// Like [`ApSumLastTermCircuit`], but with the first term, sum and last term in separate
+/// instance columns.
+#[derive(Default)]
+pub struct SplitOutputApSumCircuit<const STEP: u128, const COUNT: usize>;
+
+impl<const STEP: u128, const COUNT: usize> InstanceColumns
+    for SplitOutputApSumCircuit<STEP, COUNT>
+{
+    const N_INSTANCE: usize = 3;
+}
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> Circuit<F>
+    for SplitOutputApSumCircuit<STEP, COUNT>
+{
+    type Config = SplitOutputApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance: Vec<_> = (0..Self::N_INSTANCE)
+            .map(|_| meta.instance_column())
+            .collect();
+        for column in &instance {
+            meta.enable_equality(*column);
+        }
+
+        // The table reads a_0 from row 0 of the first column.
+        let (first_column, _) = Output::First.position();
+        SplitOutputApSumConfig {
+            ap_sum: ApSumChip::<_, STEP, COUNT>::configure(meta, advice, instance[first_column]),
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = ApSumChip::<_, STEP, COUNT>::construct(config.ap_sum.clone());
+        let cells = chip.assign(layouter.namespace(|| "AP sum table"))?;
+        config.expose_public(
+            layouter.namespace(|| "sum"),
+            cells.sum.assigned(),
+            Output::Sum,
+        )?;
+        config.expose_public(
+            layouter.namespace(|| "last term"),
+            cells.last_term.assigned(),
+            Output::LastTerm,
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, halo2curves::pasta::Fp};
+
+    fn allocated<C: Circuit<Fp> + InstanceColumns>() -> usize {
+        let mut meta = ConstraintSystem::<Fp>::default();
+        C::configure(&mut meta);
+        meta.num_instance_columns()
+    }
+
+    #[test]
+    fn configure_allocates_n_instance_columns() {
+        assert_eq!(
+            allocated::<ApSumCircuit<1, 5>>(),
+            ApSumCircuit::<1, 5>::N_INSTANCE
+        );
+        assert_eq!(
+            allocated::<ApSumLastTermCircuit<1, 5>>(),
+            ApSumLastTermCircuit::<1, 5>::N_INSTANCE
+        );
+        assert_eq!(
+            allocated::<ApSumPartialSumsCircuit<1, 5>>(),
+            ApSumPartialSumsCircuit::<1, 5>::N_INSTANCE
+        );
+        assert_eq!(
+            allocated::<ApSumCheckpointsCircuit<1, 5>>(),
+            ApSumCheckpointsCircuit::<1, 5>::N_INSTANCE
+        );
+        assert_eq!(allocated::<SplitOutputApSumCircuit<1, 5>>(), 3);
+        assert_eq!(SplitOutputApSumCircuit::<1, 5>::N_INSTANCE, 3);
+    }
+
+    #[test]
+    fn split_outputs_are_constrained_in_their_columns() {
+        let k = 4;
+        let circuit = SplitOutputApSumCircuit::<1, 5>;
+        // 1 + 2 + 3 + 4 + 5 = 15, last term 5
+        let instance = vec![vec![Fp::from(1)], vec![Fp::from(15)], vec![Fp::from(5)]];
+        let prover = MockProver::run(k, &circuit, instance).unwrap();
+        prover.assert_satisfied();
+
+        // The outputs in the single-column order are rejected.
+        let instance = vec![vec![Fp::from(1), Fp::from(15), Fp::from(5)], vec![], vec![]];
+        let prover = MockProver::run(k, &circuit, instance).unwrap();
+        assert!(prover.verify().is_err());
+
+        let instance = vec![vec![Fp::from(1)], vec![Fp::from(5)], vec![Fp::from(15)]];
+        let prover = MockProver::run(k, &circuit, instance).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
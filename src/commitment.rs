@@ -0,0 +1,156 @@
+//! Proof that the AP sum opens a public Poseidon commitment.
+//!
+//! The commitment to a value `v` with blinding factor `r` is `Poseidon(v, r)`. The circuit
+//! computes the sum as [`crate::ApSumCircuit`] does, hashes it together with the witnessed
+//! blinding factor and constrains the digest to the instance, so the sum itself stays
+//! private.
+//!
+//! Instance layout: `[first, commitment]`.
+
+use crate::{ApSumChip, ApSumConfig};
+use halo2_gadgets::poseidon::{
+    primitives::{self as poseidon, ConstantLength, P128Pow5T3},
+    Hash, Pow5Chip, Pow5Config,
+};
+use halo2_proofs::{circuit::*, halo2curves::pasta::Fp, plonk::*};
+
+const WIDTH: usize = 3;
+const RATE: usize = 2;
+
+/// Computes `Poseidon(left, right)` off-circuit.
+#[must_use]
+pub fn hash_two(left: Fp, right: Fp) -> Fp {
+    poseidon::Hash::<_, P128Pow5T3, ConstantLength<2>, WIDTH, RATE>::init().hash([left, right])
+}
+
+/// Computes the commitment `Poseidon(value, blinding)` off-circuit.
+#[must_use]
+pub fn commit(value: Fp, blinding: Fp) -> Fp {
+    hash_two(value, blinding)
+}
+
+#[derive(Clone, Debug)]
+pub struct CommittedApSumConfig {
+    ap_sum: ApSumConfig,
+    poseidon: Pow5Config<Fp, WIDTH, RATE>,
+}
+
+/// Configures a Poseidon chip over fresh columns.
+pub(crate) fn configure_poseidon(meta: &mut ConstraintSystem<Fp>) -> Pow5Config<Fp, WIDTH, RATE> {
+    let state = [(); WIDTH].map(|_| meta.advice_column());
+    let partial_sbox = meta.advice_column();
+    let rc_a = [(); WIDTH].map(|_| meta.fixed_column());
+    let rc_b = [(); WIDTH].map(|_| meta.fixed_column());
+    meta.enable_constant(rc_b[0]);
+
+    Pow5Chip::configure::<P128Pow5T3>(meta, state, partial_sbox, rc_a, rc_b)
+}
+
+/// Hashes `value` with a witnessed `blinding` factor, returning the commitment cell.
+///
+/// `column` holds the blinding witness and must have equality enabled.
+pub(crate) fn assign_commitment(
+    config: &Pow5Config<Fp, WIDTH, RATE>,
+    mut layouter: impl Layouter<Fp>,
+    column: Column<Advice>,
+    value: &AssignedCell<Fp, Fp>,
+    blinding: Value<Fp>,
+) -> Result<AssignedCell<Fp, Fp>, Error> {
+    let blinding = layouter.assign_region(
+        || "blinding",
+        |mut region| region.assign_advice(|| "blinding", column, 0, || blinding),
+    )?;
+
+    assign_hash_two(
+        config,
+        layouter.namespace(|| "commit"),
+        [value.clone(), blinding],
+    )
+}
+
+/// Hashes two assigned cells in-circuit, returning the digest cell.
+pub(crate) fn assign_hash_two(
+    config: &Pow5Config<Fp, WIDTH, RATE>,
+    mut layouter: impl Layouter<Fp>,
+    message: [AssignedCell<Fp, Fp>; 2],
+) -> Result<AssignedCell<Fp, Fp>, Error> {
+    let chip = Pow5Chip::construct(config.clone());
+    let hasher = Hash::<_, _, P128Pow5T3, ConstantLength<2>, WIDTH, RATE>::init(
+        chip,
+        layouter.namespace(|| "init"),
+    )?;
+    hasher.hash(layouter.namespace(|| "hash"), message)
+}
+
+#[derive(Default)]
+pub struct CommittedApSumCircuit<const STEP: u128, const COUNT: usize> {
+    pub blinding: Value<Fp>,
+}
+
+impl<const STEP: u128, const COUNT: usize> Circuit<Fp> for CommittedApSumCircuit<STEP, COUNT> {
+    type Config = CommittedApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+
+        CommittedApSumConfig {
+            ap_sum: ApSumChip::<_, STEP, COUNT>::configure(meta, advice, instance),
+            poseidon: configure_poseidon(meta),
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fp>,
+    ) -> Result<(), Error> {
+        let chip = ApSumChip::<_, STEP, COUNT>::construct(config.ap_sum.clone());
+        let cells = chip.assign(layouter.namespace(|| "AP sum table"))?;
+        let commitment = assign_commitment(
+            &config.poseidon,
+            layouter.namespace(|| "commitment"),
+            config.ap_sum.advice[0],
+            cells.sum.assigned(),
+            self.blinding,
+        )?;
+        chip.expose_public(layouter.namespace(|| "output"), &commitment, 1)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::dev::MockProver;
+
+    #[test]
+    fn sum_opens_commitment() {
+        let k = 7;
+        let blinding = Fp::from(0xdead_beef);
+        // 1 + 2 + 3 + 4 + 5 = 15
+        let commitment = commit(Fp::from(15), blinding);
+        let circuit = CommittedApSumCircuit::<1, 5> {
+            blinding: Value::known(blinding),
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(1), commitment]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn commitment_to_other_value_is_rejected() {
+        let k = 7;
+        let blinding = Fp::from(0xdead_beef);
+        let commitment = commit(Fp::from(16), blinding);
+        let circuit = CommittedApSumCircuit::<1, 5> {
+            blinding: Value::known(blinding),
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(1), commitment]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
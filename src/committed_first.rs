@@ -0,0 +1,106 @@
+//! AP sum starting from the opening of a public Poseidon commitment.
+//!
+//! The first term `a_0` is a witness. The circuit hashes it with a witnessed blinding
+//! factor, constrains the digest to the public commitment and then computes the sum from
+//! the same cell, proving "the AP starting at the committed value sums to `S`" without
+//! revealing the first term.
+//!
+//! Instance layout: `[commitment, sum]`.
+
+use crate::{
+    commitment::{assign_commitment, configure_poseidon},
+    ApSumChip, ApSumConfig,
+};
+use halo2_gadgets::poseidon::Pow5Config;
+use halo2_proofs::{circuit::*, halo2curves::pasta::Fp, plonk::*};
+
+#[derive(Clone, Debug)]
+pub struct CommittedFirstApSumConfig {
+    ap_sum: ApSumConfig,
+    poseidon: Pow5Config<Fp, 3, 2>,
+}
+
+#[derive(Default)]
+pub struct CommittedFirstApSumCircuit<const STEP: u128, const COUNT: usize> {
+    /// The committed first term.
+    pub first: Value<Fp>,
+    pub blinding: Value<Fp>,
+}
+
+impl<const STEP: u128, const COUNT: usize> Circuit<Fp> for CommittedFirstApSumCircuit<STEP, COUNT> {
+    type Config = CommittedFirstApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+
+        CommittedFirstApSumConfig {
+            ap_sum: ApSumChip::<_, STEP, COUNT>::configure_sum_only(meta, advice, instance),
+            poseidon: configure_poseidon(meta),
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fp>,
+    ) -> Result<(), Error> {
+        // The sum column has equality enabled, so the witnesses can be copied from it.
+        let column = config.ap_sum.advice[1];
+        let first = layouter.assign_region(
+            || "first term",
+            |mut region| region.assign_advice(|| "first", column, 0, || self.first),
+        )?;
+        let commitment = assign_commitment(
+            &config.poseidon,
+            layouter.namespace(|| "commitment"),
+            column,
+            &first,
+            self.blinding,
+        )?;
+
+        let chip = ApSumChip::<_, STEP, COUNT>::construct(config.ap_sum);
+        chip.expose_public(layouter.namespace(|| "public commitment"), &commitment, 0)?;
+        let cells = chip.assign_from(layouter.namespace(|| "AP sum table"), &first)?;
+        chip.expose_public(layouter.namespace(|| "output"), cells.sum.assigned(), 1)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commitment::commit;
+    use halo2_proofs::dev::MockProver;
+
+    fn run(first: u64, commitment: Fp, sum: u64) -> bool {
+        let blinding = Fp::from(0x5eed);
+        let circuit = CommittedFirstApSumCircuit::<1, 5> {
+            first: Value::known(Fp::from(first)),
+            blinding: Value::known(blinding),
+        };
+        MockProver::run(7, &circuit, vec![vec![commitment, Fp::from(sum)]])
+            .unwrap()
+            .verify()
+            .is_ok()
+    }
+
+    #[test]
+    fn sum_from_committed_first_term() {
+        let commitment = commit(Fp::from(1), Fp::from(0x5eed));
+        // 1 + 2 + 3 + 4 + 5 = 15
+        assert!(run(1, commitment, 15));
+    }
+
+    #[test]
+    fn first_term_other_than_the_opening_is_rejected() {
+        let commitment = commit(Fp::from(1), Fp::from(0x5eed));
+        // 2 + 3 + 4 + 5 + 6 = 20 is the right sum for a first term the commitment does not open to
+        assert!(!run(2, commitment, 20));
+    }
+}
@@ -0,0 +1,197 @@
+//! Sum of the cubes of an arithmetic progression.
+//!
+//! Each row holds the term `a_n`, its square and the running cube sum. The cube is
+//! built from the square column so every gate stays at degree 3 including the selector:
+//! `square == a * a` and `cube_sum == prev_cube_sum + square * a`. For terms `1, 2, 3`
+//! the circuit computes `1 + 8 + 27 = 36`.
+//!
+//! Instance layout: `[first, cube_sum]`.
+
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+use std::marker::PhantomData;
+
+#[derive(Clone, Debug)]
+pub struct CubeSumConfig {
+    // [a_n, square_n, cube_sum_n]
+    advice: [Column<Advice>; 3],
+    s_first: Selector,
+    s_next: Selector,
+    instance: Column<Instance>,
+}
+
+pub struct CubeSumChip<F, const STEP: u128, const COUNT: usize> {
+    config: CubeSumConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> CubeSumChip<F, STEP, COUNT> {
+    pub fn construct(config: CubeSumConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 3],
+        instance: Column<Instance>,
+    ) -> CubeSumConfig {
+        let s_first = meta.selector();
+        let s_next = meta.selector();
+
+        meta.enable_equality(advice[0]);
+        meta.enable_equality(advice[2]);
+        meta.enable_equality(instance);
+
+        // | advice[0] | advice[1] |  advice[2]  | s_first | s_next
+        // -----------------------------------------------------------
+        // |    a_0    |   a_0^2   |    a_0^3    |    s    |
+        // |    a_1    |   a_1^2   | cube_sum_1  |         |   s
+        // |    ...    |    ...    |     ...     |         |   s
+        meta.create_gate("first cube", |meta| {
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let square = meta.query_advice(advice[1], Rotation::cur());
+            let cube_sum = meta.query_advice(advice[2], Rotation::cur());
+            let s = meta.query_selector(s_first);
+            vec![
+                s.clone() * (a.clone() * a.clone() - square.clone()),
+                s * (square * a - cube_sum),
+            ]
+        });
+
+        meta.create_gate("step and cube sum", |meta| {
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let square = meta.query_advice(advice[1], Rotation::cur());
+            let cube_sum = meta.query_advice(advice[2], Rotation::cur());
+            let prev_a = meta.query_advice(advice[0], Rotation::prev());
+            let prev_cube_sum = meta.query_advice(advice[2], Rotation::prev());
+            let s = meta.query_selector(s_next);
+            vec![
+                // a == prev_a + STEP
+                s.clone() * (a.clone() - prev_a - Expression::Constant(F::from_u128(STEP))),
+                // square == a * a
+                s.clone() * (a.clone() * a.clone() - square.clone()),
+                // cube_sum == prev_cube_sum + square * a
+                s * (prev_cube_sum + square * a - cube_sum),
+            ]
+        });
+
+        CubeSumConfig {
+            advice,
+            s_first,
+            s_next,
+            instance,
+        }
+    }
+
+    /// Assigns the table, returning the cube-sum cell.
+    pub fn assign(&self, mut layouter: impl Layouter<F>) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "cube sum table",
+            |mut region| {
+                let [a_column, square_column, cube_sum_column] = self.config.advice;
+                let step = F::from_u128(STEP);
+
+                let mut a_cell = region.assign_advice_from_instance(
+                    || "a",
+                    self.config.instance,
+                    0,
+                    a_column,
+                    0,
+                )?;
+                let square = a_cell.value().map(|a| a.square());
+                region.assign_advice(|| "square", square_column, 0, || square)?;
+                let mut cube_sum_cell = region.assign_advice(
+                    || "cube_sum",
+                    cube_sum_column,
+                    0,
+                    || square * a_cell.value().copied(),
+                )?;
+                self.config.s_first.enable(&mut region, 0)?;
+
+                for row in 1..COUNT {
+                    self.config.s_next.enable(&mut region, row)?;
+
+                    let new_a_val = a_cell.value().map(|a| *a + step);
+                    a_cell = region.assign_advice(|| "a", a_column, row, || new_a_val)?;
+
+                    let square = new_a_val.map(|a| a.square());
+                    region.assign_advice(|| "square", square_column, row, || square)?;
+
+                    let new_cube_sum = cube_sum_cell.value().copied() + square * new_a_val;
+                    cube_sum_cell = region.assign_advice(
+                        || "cube_sum",
+                        cube_sum_column,
+                        row,
+                        || new_cube_sum,
+                    )?;
+                }
+
+                Ok(cube_sum_cell)
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+#[derive(Default)]
+pub struct CubeSumCircuit<const STEP: u128, const COUNT: usize>;
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> Circuit<F> for CubeSumCircuit<STEP, COUNT> {
+    type Config = CubeSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [(); 3].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+
+        CubeSumChip::<_, STEP, COUNT>::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = CubeSumChip::<_, STEP, COUNT>::construct(config);
+        let cube_sum = chip.assign(layouter.namespace(|| "cube sum table"))?;
+        chip.expose_public(layouter.namespace(|| "output"), &cube_sum, 1)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, halo2curves::pasta::Fp};
+
+    #[test]
+    fn step_one_count_three_cube_sum_is_36() {
+        let k = 4;
+        // 1 + 8 + 27 = 36
+        let circuit = CubeSumCircuit::<1, 3>;
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(1), Fp::from(36)]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn plain_sum_is_rejected() {
+        let k = 4;
+        let circuit = CubeSumCircuit::<1, 3>;
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(1), Fp::from(6)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
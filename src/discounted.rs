@@ -0,0 +1,232 @@
+//! Discounted sum `sum_n a_n * d^n` of an arithmetic progression.
+//!
+//! The discount factor `d` is a public field element, so a fraction such as `1/2` is
+//! represented by its modular inverse. A discount column starts at `1` and is multiplied
+//! by `d` on every row, and each term is weighted by it. For terms `1, 2, 3` and `d = 1/2`
+//! the circuit computes `1 + 1 + 3/4`.
+//!
+//! Instance layout: `[first, d, sum]`.
+
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+use std::marker::PhantomData;
+
+#[derive(Clone, Debug)]
+pub struct DiscountedApSumConfig {
+    // [a_n, sum_n, discount_n, d]
+    advice: [Column<Advice>; 4],
+    s_first: Selector,
+    s_next: Selector,
+    instance: Column<Instance>,
+}
+
+struct DiscountedApSumChip<F, const STEP: u128, const COUNT: usize> {
+    config: DiscountedApSumConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> DiscountedApSumChip<F, STEP, COUNT> {
+    fn construct(config: DiscountedApSumConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 4],
+        instance: Column<Instance>,
+    ) -> DiscountedApSumConfig {
+        let s_first = meta.selector();
+        let s_next = meta.selector();
+
+        meta.enable_equality(advice[0]);
+        meta.enable_equality(advice[1]);
+        meta.enable_equality(advice[3]);
+        meta.enable_equality(instance);
+
+        // | advice[0] | advice[1] |  advice[2]   | advice[3] | s_first | s_next
+        // ----------------------------------------------------------------------
+        // |    a_0    |   sum_0   |      1       |     d     |    s    |
+        // |    a_1    |   sum_1   |      d       |     d     |         |   s
+        // |    a_2    |   sum_2   |     d^2      |     d     |         |   s
+        // |    ...    |    ...    |     ...      |    ...    |         |   s
+        meta.create_gate("first discount", |meta| {
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let sum = meta.query_advice(advice[1], Rotation::cur());
+            let discount = meta.query_advice(advice[2], Rotation::cur());
+            let s = meta.query_selector(s_first);
+            vec![
+                s.clone() * (discount - Expression::Constant(F::one())),
+                s * (a - sum),
+            ]
+        });
+
+        meta.create_gate("step and discounted sum", |meta| {
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let sum = meta.query_advice(advice[1], Rotation::cur());
+            let discount = meta.query_advice(advice[2], Rotation::cur());
+            let d = meta.query_advice(advice[3], Rotation::cur());
+            let prev_a = meta.query_advice(advice[0], Rotation::prev());
+            let prev_sum = meta.query_advice(advice[1], Rotation::prev());
+            let prev_discount = meta.query_advice(advice[2], Rotation::prev());
+            let prev_d = meta.query_advice(advice[3], Rotation::prev());
+            let s = meta.query_selector(s_next);
+            vec![
+                // a == prev_a + STEP
+                s.clone() * (a.clone() - prev_a - Expression::Constant(F::from_u128(STEP))),
+                // d is the same on every row
+                s.clone() * (d.clone() - prev_d),
+                // discount == prev_discount * d
+                s.clone() * (prev_discount * d - discount.clone()),
+                // sum == prev_sum + a * discount
+                s * (prev_sum + a * discount - sum),
+            ]
+        });
+
+        DiscountedApSumConfig {
+            advice,
+            s_first,
+            s_next,
+            instance,
+        }
+    }
+
+    fn assign(&self, mut layouter: impl Layouter<F>) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "discounted AP sum table",
+            |mut region| {
+                let [a_column, sum_column, discount_column, d_column] = self.config.advice;
+                let step = F::from_u128(STEP);
+
+                self.config.s_first.enable(&mut region, 0)?;
+                let mut a_cell = region.assign_advice_from_instance(
+                    || "a",
+                    self.config.instance,
+                    0,
+                    a_column,
+                    0,
+                )?;
+                let mut sum_cell = region.assign_advice_from_instance(
+                    || "sum",
+                    self.config.instance,
+                    0,
+                    sum_column,
+                    0,
+                )?;
+                let mut discount_cell = region.assign_advice(
+                    || "discount",
+                    discount_column,
+                    0,
+                    || Value::known(F::one()),
+                )?;
+                let d_cell = region.assign_advice_from_instance(
+                    || "d",
+                    self.config.instance,
+                    1,
+                    d_column,
+                    0,
+                )?;
+                let d = d_cell.value().copied();
+
+                for row in 1..COUNT {
+                    self.config.s_next.enable(&mut region, row)?;
+
+                    let new_a_val = a_cell.value().map(|a| *a + step);
+                    a_cell = region.assign_advice(|| "a", a_column, row, || new_a_val)?;
+
+                    region.assign_advice(|| "d", d_column, row, || d)?;
+                    let discount = discount_cell.value().copied() * d;
+                    discount_cell =
+                        region.assign_advice(|| "discount", discount_column, row, || discount)?;
+
+                    let new_sum = sum_cell.value().copied() + new_a_val * discount;
+                    sum_cell = region.assign_advice(|| "sum", sum_column, row, || new_sum)?;
+                }
+
+                Ok(sum_cell)
+            },
+        )
+    }
+
+    fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+#[derive(Default)]
+pub struct DiscountedApSumCircuit<const STEP: u128, const COUNT: usize>;
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> Circuit<F>
+    for DiscountedApSumCircuit<STEP, COUNT>
+{
+    type Config = DiscountedApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [(); 4].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+
+        DiscountedApSumChip::<_, STEP, COUNT>::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = DiscountedApSumChip::<_, STEP, COUNT>::construct(config);
+        let sum_cell = chip.assign(layouter.namespace(|| "discounted AP sum table"))?;
+        chip.expose_public(layouter.namespace(|| "output"), &sum_cell, 2)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{
+        dev::{MockProver, VerifyFailure},
+        halo2curves::pasta::Fp,
+    };
+
+    fn inverse<F: FieldExt>(x: F) -> F {
+        x.invert().unwrap()
+    }
+
+    fn run(d: Fp, sum: Fp) -> Result<(), Vec<VerifyFailure>> {
+        let k = 4;
+        MockProver::run(
+            k,
+            &DiscountedApSumCircuit::<1, 3>,
+            vec![vec![Fp::from(1), d, sum]],
+        )
+        .unwrap()
+        .verify()
+    }
+
+    #[test]
+    fn half_discount_of_one_two_three() {
+        let half = inverse(Fp::from(2));
+        // 1 + 2/2 + 3/4 = 11/4
+        let sum = Fp::from(11) * inverse(Fp::from(4));
+        assert_eq!(run(half, sum), Ok(()));
+    }
+
+    #[test]
+    fn undiscounted_sum_is_rejected() {
+        let half = inverse(Fp::from(2));
+        // 1 + 2 + 3 would only hold for d = 1
+        assert!(run(half, Fp::from(6)).is_err());
+        assert_eq!(run(Fp::one(), Fp::from(6)), Ok(()));
+    }
+}
@@ -0,0 +1,125 @@
+//! Decimal formatting of field elements for display.
+//!
+//! Field elements print as hex of their canonical representation, which is unreadable
+//! for sums shown to users. [`field_to_decimal_string`] formats the integer `0..p` an
+//! element represents in base 10, whether or not it fits in a `u128`. Like
+//! [`crate::bytes`], it assumes the little-endian representation of the Pasta fields.
+
+use halo2_proofs::{arithmetic::FieldExt, circuit::AssignedCell};
+use std::fmt::Write;
+
+/// Largest power of ten that fits in a `u32` limb, so each division step stays in `u64`.
+const DECIMAL_BASE: u64 = 1_000_000_000;
+
+/// Formats `v` as the decimal integer it represents.
+#[must_use]
+pub fn field_to_decimal_string<F: FieldExt>(v: &F) -> String {
+    // Little-endian 32-bit limbs of the canonical integer.
+    let mut limbs: Vec<u32> = v
+        .to_repr()
+        .as_ref()
+        .chunks(4)
+        .map(|chunk| {
+            let mut bytes = [0; 4];
+            bytes[..chunk.len()].copy_from_slice(chunk);
+            u32::from_le_bytes(bytes)
+        })
+        .collect();
+
+    // Divide by 10^9 until nothing is left, collecting the remainders least significant
+    // first.
+    let mut groups = vec![];
+    while limbs.iter().any(|&limb| limb != 0) {
+        let mut remainder = 0;
+        for limb in limbs.iter_mut().rev() {
+            let acc = (remainder << 32) | u64::from(*limb);
+            *limb = (acc / DECIMAL_BASE) as u32;
+            remainder = acc % DECIMAL_BASE;
+        }
+        groups.push(remainder);
+    }
+
+    let Some((most, rest)) = groups.split_last() else {
+        return "0".to_string();
+    };
+    let mut out = most.to_string();
+    for group in rest.iter().rev() {
+        let _ = write!(out, "{group:09}");
+    }
+    out
+}
+
+/// Formats the value of `cell` as [`field_to_decimal_string`] does.
+///
+/// Returns `None` while the witness is unknown, as during key generation.
+#[must_use]
+pub fn assigned_to_decimal_string<F: FieldExt>(cell: &AssignedCell<F, F>) -> Option<String> {
+    let mut out = None;
+    cell.value().map(|v| out = Some(field_to_decimal_string(v)));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ApSumCircuit, ApSumConfig};
+    use halo2_proofs::{circuit::*, dev::MockProver, halo2curves::pasta::Fp, plonk::*};
+    use std::cell::RefCell;
+
+    /// Synthesizes `ApSumCircuit<1, 5>` and records its formatted sum.
+    #[derive(Default)]
+    struct FormattedSumCircuit {
+        sum: RefCell<Option<String>>,
+    }
+
+    impl Circuit<Fp> for FormattedSumCircuit {
+        type Config = ApSumConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let advice = [meta.advice_column(), meta.advice_column()];
+            let instance = meta.instance_column();
+            ApSumCircuit::<1, 5>::configure_with_columns(meta, advice, instance)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let sum = ApSumCircuit::<1, 5>::synthesize_at(config, layouter, 0, 1)?;
+            *self.sum.borrow_mut() = assigned_to_decimal_string(sum.assigned());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn sum_of_step_one_count_five_formats_as_fifteen() {
+        let circuit = FormattedSumCircuit::default();
+        let prover = MockProver::run(4, &circuit, vec![vec![Fp::from(1), Fp::from(15)]]).unwrap();
+        prover.assert_satisfied();
+        assert_eq!(circuit.sum.into_inner().as_deref(), Some("15"));
+    }
+
+    #[test]
+    fn values_beyond_u128_format_in_full() {
+        assert_eq!(field_to_decimal_string(&Fp::zero()), "0");
+        assert_eq!(
+            field_to_decimal_string(&Fp::from_u128(u128::MAX)),
+            u128::MAX.to_string()
+        );
+        // 2^128, with inner zero groups padded
+        assert_eq!(
+            field_to_decimal_string(&(Fp::from_u128(u128::MAX) + Fp::one())),
+            "340282366920938463463374607431768211456"
+        );
+        assert_eq!(
+            field_to_decimal_string(&Fp::from(1_000_000_000)),
+            "1000000000"
+        );
+    }
+}
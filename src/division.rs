@@ -0,0 +1,152 @@
+//! Euclidean division of the AP sum by its count, for an integer average.
+//!
+//! The circuit computes the sum as [`crate::ApSumCircuit`] does and exposes `q` and `r`
+//! with `sum == q * COUNT + r` and `r < COUNT`. `q`, `r` and `COUNT - 1 - r` are all
+//! range-checked below `2^RANGE_BITS`, so the equation cannot wrap around the modulus and
+//! `(q, r)` is the unique integer quotient and remainder. `COUNT` must be at most
+//! `2^RANGE_BITS`, and the sum below `2^RANGE_BITS * COUNT`.
+//!
+//! The witnesses are derived from the sum's canonical representation, assumed
+//! little-endian as for the Pasta fields.
+//!
+//! Instance layout: `[first, quotient, remainder]`.
+
+use crate::{range::RangeCheckConfig, ApSumChip, ApSumConfig};
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+
+/// Bits of the range checks on the quotient and remainder.
+pub const RANGE_BITS: usize = 8;
+
+#[derive(Clone, Debug)]
+pub struct DivisionApSumConfig {
+    ap_sum: ApSumConfig,
+    // [sum, value]
+    division: [Column<Advice>; 2],
+    s_division: Selector,
+    range: RangeCheckConfig<RANGE_BITS>,
+}
+
+/// Reads the low 128 bits of `value`'s canonical integer.
+fn low_u128<F: FieldExt>(value: &F) -> u128 {
+    let mut bytes = [0; 16];
+    bytes.copy_from_slice(&value.to_repr().as_ref()[..16]);
+    u128::from_le_bytes(bytes)
+}
+
+#[derive(Default)]
+pub struct DivisionApSumCircuit<const STEP: u128, const COUNT: usize>;
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> Circuit<F>
+    for DivisionApSumCircuit<STEP, COUNT>
+{
+    type Config = DivisionApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let ap_sum = ApSumChip::<_, STEP, COUNT>::configure_sum_only(meta, advice, instance);
+
+        // The sum is copied back into its own column, in the division region.
+        let division = [advice[1], meta.advice_column()];
+        meta.enable_equality(division[1]);
+        let s_division = meta.selector();
+        let range = RangeCheckConfig::configure(meta, division[1]);
+
+        // |  sum  | value | s_division
+        // -----------------------------
+        // |   s   |   q   |     s        s == q * COUNT + r
+        // |       |   r   |              d == COUNT - 1 - r
+        // |       |   d   |
+        // Every value row is range-checked.
+        meta.create_gate("euclidean division", |meta| {
+            let sum = meta.query_advice(division[0], Rotation::cur());
+            let q = meta.query_advice(division[1], Rotation::cur());
+            let r = meta.query_advice(division[1], Rotation::next());
+            let d = meta.query_advice(division[1], Rotation(2));
+            let s = meta.query_selector(s_division);
+            let count = Expression::Constant(F::from(COUNT as u64));
+            vec![
+                s.clone() * (q * count.clone() + r.clone() - sum),
+                s * (count - Expression::Constant(F::one()) - r - d),
+            ]
+        });
+
+        DivisionApSumConfig {
+            ap_sum,
+            division,
+            s_division,
+            range,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        if COUNT == 0 {
+            return Err(Error::Synthesis);
+        }
+
+        config
+            .range
+            .load_table(layouter.namespace(|| "range table"))?;
+        let chip = ApSumChip::<_, STEP, COUNT>::construct(config.ap_sum.clone());
+        let cells = chip.assign(layouter.namespace(|| "AP sum table"))?;
+        let (q, r) = layouter.assign_region(
+            || "euclidean division",
+            |mut region| {
+                config.s_division.enable(&mut region, 0)?;
+                let sum = cells
+                    .sum
+                    .copy_advice(|| "sum", &mut region, config.division[0], 0)?;
+
+                let count = COUNT as u128;
+                let sum = sum.value().map(low_u128);
+                let q = sum.map(|sum| F::from_u128(sum / count));
+                let r = sum.map(|sum| F::from_u128(sum % count));
+                let d = r.map(|r| F::from(COUNT as u64 - 1) - r);
+                let q = config.range.assign(&mut region, 0, q)?;
+                let r = config.range.assign(&mut region, 1, r)?;
+                config.range.assign(&mut region, 2, d)?;
+                Ok((q, r))
+            },
+        )?;
+        chip.expose_public(layouter.namespace(|| "quotient"), &q, 1)?;
+        chip.expose_public(layouter.namespace(|| "remainder"), &r, 2)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, halo2curves::pasta::Fp};
+
+    fn run(quotient: u64, remainder: u64) -> bool {
+        let k = 9;
+        let instance = vec![Fp::from(1), Fp::from(quotient), Fp::from(remainder)];
+        MockProver::run(k, &DivisionApSumCircuit::<1, 4>, vec![instance])
+            .unwrap()
+            .verify()
+            .is_ok()
+    }
+
+    #[test]
+    fn sum_ten_over_count_four_is_two_remainder_two() {
+        // 1 + 2 + 3 + 4 = 10 = 2 * 4 + 2
+        assert!(run(2, 2));
+    }
+
+    #[test]
+    fn remainder_not_below_count_is_rejected() {
+        // 10 = 1 * 4 + 6 holds, but 6 >= 4
+        assert!(!run(1, 6));
+        assert!(!run(0, 10));
+    }
+}
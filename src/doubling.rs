@@ -0,0 +1,193 @@
+//! Sum of a progression whose step is itself geometric.
+//!
+//! A `step` column starts at `INITIAL_STEP` and is multiplied by `RATIO` on every row,
+//! while each term adds the previous row's step: `a_n == a_{n-1} + step_{n-1}` and
+//! `step_n == step_{n-1} * RATIO`. For first term `1`, `INITIAL_STEP = 1`, `RATIO = 2`
+//! and count `5`, the terms are `1, 2, 4, 8, 16` and the circuit computes `31`.
+//!
+//! Instance layout: `[first, sum]`.
+
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+use std::marker::PhantomData;
+
+#[derive(Clone, Debug)]
+pub struct DoublingApSumConfig {
+    // [a_n, sum_n, step_n]
+    advice: [Column<Advice>; 3],
+    s_first: Selector,
+    s_next: Selector,
+    instance: Column<Instance>,
+}
+
+struct DoublingApSumChip<F, const INITIAL_STEP: u128, const RATIO: u128, const COUNT: usize> {
+    config: DoublingApSumConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const INITIAL_STEP: u128, const RATIO: u128, const COUNT: usize>
+    DoublingApSumChip<F, INITIAL_STEP, RATIO, COUNT>
+{
+    fn construct(config: DoublingApSumConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 3],
+        instance: Column<Instance>,
+    ) -> DoublingApSumConfig {
+        let s_first = meta.selector();
+        let s_next = meta.selector();
+
+        meta.enable_equality(advice[0]);
+        meta.enable_equality(advice[1]);
+        meta.enable_equality(instance);
+
+        // | advice[0] | advice[1] | advice[2] | s_first | s_next
+        // ---------------------------------------------------------
+        // |    a_0    |   sum_0   |  step_0   |    s    |
+        // |    a_1    |   sum_1   |  step_1   |         |   s
+        // |    ...    |    ...    |    ...    |         |   s
+        meta.create_gate("initial step", |meta| {
+            let step = meta.query_advice(advice[2], Rotation::cur());
+            let s = meta.query_selector(s_first);
+            vec![s * (step - Expression::Constant(F::from_u128(INITIAL_STEP)))]
+        });
+
+        meta.create_gate("geometric step and sum", |meta| {
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let sum = meta.query_advice(advice[1], Rotation::cur());
+            let step = meta.query_advice(advice[2], Rotation::cur());
+            let prev_a = meta.query_advice(advice[0], Rotation::prev());
+            let prev_sum = meta.query_advice(advice[1], Rotation::prev());
+            let prev_step = meta.query_advice(advice[2], Rotation::prev());
+            let s = meta.query_selector(s_next);
+            vec![
+                // sum == a + prev_sum
+                s.clone() * (a.clone() + prev_sum - sum),
+                // a == prev_a + prev_step
+                s.clone() * (a - prev_a - prev_step.clone()),
+                // step == prev_step * RATIO
+                s * (prev_step * Expression::Constant(F::from_u128(RATIO)) - step),
+            ]
+        });
+
+        DoublingApSumConfig {
+            advice,
+            s_first,
+            s_next,
+            instance,
+        }
+    }
+
+    fn assign(&self, mut layouter: impl Layouter<F>) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "doubling AP sum table",
+            |mut region| {
+                let [a_column, sum_column, step_column] = self.config.advice;
+                let ratio = F::from_u128(RATIO);
+
+                let mut a_cell = region.assign_advice_from_instance(
+                    || "a",
+                    self.config.instance,
+                    0,
+                    a_column,
+                    0,
+                )?;
+                let mut sum_cell = region.assign_advice_from_instance(
+                    || "sum",
+                    self.config.instance,
+                    0,
+                    sum_column,
+                    0,
+                )?;
+                let mut step = Value::known(F::from_u128(INITIAL_STEP));
+                region.assign_advice(|| "step", step_column, 0, || step)?;
+                self.config.s_first.enable(&mut region, 0)?;
+
+                for row in 1..COUNT {
+                    self.config.s_next.enable(&mut region, row)?;
+
+                    let new_a_val = a_cell.value().copied() + step;
+                    a_cell = region.assign_advice(|| "a", a_column, row, || new_a_val)?;
+
+                    let new_sum = sum_cell.value().copied() + new_a_val;
+                    sum_cell = region.assign_advice(|| "sum", sum_column, row, || new_sum)?;
+
+                    step = step.map(|step| step * ratio);
+                    region.assign_advice(|| "step", step_column, row, || step)?;
+                }
+
+                Ok(sum_cell)
+            },
+        )
+    }
+
+    fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+#[derive(Default)]
+pub struct DoublingApSumCircuit<const INITIAL_STEP: u128, const RATIO: u128, const COUNT: usize>;
+
+impl<F: FieldExt, const INITIAL_STEP: u128, const RATIO: u128, const COUNT: usize> Circuit<F>
+    for DoublingApSumCircuit<INITIAL_STEP, RATIO, COUNT>
+{
+    type Config = DoublingApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [(); 3].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+
+        DoublingApSumChip::<_, INITIAL_STEP, RATIO, COUNT>::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = DoublingApSumChip::<_, INITIAL_STEP, RATIO, COUNT>::construct(config);
+        let sum_cell = chip.assign(layouter.namespace(|| "doubling AP sum table"))?;
+        chip.expose_public(layouter.namespace(|| "output"), &sum_cell, 1)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, halo2curves::pasta::Fp};
+
+    #[test]
+    fn doubling_step_count_five_works() {
+        let k = 4;
+        let circuit = DoublingApSumCircuit::<1, 2, 5>;
+        // deltas 1, 2, 4, 8: 1 + 2 + 4 + 8 + 16 = 31
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(1), Fp::from(31)]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn constant_step_sum_is_rejected() {
+        let k = 4;
+        let circuit = DoublingApSumCircuit::<1, 2, 5>;
+        // 1 + 2 + 3 + 4 + 5 = 15 would hold for a constant step
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(1), Fp::from(15)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,291 @@
+//! AP sum circuit with runtime parameters.
+//!
+//! [`ApSumCircuitDyn`] takes the first term, step and count as values rather than const
+//! generics, so they can come from configuration. The step is baked into a fixed column
+//! and the count into the table height at key generation, so a key is specific to both.
+//!
+//! Instance layout: `[first, sum]`.
+
+use crate::{ap_sum_field, ApSumError};
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+
+#[derive(Clone, Debug)]
+pub struct ApSumDynConfig {
+    // [a_n, sum_n]
+    advice: [Column<Advice>; 2],
+    step: Column<Fixed>,
+    selector: Selector,
+    instance: Column<Instance>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ApSumCircuitDyn<F: FieldExt> {
+    pub first: F,
+    pub step: F,
+    pub count: usize,
+}
+
+impl<F: FieldExt> ApSumCircuitDyn<F> {
+    /// Parses decimal `first` and `step`, each below the field modulus, and a decimal
+    /// `count`.
+    pub fn from_strings(first: &str, step: &str, count: &str) -> Result<Self, ApSumError> {
+        Ok(Self {
+            first: parse_field(first)?,
+            step: parse_field(step)?,
+            count: count
+                .parse()
+                .map_err(|_| ApSumError::ParseError(count.to_owned()))?,
+        })
+    }
+
+    /// Builds the `count`-term progression from `first` to `last`, inferring
+    /// `step = (last - first) / (count - 1)`.
+    ///
+    /// A decreasing progression gets the negated step. Returns
+    /// [`ApSumError::NotDivisible`] if the step is not an integer, and for a single term
+    /// unless `first == last`.
+    pub fn from_endpoints(first: u128, last: u128, count: usize) -> Result<Self, ApSumError> {
+        let gaps = count.saturating_sub(1) as u128;
+        let span = first.abs_diff(last);
+        let step = match (span, gaps) {
+            (0, _) => 0,
+            (_, 0) => return Err(ApSumError::NotDivisible),
+            _ if span % gaps != 0 => return Err(ApSumError::NotDivisible),
+            _ => span / gaps,
+        };
+        let step = F::from_u128(step);
+        Ok(Self {
+            first: F::from_u128(first),
+            step: if last < first { -step } else { step },
+            count,
+        })
+    }
+
+    /// Builds the `count`-term progression with the given `step` whose terms add up to
+    /// `sum`, solving `first = (sum - step * count * (count - 1) / 2) / count`.
+    ///
+    /// A first term below zero is negated in the field. Returns
+    /// [`ApSumError::NotDivisible`] if `first` is not an integer or `count` is `0`.
+    pub fn for_target_sum(sum: u128, step: u128, count: usize) -> Result<Self, ApSumError> {
+        let n = count as u128;
+        let steps = (n * n.saturating_sub(1) / 2)
+            .checked_mul(step)
+            .ok_or(ApSumError::Overflow)?;
+        let span = sum.abs_diff(steps);
+        if n == 0 || span % n != 0 {
+            return Err(ApSumError::NotDivisible);
+        }
+        let first = F::from_u128(span / n);
+        Ok(Self {
+            first: if sum < steps { -first } else { first },
+            step: F::from_u128(step),
+            count,
+        })
+    }
+
+    /// Returns the `[first, sum]` instance of the progression.
+    pub fn instance(&self) -> Vec<F> {
+        vec![self.first, ap_sum_field(self.first, self.step, self.count)]
+    }
+}
+
+/// Parses a decimal string into a canonical field element.
+///
+/// Assumes a little-endian representation, as for the Pasta fields.
+fn parse_field<F: FieldExt>(input: &str) -> Result<F, ApSumError> {
+    let error = || ApSumError::ParseError(input.to_owned());
+    if input.is_empty() || !input.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(error());
+    }
+
+    let mut repr = F::Repr::default();
+    for digit in input.bytes().map(|b| u16::from(b - b'0')) {
+        // repr = repr * 10 + digit
+        let mut carry = digit;
+        for byte in repr.as_mut() {
+            let value = u16::from(*byte) * 10 + carry;
+            *byte = value as u8;
+            carry = value >> 8;
+        }
+        if carry != 0 {
+            return Err(error());
+        }
+    }
+    Option::from(F::from_repr(repr)).ok_or_else(error)
+}
+
+impl<F: FieldExt> Circuit<F> for ApSumCircuitDyn<F> {
+    type Config = ApSumDynConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        // All fields are parameters or public inputs.
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let step = meta.fixed_column();
+        let selector = meta.selector();
+        let instance = meta.instance_column();
+
+        meta.enable_equality(advice[0]);
+        meta.enable_equality(advice[1]);
+        meta.enable_equality(instance);
+
+        // |  advice[0]   |    advice[1]   |  step  | selector
+        // ----------------------------------------------------
+        // |     a_0      |     sum_0      |        |
+        // |     a_1      |     sum_1      |  step  |    s
+        // |     ...      |     ...        |  step  |    s
+        meta.create_gate("step and sum", |meta| {
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let sum = meta.query_advice(advice[1], Rotation::cur());
+            let prev_a = meta.query_advice(advice[0], Rotation::prev());
+            let prev_sum = meta.query_advice(advice[1], Rotation::prev());
+            let step = meta.query_fixed(step, Rotation::cur());
+            let s = meta.query_selector(selector);
+            vec![
+                // sum == a + prev_sum
+                s.clone() * (a.clone() + prev_sum - sum),
+                // a == prev_a + step
+                s * (a - prev_a - step),
+            ]
+        });
+
+        ApSumDynConfig {
+            advice,
+            step,
+            selector,
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        if self.count == 0 {
+            return Err(Error::Synthesis);
+        }
+
+        let sum_cell = layouter.assign_region(
+            || "AP sum table",
+            |mut region| {
+                let [a_column, sum_column] = config.advice;
+
+                let mut a_cell =
+                    region.assign_advice_from_instance(|| "a", config.instance, 0, a_column, 0)?;
+                let mut sum_cell = region.assign_advice_from_instance(
+                    || "sum",
+                    config.instance,
+                    0,
+                    sum_column,
+                    0,
+                )?;
+
+                for row in 1..self.count {
+                    config.selector.enable(&mut region, row)?;
+                    region.assign_fixed(|| "step", config.step, row, || Value::known(self.step))?;
+
+                    let new_a_val = a_cell.value().map(|a| *a + self.step);
+                    a_cell = region.assign_advice(|| "a", a_column, row, || new_a_val)?;
+
+                    let new_sum = sum_cell.value().copied() + new_a_val;
+                    sum_cell = region.assign_advice(|| "sum", sum_column, row, || new_sum)?;
+                }
+
+                Ok(sum_cell)
+            },
+        )?;
+        layouter.constrain_instance(sum_cell.cell(), config.instance, 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, halo2curves::pasta::Fp};
+
+    // The modulus of the Pallas base field.
+    const MODULUS: &str =
+        "28948022309329048855892746252171976963363056481941560715954676764349967630337";
+
+    #[test]
+    fn parsed_strings_satisfy_circuit() {
+        let circuit = ApSumCircuitDyn::<Fp>::from_strings("1", "1", "5").unwrap();
+        let instance = circuit.instance();
+        // 1 + 2 + 3 + 4 + 5 = 15
+        assert_eq!(instance, vec![Fp::from(1), Fp::from(15)]);
+        let prover = MockProver::run(4, &circuit, vec![instance]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn step_is_inferred_from_endpoints() {
+        let circuit = ApSumCircuitDyn::<Fp>::from_endpoints(1, 7, 4).unwrap();
+        assert_eq!(circuit.step, Fp::from(2));
+        let instance = circuit.instance();
+        // 1 + 3 + 5 + 7 = 16
+        assert_eq!(instance, vec![Fp::from(1), Fp::from(16)]);
+        let prover = MockProver::run(4, &circuit, vec![instance]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn endpoints_between_steps_are_rejected() {
+        assert!(matches!(
+            ApSumCircuitDyn::<Fp>::from_endpoints(1, 8, 4),
+            Err(ApSumError::NotDivisible)
+        ));
+    }
+
+    #[test]
+    fn first_term_is_solved_from_target_sum() {
+        // 1 + 4 + 7 + 10 = 22
+        let circuit = ApSumCircuitDyn::<Fp>::for_target_sum(22, 3, 4).unwrap();
+        assert_eq!(circuit.first, Fp::from(1));
+        let instance = circuit.instance();
+        assert_eq!(instance, vec![Fp::from(1), Fp::from(22)]);
+        let prover = MockProver::run(4, &circuit, vec![instance]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn target_sum_without_integer_first_term_is_rejected() {
+        assert!(matches!(
+            ApSumCircuitDyn::<Fp>::for_target_sum(23, 3, 4),
+            Err(ApSumError::NotDivisible)
+        ));
+    }
+
+    #[test]
+    fn values_up_to_the_modulus_parse() {
+        let below_modulus =
+            "28948022309329048855892746252171976963363056481941560715954676764349967630336";
+        let circuit = ApSumCircuitDyn::<Fp>::from_strings(below_modulus, "1", "5").unwrap();
+        assert_eq!(circuit.first, -Fp::one());
+
+        // -1 + 0 + 1 + 2 + 3 = 5
+        let instance = circuit.instance();
+        assert_eq!(instance[1], Fp::from(5));
+        let prover = MockProver::run(4, &circuit, vec![instance]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn out_of_range_or_malformed_strings_are_rejected() {
+        for (first, step, count) in [
+            (MODULUS, "1", "5"),
+            ("1", "12a", "5"),
+            ("1", "", "5"),
+            ("1", "1", "-5"),
+        ] {
+            assert!(matches!(
+                ApSumCircuitDyn::<Fp>::from_strings(first, step, count),
+                Err(ApSumError::ParseError(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,142 @@
+//! Proof that a public count is consistent with public endpoints.
+//!
+//! For a progression with step `STEP`, the count of terms from `first` to `last` is
+//! `(last - first) / STEP + 1`. Rather than dividing, the circuit checks the
+//! multiplication `last == first + STEP * (count - 1)` in a single row.
+//!
+//! Instance layout: `[first, last, count]`.
+
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+use std::marker::PhantomData;
+
+#[derive(Clone, Debug)]
+pub struct EndpointsConfig {
+    // [first, last, count]
+    advice: [Column<Advice>; 3],
+    selector: Selector,
+    instance: Column<Instance>,
+}
+
+struct EndpointsChip<F, const STEP: u128> {
+    config: EndpointsConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const STEP: u128> EndpointsChip<F, STEP> {
+    fn construct(config: EndpointsConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 3],
+        instance: Column<Instance>,
+    ) -> EndpointsConfig {
+        let selector = meta.selector();
+
+        for column in advice {
+            meta.enable_equality(column);
+        }
+        meta.enable_equality(instance);
+
+        // | advice[0] | advice[1] | advice[2] | selector
+        // -----------------------------------------------
+        // |   first   |   last    |   count   |    s
+        meta.create_gate("count from endpoints", |meta| {
+            let first = meta.query_advice(advice[0], Rotation::cur());
+            let last = meta.query_advice(advice[1], Rotation::cur());
+            let count = meta.query_advice(advice[2], Rotation::cur());
+            let s = meta.query_selector(selector);
+            // last == first + STEP * (count - 1)
+            vec![
+                s * (first
+                    + Expression::Constant(F::from_u128(STEP))
+                        * (count - Expression::Constant(F::one()))
+                    - last),
+            ]
+        });
+
+        EndpointsConfig {
+            advice,
+            selector,
+            instance,
+        }
+    }
+
+    fn assign(&self, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_region(
+            || "endpoints",
+            |mut region| {
+                self.config.selector.enable(&mut region, 0)?;
+                for (row, (name, column)) in ["first", "last", "count"]
+                    .into_iter()
+                    .zip(self.config.advice)
+                    .enumerate()
+                {
+                    region.assign_advice_from_instance(
+                        || name,
+                        self.config.instance,
+                        row,
+                        column,
+                        0,
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+#[derive(Default)]
+pub struct EndpointsCircuit<const STEP: u128>;
+
+impl<F: FieldExt, const STEP: u128> Circuit<F> for EndpointsCircuit<STEP> {
+    type Config = EndpointsConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [(); 3].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+
+        EndpointsChip::<_, STEP>::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = EndpointsChip::<_, STEP>::construct(config);
+        chip.assign(layouter.namespace(|| "endpoints"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, halo2curves::pasta::Fp};
+
+    #[test]
+    fn step_two_from_one_to_seven_has_four_terms() {
+        let k = 4;
+        // 1, 3, 5, 7
+        let instance = vec![Fp::from(1), Fp::from(7), Fp::from(4)];
+        let prover = MockProver::run(k, &EndpointsCircuit::<2>, vec![instance]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn inconsistent_count_is_rejected() {
+        let k = 4;
+        let instance = vec![Fp::from(1), Fp::from(7), Fp::from(5)];
+        let prover = MockProver::run(k, &EndpointsCircuit::<2>, vec![instance]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
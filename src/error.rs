@@ -0,0 +1,96 @@
+//! The error type of the crate's helpers.
+
+use halo2_proofs::{dev::VerifyFailure, plonk::Error};
+use std::fmt;
+
+/// Errors returned by this crate's helpers.
+#[derive(Debug)]
+pub enum ApSumError {
+    /// The integer sum does not fit in a `u128`.
+    Overflow,
+    /// The instance column has the wrong number of rows for the layout.
+    InstanceLength { expected: usize, actual: usize },
+    /// The instance's first term differs from the expected one.
+    FirstTermMismatch,
+    /// The instance's sum differs from the expected one.
+    SumMismatch,
+    /// Two instance rows bound to the same cell hold different values.
+    ConflictingInstance { first_row: usize, second_row: usize },
+    /// Proving was aborted through its cancel flag.
+    Cancelled,
+    /// The proof's instance carries the parameter tag of a different `STEP` and `COUNT`.
+    ParameterMismatch,
+    /// The parameters do not determine an integer step or first term.
+    NotDivisible,
+    /// The progression has no terms, so it has no last term.
+    NoTerms,
+    /// The verifier's params differ from those a proof or bundle was made with.
+    ParamsFingerprintMismatch,
+    /// A table row lies beyond the usable rows of a circuit of size `2^k`.
+    RowOutOfBounds { row: usize, usable: usize },
+    /// The prover failed.
+    Proving(Error),
+    /// `MockProver` found the circuit unsatisfied.
+    Unsatisfied(Vec<VerifyFailure>),
+    /// Reading or writing a proof or params failed, or the data is truncated or malformed.
+    Io(std::io::Error),
+    /// The string is not a valid number for its target type.
+    ParseError(String),
+    /// A layout of `rows` rows needs a larger canvas than the renderer allows.
+    #[cfg(feature = "dev-graph")]
+    RenderTooLarge { rows: usize },
+    /// The drawing backend failed.
+    #[cfg(feature = "dev-graph")]
+    Render(String),
+}
+
+impl fmt::Display for ApSumError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApSumError::Overflow => write!(f, "arithmetic progression sum overflows u128"),
+            ApSumError::InstanceLength { expected, actual } => {
+                write!(f, "instance has {actual} rows, layout expects {expected}")
+            }
+            ApSumError::FirstTermMismatch => write!(f, "instance first term does not match"),
+            ApSumError::SumMismatch => write!(f, "instance sum does not match"),
+            ApSumError::ConflictingInstance {
+                first_row,
+                second_row,
+            } => write!(
+                f,
+                "instance rows {first_row} and {second_row} are bound to the same cell but differ"
+            ),
+            ApSumError::Cancelled => write!(f, "proving was cancelled"),
+            ApSumError::ParameterMismatch => {
+                write!(f, "proof was generated for different circuit parameters")
+            }
+            ApSumError::NotDivisible => {
+                write!(f, "parameters do not determine an integer progression")
+            }
+            ApSumError::NoTerms => write!(f, "progression has no terms"),
+            ApSumError::ParamsFingerprintMismatch => {
+                write!(f, "params fingerprint does not match the expected one")
+            }
+            ApSumError::RowOutOfBounds { row, usable } => {
+                write!(
+                    f,
+                    "row {row} is beyond the {usable} usable rows, increase k"
+                )
+            }
+            ApSumError::Proving(e) => write!(f, "proving failed: {e}"),
+            ApSumError::Unsatisfied(failures) => {
+                write!(f, "circuit is not satisfied: {} failures", failures.len())
+            }
+            ApSumError::Io(e) => write!(f, "i/o failed: {e}"),
+            ApSumError::ParseError(input) => write!(f, "cannot parse {input:?} as a number"),
+            #[cfg(feature = "dev-graph")]
+            ApSumError::RenderTooLarge { rows } => {
+                write!(f, "layout of {rows} rows is too large to render")
+            }
+            #[cfg(feature = "dev-graph")]
+            ApSumError::Render(e) => write!(f, "failed to render layout: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for ApSumError {}
@@ -0,0 +1,80 @@
+//! Verifying key encoding for the `evm` feature.
+//!
+//! [`vk_to_evm_bytes`] follows the constant layout read by halo2-solidity-verifier style
+//! verifiers: the key's transcript representation as one 32-byte word, then every fixed
+//! commitment and every permutation commitment as an `(x, y)` pair of 32-byte words, all
+//! big-endian. The point at infinity is encoded as `(0, 0)`.
+//!
+//! This crate proves over Pasta with IPA, so the bytes describe Pasta points; a deployed
+//! verifier has to be generated for the same curve and commitment scheme.
+
+use crate::bytes::{sum_to_bytes, Endianness};
+use halo2_proofs::{
+    arithmetic::CurveAffine,
+    halo2curves::pasta::{EqAffine, Fq},
+    plonk::VerifyingKey,
+};
+
+/// Size of one encoded word.
+pub const WORD_LEN: usize = 32;
+
+/// Encodes `vk` for an EVM verifier.
+#[must_use]
+pub fn vk_to_evm_bytes(vk: &VerifyingKey<EqAffine>) -> Vec<u8> {
+    let commitments = vk
+        .fixed_commitments()
+        .iter()
+        .chain(vk.permutation().commitments());
+
+    let mut bytes = sum_to_bytes(vk.transcript_repr(), Endianness::Big);
+    for point in commitments {
+        let x = point.coordinates().map(|c| *c.x()).unwrap_or(Fq::zero());
+        let y = point.coordinates().map(|c| *c.y()).unwrap_or(Fq::zero());
+        bytes.extend(sum_to_bytes(x, Endianness::Big));
+        bytes.extend(sum_to_bytes(y, Endianness::Big));
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{proof::setup, ApSumCircuit};
+
+    #[test]
+    fn encoding_has_one_word_pair_per_commitment() {
+        let (_, pk) = setup(5, &ApSumCircuit::<1, 5>).unwrap();
+        let vk = pk.get_vk();
+        let bytes = vk_to_evm_bytes(vk);
+
+        // The sum and instance columns take part in the permutation.
+        assert_eq!(vk.permutation().commitments().len(), 2);
+        let points = vk.fixed_commitments().len() + vk.permutation().commitments().len();
+        assert_eq!(bytes.len(), WORD_LEN + 2 * WORD_LEN * points);
+    }
+
+    /// The committed length and leading words of the encoding of `ApSumCircuit<1, 5>` at
+    /// `k = 5`, rewritten when `UPDATE_SNAPSHOTS` is set.
+    const SNAPSHOT: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/snapshots/ap_sum_vk_evm.txt"
+    );
+
+    #[test]
+    fn encoding_matches_snapshot() {
+        let (_, pk) = setup(5, &ApSumCircuit::<1, 5>).unwrap();
+        let bytes = vk_to_evm_bytes(pk.get_vk());
+        let prefix: String = bytes[..2 * WORD_LEN]
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+        let encoded = format!("len: {}\nprefix: {prefix}\n", bytes.len());
+
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            std::fs::write(SNAPSHOT, &encoded).unwrap();
+        }
+        let snapshot = std::fs::read_to_string(SNAPSHOT)
+            .unwrap_or_else(|_| panic!("missing {SNAPSHOT}, run with UPDATE_SNAPSHOTS=1"));
+        assert_eq!(encoded, snapshot, "the vk encoding changed");
+    }
+}
@@ -0,0 +1,75 @@
+//! Proof that the AP sum strictly exceeds a public threshold.
+//!
+//! The circuit computes the sum as [`crate::ApSumCircuit`] does and compares it with the
+//! threshold using [`GreaterThanChip`], so only the threshold is published. The sum and
+//! the threshold must be below `2^RANGE_BITS`.
+//!
+//! Instance layout: `[first, threshold]`.
+
+use crate::{
+    range::{ComparisonConfig, GreaterThanChip},
+    ApSumChip, ApSumConfig,
+};
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*};
+
+/// Bits of the range check used for the comparison.
+pub const RANGE_BITS: usize = 8;
+
+#[derive(Clone, Debug)]
+pub struct ExceedsThresholdConfig {
+    ap_sum: ApSumConfig,
+    greater_than: ComparisonConfig<RANGE_BITS>,
+}
+
+#[derive(Default)]
+pub struct ExceedsThresholdCircuit<const STEP: u128, const COUNT: usize>;
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> Circuit<F>
+    for ExceedsThresholdCircuit<STEP, COUNT>
+{
+    type Config = ExceedsThresholdConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+
+        ExceedsThresholdConfig {
+            ap_sum: ApSumChip::<_, STEP, COUNT>::configure(meta, advice, instance),
+            greater_than: GreaterThanChip::configure(meta),
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = ApSumChip::<_, STEP, COUNT>::construct(config.ap_sum.clone());
+        let greater_than = GreaterThanChip::construct(config.greater_than);
+        greater_than.load_table(layouter.namespace(|| "range table"))?;
+
+        let cells = chip.assign(layouter.namespace(|| "AP sum table"))?;
+        let threshold = layouter.assign_region(
+            || "threshold",
+            |mut region| {
+                region.assign_advice_from_instance(
+                    || "threshold",
+                    config.ap_sum.instance,
+                    1,
+                    config.ap_sum.advice[0],
+                    0,
+                )
+            },
+        )?;
+        greater_than.assert_greater_than(
+            layouter.namespace(|| "sum > threshold"),
+            cells.sum.assigned(),
+            &threshold,
+        )
+    }
+}
@@ -0,0 +1,132 @@
+//! Sum of the fractions `a_n / D` for a public denominator `D`.
+//!
+//! The table accumulates the numerators `a_n`, an arithmetic progression, as
+//! [`crate::ApSumCircuit`] does. A scaling gate then proves `numerator_sum == result * D`,
+//! so the exposed result is `numerator_sum / D` without an in-circuit inversion. `D` must
+//! be non-zero, which the verifier can check since it is public. For numerators `1, 2, 3`
+//! and `D = 2` the result is `3`.
+//!
+//! Instance layout: `[first_numerator, denominator, result]`.
+
+use crate::{ApSumChip, ApSumConfig};
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+
+#[derive(Clone, Debug)]
+pub struct FractionApSumConfig {
+    ap_sum: ApSumConfig,
+    // [numerator_sum, denominator, result]
+    scaling: [Column<Advice>; 3],
+    s_scale: Selector,
+}
+
+#[derive(Default)]
+pub struct FractionApSumCircuit<const STEP: u128, const COUNT: usize>;
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> Circuit<F>
+    for FractionApSumCircuit<STEP, COUNT>
+{
+    type Config = FractionApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let ap_sum = ApSumChip::<_, STEP, COUNT>::configure_sum_only(meta, advice, instance);
+
+        let scaling = [(); 3].map(|_| meta.advice_column());
+        for column in scaling {
+            meta.enable_equality(column);
+        }
+        let s_scale = meta.selector();
+
+        // | numerator_sum | denominator | result | s_scale
+        // --------------------------------------------------
+        // |       n       |      d      |   r    |    s       n == r * d
+        meta.create_gate("common denominator", |meta| {
+            let numerator_sum = meta.query_advice(scaling[0], Rotation::cur());
+            let denominator = meta.query_advice(scaling[1], Rotation::cur());
+            let result = meta.query_advice(scaling[2], Rotation::cur());
+            let s = meta.query_selector(s_scale);
+            vec![s * (numerator_sum - result * denominator)]
+        });
+
+        FractionApSumConfig {
+            ap_sum,
+            scaling,
+            s_scale,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let instance = config.ap_sum.instance;
+        let chip = ApSumChip::<_, STEP, COUNT>::construct(config.ap_sum);
+        let cells = chip.assign(layouter.namespace(|| "numerator table"))?;
+
+        let result = layouter.assign_region(
+            || "common denominator",
+            |mut region| {
+                config.s_scale.enable(&mut region, 0)?;
+                let [numerator_column, denominator_column, result_column] = config.scaling;
+                let numerator_sum =
+                    cells
+                        .sum
+                        .copy_advice(|| "numerator sum", &mut region, numerator_column, 0)?;
+                let denominator = region.assign_advice_from_instance(
+                    || "denominator",
+                    instance,
+                    1,
+                    denominator_column,
+                    0,
+                )?;
+                // A zero denominator has no inverse; the unknown result then fails the gate.
+                let result = numerator_sum.value().zip(denominator.value()).and_then(
+                    |(numerator, denominator)| {
+                        Option::from(denominator.invert()).map_or_else(Value::unknown, |inverse| {
+                            Value::known(*numerator * inverse)
+                        })
+                    },
+                );
+                region.assign_advice(|| "result", result_column, 0, || result)
+            },
+        )?;
+        chip.expose_public(layouter.namespace(|| "result"), &result, 2)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, halo2curves::pasta::Fp};
+
+    fn run(denominator: u64, result: u64) -> bool {
+        let k = 4;
+        let instance = vec![Fp::from(1), Fp::from(denominator), Fp::from(result)];
+        MockProver::run(k, &FractionApSumCircuit::<1, 3>, vec![instance])
+            .unwrap()
+            .verify()
+            .is_ok()
+    }
+
+    #[test]
+    fn halves_one_two_three_sum_to_three() {
+        // (1 + 2 + 3) / 2 = 3
+        assert!(run(2, 3));
+        // (1 + 2 + 3) / 3 = 2
+        assert!(run(3, 2));
+    }
+
+    #[test]
+    fn wrong_result_is_rejected() {
+        assert!(!run(2, 6));
+        assert!(!run(2, 4));
+    }
+}
@@ -0,0 +1,171 @@
+//! Proof that the running sum reached at least a public percentage of a public goal.
+//!
+//! The table and the one-hot selection of `sum_{k-1}` at a hidden count `k` are those of
+//! [`crate::hidden`]. A scaling row then computes `sum_{k-1} * 100` and `P * G`, and a
+//! range-checked comparison proves `P * G <= sum_{k-1} * 100`, so `k` stays private.
+//!
+//! Both products must be below `2^RANGE_BITS`.
+//!
+//! Instance layout: `[first, goal, percent]`.
+
+use crate::{
+    hidden::{HiddenCountApSumChip, HiddenCountApSumConfig},
+    range::ComparisonConfig,
+};
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+
+/// Bits of the range check used for the comparison.
+pub const RANGE_BITS: usize = 12;
+
+/// Instance rows of the goal and the percentage.
+const GOAL_ROW: usize = 1;
+const PERCENT_ROW: usize = 2;
+
+#[derive(Clone, Debug)]
+pub struct GoalApSumConfig {
+    selection: HiddenCountApSumConfig,
+    // [reached, scaled, percent, goal, target]
+    advice: [Column<Advice>; 5],
+    s_scale: Selector,
+    comparison: ComparisonConfig<RANGE_BITS>,
+    instance: Column<Instance>,
+}
+
+#[derive(Default)]
+pub struct GoalApSumCircuit<const STEP: u128, const MAX_COUNT: usize> {
+    /// The hidden count `k` by which the running sum reaches the percentage.
+    pub count: Value<usize>,
+}
+
+impl<F: FieldExt, const STEP: u128, const MAX_COUNT: usize> Circuit<F>
+    for GoalApSumCircuit<STEP, MAX_COUNT>
+{
+    type Config = GoalApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [(); 5].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+        let selection =
+            HiddenCountApSumChip::<_, STEP, MAX_COUNT>::configure(meta, advice, instance);
+        let s_scale = meta.selector();
+
+        // The scaling row copies in the selected sum, the goal and the percentage.
+        for column in advice {
+            meta.enable_equality(column);
+        }
+
+        // | advice[0] | advice[1] | advice[2] | advice[3] | advice[4] | s_scale
+        // | reached   |  scaled   |  percent  |   goal    |  target   |    s
+        meta.create_gate("scale", |meta| {
+            let [reached, scaled, percent, goal, target] =
+                advice.map(|column| meta.query_advice(column, Rotation::cur()));
+            let s = meta.query_selector(s_scale);
+            vec![
+                // scaled == reached * 100
+                s.clone() * (reached * Expression::Constant(F::from(100)) - scaled),
+                // target == percent * goal
+                s * (percent * goal - target),
+            ]
+        });
+
+        // The comparison reuses the reached, scaled and percent columns in its own regions.
+        let comparison = ComparisonConfig::configure(meta, [advice[0], advice[1]], advice[2]);
+
+        GoalApSumConfig {
+            selection,
+            advice,
+            s_scale,
+            comparison,
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config
+            .comparison
+            .load_table(layouter.namespace(|| "range table"))?;
+        let chip = HiddenCountApSumChip::<_, STEP, MAX_COUNT>::construct(config.selection);
+        let reached = chip.assign(layouter.namespace(|| "goal AP sum table"), self.count)?;
+
+        let (scaled, target) = layouter.assign_region(
+            || "scale",
+            |mut region| {
+                let [reached_column, scaled_column, percent_column, goal_column, target_column] =
+                    config.advice;
+                config.s_scale.enable(&mut region, 0)?;
+
+                let reached = reached.copy_advice(|| "reached", &mut region, reached_column, 0)?;
+                let scaled = reached.value().map(|reached| *reached * F::from(100));
+                let scaled = region.assign_advice(|| "scaled", scaled_column, 0, || scaled)?;
+                let percent = region.assign_advice_from_instance(
+                    || "percent",
+                    config.instance,
+                    PERCENT_ROW,
+                    percent_column,
+                    0,
+                )?;
+                let goal = region.assign_advice_from_instance(
+                    || "goal",
+                    config.instance,
+                    GOAL_ROW,
+                    goal_column,
+                    0,
+                )?;
+                let target = percent.value().copied() * goal.value().copied();
+                let target = region.assign_advice(|| "target", target_column, 0, || target)?;
+                Ok((scaled, target))
+            },
+        )?;
+
+        // P * G <= sum_{k-1} * 100
+        config.comparison.assert_less_or_equal(
+            layouter.namespace(|| "target <= scaled"),
+            &target,
+            &scaled,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, halo2curves::pasta::Fp};
+
+    fn run(count: usize, goal: u64, percent: u64) -> bool {
+        let k = 13;
+        let circuit = GoalApSumCircuit::<1, 6> {
+            count: Value::known(count),
+        };
+        // running sums: 1, 3, 6, 10, 15, 21
+        let instance = vec![Fp::from(1), Fp::from(goal), Fp::from(percent)];
+        MockProver::run(k, &circuit, vec![instance])
+            .unwrap()
+            .verify()
+            .is_ok()
+    }
+
+    #[test]
+    fn half_of_goal_twenty_is_reached_at_count_four() {
+        // sum_3 = 10 is 50% of 20
+        assert!(run(4, 20, 50));
+        // sum_2 = 6 is not
+        assert!(!run(3, 20, 50));
+    }
+
+    #[test]
+    fn unreachable_percentage_is_rejected() {
+        // 50% of 50 is 25, above the largest running sum 21
+        for count in 1..=6 {
+            assert!(!run(count, 50, 50));
+        }
+    }
+}
@@ -0,0 +1,335 @@
+//! Proof that the running sum equals a public value at a hidden count.
+//!
+//! The table lays out `MAX_COUNT` terms and running sums. The prover marks the row of the
+//! `k`-th term in a one-hot `hit` column, and the circuit accumulates the marked running
+//! sum `sum_{k-1}` and constrains it to the public `V`, so `k` stays private.
+//!
+//! [`HiddenCountApSumChip::configure_with_previous`] additionally accumulates the running
+//! sum before the marked one, `sum_{k-2}` (`0` for `k = 1`), for [`crate::threshold`].
+//!
+//! Instance layout: `[first, value]`.
+
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+use std::marker::PhantomData;
+
+#[derive(Clone, Debug)]
+pub struct HiddenCountApSumConfig {
+    // [a_n, sum_n, hit_n, selected_n, hits_n]
+    advice: [Column<Advice>; 5],
+    // before_n, accumulating the running sum before the marked one
+    before: Option<Column<Advice>>,
+    s_first: Selector,
+    s_next: Selector,
+    s_last: Selector,
+    instance: Column<Instance>,
+}
+
+pub(crate) struct HiddenCountApSumChip<F, const STEP: u128, const MAX_COUNT: usize> {
+    config: HiddenCountApSumConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const STEP: u128, const MAX_COUNT: usize>
+    HiddenCountApSumChip<F, STEP, MAX_COUNT>
+{
+    pub(crate) fn construct(config: HiddenCountApSumConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub(crate) fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 5],
+        instance: Column<Instance>,
+    ) -> HiddenCountApSumConfig {
+        Self::configure_selection(meta, advice, None, instance)
+    }
+
+    /// Configures the table as [`HiddenCountApSumChip::configure`] does, additionally
+    /// accumulating the running sum before the marked one in `before`.
+    pub(crate) fn configure_with_previous(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 5],
+        before: Column<Advice>,
+        instance: Column<Instance>,
+    ) -> HiddenCountApSumConfig {
+        Self::configure_selection(meta, advice, Some(before), instance)
+    }
+
+    fn configure_selection(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 5],
+        before: Option<Column<Advice>>,
+        instance: Column<Instance>,
+    ) -> HiddenCountApSumConfig {
+        let s_first = meta.selector();
+        let s_next = meta.selector();
+        let s_last = meta.selector();
+
+        meta.enable_equality(advice[0]);
+        meta.enable_equality(advice[1]);
+        meta.enable_equality(advice[3]);
+        if let Some(before) = before {
+            meta.enable_equality(before);
+        }
+        meta.enable_equality(instance);
+
+        // | advice[0] | advice[1] | advice[2] |  advice[3]  | advice[4] |  before   |
+        // |    a_n    |   sum_n   |   hit_n   | selected_n  |  hits_n   | before_n  |
+        // ---------------------------------------------------------------------------
+        // row 0: selected = hit * sum, hits = hit, before = 0             (s_first)
+        // row n: selected += hit * sum, hits += hit,
+        //        before += hit * prev_sum                                 (s_next)
+        // last row: hits == 1                                             (s_last)
+        meta.create_gate("first row", |meta| {
+            let sum = meta.query_advice(advice[1], Rotation::cur());
+            let hit = meta.query_advice(advice[2], Rotation::cur());
+            let selected = meta.query_advice(advice[3], Rotation::cur());
+            let hits = meta.query_advice(advice[4], Rotation::cur());
+            let s = meta.query_selector(s_first);
+            let mut constraints = vec![
+                s.clone() * hit.clone() * (Expression::Constant(F::one()) - hit.clone()),
+                s.clone() * (hit.clone() * sum - selected),
+                s.clone() * (hit - hits),
+            ];
+            if let Some(before) = before {
+                constraints.push(s * meta.query_advice(before, Rotation::cur()));
+            }
+            constraints
+        });
+
+        meta.create_gate("step, sum and selection", |meta| {
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let sum = meta.query_advice(advice[1], Rotation::cur());
+            let hit = meta.query_advice(advice[2], Rotation::cur());
+            let selected = meta.query_advice(advice[3], Rotation::cur());
+            let hits = meta.query_advice(advice[4], Rotation::cur());
+            let prev_a = meta.query_advice(advice[0], Rotation::prev());
+            let prev_sum = meta.query_advice(advice[1], Rotation::prev());
+            let prev_selected = meta.query_advice(advice[3], Rotation::prev());
+            let prev_hits = meta.query_advice(advice[4], Rotation::prev());
+            let s = meta.query_selector(s_next);
+            let mut constraints = vec![
+                // sum == a + prev_sum
+                s.clone() * (a.clone() + prev_sum.clone() - sum.clone()),
+                // a == prev_a + STEP
+                s.clone() * (a - prev_a - Expression::Constant(F::from_u128(STEP))),
+                // hit is boolean
+                s.clone() * hit.clone() * (Expression::Constant(F::one()) - hit.clone()),
+                s.clone() * (prev_selected + hit.clone() * sum - selected),
+                s.clone() * (prev_hits + hit.clone() - hits),
+            ];
+            if let Some(before_column) = before {
+                let before = meta.query_advice(before_column, Rotation::cur());
+                let prev_before = meta.query_advice(before_column, Rotation::prev());
+                constraints.push(s * (prev_before + hit * prev_sum - before));
+            }
+            constraints
+        });
+
+        meta.create_gate("exactly one hit", |meta| {
+            let hits = meta.query_advice(advice[4], Rotation::cur());
+            let s = meta.query_selector(s_last);
+            vec![s * (hits - Expression::Constant(F::one()))]
+        });
+
+        HiddenCountApSumConfig {
+            advice,
+            before,
+            s_first,
+            s_next,
+            s_last,
+            instance,
+        }
+    }
+
+    /// Assigns the table, returning the `sum_{k-1}` cell selected by `count`.
+    pub(crate) fn assign(
+        &self,
+        layouter: impl Layouter<F>,
+        count: Value<usize>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.assign_selection(layouter, count)
+            .map(|(selected, _)| selected)
+    }
+
+    /// Assigns the table, returning the `(sum_{k-1}, sum_{k-2})` cells selected by
+    /// `count`. Fails with [`Error::Synthesis`] unless the chip was configured with
+    /// [`HiddenCountApSumChip::configure_with_previous`].
+    pub(crate) fn assign_with_previous(
+        &self,
+        layouter: impl Layouter<F>,
+        count: Value<usize>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        match self.assign_selection(layouter, count)? {
+            (selected, Some(before)) => Ok((selected, before)),
+            (_, None) => Err(Error::Synthesis),
+        }
+    }
+
+    fn assign_selection(
+        &self,
+        mut layouter: impl Layouter<F>,
+        count: Value<usize>,
+    ) -> Result<(AssignedCell<F, F>, Option<AssignedCell<F, F>>), Error> {
+        layouter.assign_region(
+            || "hidden count AP sum table",
+            |mut region| {
+                // A table of no terms has no row to close with the count check.
+                let last_row = MAX_COUNT.checked_sub(1).ok_or(Error::Synthesis)?;
+                let [a_column, sum_column, hit_column, selected_column, hits_column] =
+                    self.config.advice;
+                let hit_value = |row: usize| count.map(|k| F::from(u64::from(row + 1 == k)));
+                let step = F::from_u128(STEP);
+
+                let mut a_cell = region.assign_advice_from_instance(
+                    || "a",
+                    self.config.instance,
+                    0,
+                    a_column,
+                    0,
+                )?;
+                let mut sum_cell = region.assign_advice_from_instance(
+                    || "sum",
+                    self.config.instance,
+                    0,
+                    sum_column,
+                    0,
+                )?;
+                let mut hit = hit_value(0);
+                region.assign_advice(|| "hit", hit_column, 0, || hit)?;
+                let mut selected_cell = region.assign_advice(
+                    || "selected",
+                    selected_column,
+                    0,
+                    || hit * sum_cell.value().copied(),
+                )?;
+                let mut hits_cell = region.assign_advice(|| "hits", hits_column, 0, || hit)?;
+                let mut before_cell = self
+                    .config
+                    .before
+                    .map(|column| {
+                        region.assign_advice(|| "before", column, 0, || Value::known(F::zero()))
+                    })
+                    .transpose()?;
+                self.config.s_first.enable(&mut region, 0)?;
+
+                for row in 1..MAX_COUNT {
+                    self.config.s_next.enable(&mut region, row)?;
+
+                    let prev_sum = sum_cell.value().copied();
+                    let new_a_val = a_cell.value().map(|a| *a + step);
+                    a_cell = region.assign_advice(|| "a", a_column, row, || new_a_val)?;
+
+                    let new_sum = prev_sum + new_a_val;
+                    sum_cell = region.assign_advice(|| "sum", sum_column, row, || new_sum)?;
+
+                    hit = hit_value(row);
+                    region.assign_advice(|| "hit", hit_column, row, || hit)?;
+                    let selected = selected_cell.value().copied() + hit * new_sum;
+                    selected_cell =
+                        region.assign_advice(|| "selected", selected_column, row, || selected)?;
+                    let hits = hits_cell.value().copied() + hit;
+                    hits_cell = region.assign_advice(|| "hits", hits_column, row, || hits)?;
+                    if let (Some(column), Some(cell)) = (self.config.before, before_cell.as_mut()) {
+                        let before = cell.value().copied() + hit * prev_sum;
+                        *cell = region.assign_advice(|| "before", column, row, || before)?;
+                    }
+                }
+                self.config.s_last.enable(&mut region, last_row)?;
+
+                Ok((selected_cell, before_cell))
+            },
+        )
+    }
+
+    fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+#[derive(Default)]
+pub struct HiddenCountApSumCircuit<const STEP: u128, const MAX_COUNT: usize> {
+    /// The hidden count `k` whose running sum equals the public value.
+    pub count: Value<usize>,
+}
+
+impl<F: FieldExt, const STEP: u128, const MAX_COUNT: usize> Circuit<F>
+    for HiddenCountApSumCircuit<STEP, MAX_COUNT>
+{
+    type Config = HiddenCountApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [(); 5].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+
+        HiddenCountApSumChip::<_, STEP, MAX_COUNT>::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = HiddenCountApSumChip::<_, STEP, MAX_COUNT>::construct(config);
+        let selected = chip.assign(
+            layouter.namespace(|| "hidden count AP sum table"),
+            self.count,
+        )?;
+        chip.expose_public(layouter.namespace(|| "value"), &selected, 1)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{
+        dev::{MockProver, VerifyFailure},
+        halo2curves::pasta::Fp,
+    };
+
+    fn run(count: usize, value: u64) -> Result<(), Vec<VerifyFailure>> {
+        let k = 4;
+        let circuit = HiddenCountApSumCircuit::<1, 5> {
+            count: Value::known(count),
+        };
+        // running sums: 1, 3, 6, 10, 15
+        MockProver::run(k, &circuit, vec![vec![Fp::from(1), Fp::from(value)]])
+            .unwrap()
+            .verify()
+    }
+
+    #[test]
+    fn running_sum_hits_six_at_count_three() {
+        assert_eq!(run(3, 6), Ok(()));
+    }
+
+    #[test]
+    fn value_never_taken_is_rejected() {
+        for count in 1..=5 {
+            assert!(run(count, 7).is_err());
+        }
+    }
+
+    #[test]
+    fn empty_table_is_an_error_not_a_panic() {
+        let circuit = HiddenCountApSumCircuit::<1, 0> {
+            count: Value::known(0),
+        };
+        let result = MockProver::run(4, &circuit, vec![vec![Fp::from(1), Fp::from(0)]]);
+        assert!(matches!(result, Err(Error::Synthesis)));
+    }
+}
@@ -0,0 +1,90 @@
+//! AP sum with externally injected terms on chosen rows.
+//!
+//! Every term is supplied by the prover, as in [`crate::ApSumWitnessCircuit`]. Rows listed
+//! in `injected` enable only the sum constraint, so their term is free, while every other
+//! row must still follow the step rule. For terms `1, 2, 10, 11` with row `2` injected the
+//! circuit computes `24`.
+//!
+//! Instance layout: `[first, sum]`.
+
+use crate::{ApSumChip, ApSumConfig};
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*};
+
+pub struct InjectedApSumCircuit<F: FieldExt, const STEP: u128, const COUNT: usize> {
+    pub terms: Vec<Value<F>>,
+    /// Rows whose term is not constrained by the step rule. Row `0` is always the first
+    /// term.
+    pub injected: Vec<usize>,
+}
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> Circuit<F>
+    for InjectedApSumCircuit<F, STEP, COUNT>
+{
+    type Config = ApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            terms: vec![Value::unknown(); COUNT],
+            injected: self.injected.clone(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+
+        ApSumChip::<_, STEP, COUNT>::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = ApSumChip::<_, STEP, COUNT>::construct(config);
+        let cells = chip.assign_with_injected(
+            layouter.namespace(|| "AP sum table"),
+            &self.terms,
+            &self.injected,
+        )?;
+        chip.expose_public(layouter.namespace(|| "output"), cells.sum.assigned(), 1)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{
+        dev::{MockProver, VerifyFailure},
+        halo2curves::pasta::Fp,
+    };
+
+    fn run(terms: [u64; 4], injected: Vec<usize>, sum: u64) -> Result<(), Vec<VerifyFailure>> {
+        let k = 4;
+        let circuit = InjectedApSumCircuit::<Fp, 1, 4> {
+            terms: terms.map(|term| Value::known(Fp::from(term))).to_vec(),
+            injected,
+        };
+        MockProver::run(k, &circuit, vec![vec![Fp::from(terms[0]), Fp::from(sum)]])
+            .unwrap()
+            .verify()
+    }
+
+    #[test]
+    fn without_injected_rows_both_constraints_apply() {
+        // 1 + 2 + 3 + 4 = 10
+        assert_eq!(run([1, 2, 3, 4], vec![], 10), Ok(()));
+        // the jump to 10 breaks the step rule
+        assert!(run([1, 2, 10, 11], vec![], 24).is_err());
+    }
+
+    #[test]
+    fn injected_row_is_only_sum_constrained() {
+        // 1 + 2 + 10 + 11 = 24
+        assert_eq!(run([1, 2, 10, 11], vec![2], 24), Ok(()));
+        // row 3 still follows the step rule
+        assert!(run([1, 2, 10, 12], vec![2], 25).is_err());
+    }
+}
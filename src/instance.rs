@@ -0,0 +1,202 @@
+//! Assembly of the public instance column.
+//!
+//! The circuits described by [`Layout`] and built with [`InstanceBuilder`] read their
+//! public values from a single instance column in the same order:
+//!
+//! | row        | value              |
+//! |------------|--------------------|
+//! | 0          | first term `a_0`   |
+//! | 1          | sum                |
+//! | 2          | last term (if any) |
+//! | following  | partial sums       |
+//!
+//! Outputs a circuit does not expose are skipped, so later entries move up.
+//!
+//! Other circuits lay out their instance differently, e.g. [`crate::merge`],
+//! [`crate::division`] or the several columns of [`crate::columns`]; each documents its
+//! own order in an "Instance layout" line of its module or type docs.
+
+use crate::ApSumError;
+use halo2_proofs::arithmetic::FieldExt;
+
+/// The public outputs a circuit exposes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layout {
+    /// `[first, sum]`, as exposed by [`crate::ApSumCircuit`].
+    Sum,
+    /// `[first, sum, last_term]`, as exposed by [`crate::ApSumLastTermCircuit`].
+    SumAndLastTerm,
+    /// `[first, sum_0, ..., sum_{count-1}]`, as exposed by
+    /// [`crate::ApSumPartialSumsCircuit`].
+    PartialSums { count: usize },
+}
+
+/// Returns the number of instance rows `layout` reads.
+#[must_use]
+pub fn required_instance_len(layout: Layout) -> usize {
+    match layout {
+        Layout::Sum => 2,
+        Layout::SumAndLastTerm => 3,
+        Layout::PartialSums { count } => count + 1,
+    }
+}
+
+/// Returns pairs of instance rows that `layout` copies into the same advice cell.
+///
+/// The circuits copy row 0 into both `a_0` and `sum_0`, so a layout exposing `sum_0`
+/// binds its row to row 0 as well.
+fn aliased_rows(layout: Layout) -> &'static [(usize, usize)] {
+    match layout {
+        Layout::Sum | Layout::SumAndLastTerm => &[],
+        // sum_0 is exposed at row 1
+        Layout::PartialSums { .. } => &[(0, 1)],
+    }
+}
+
+/// Checks `instance` has exactly the rows `layout` reads, and that rows bound to the
+/// same cell hold the same value.
+///
+/// Halo2 pads a short instance with zeros, and conflicting copies of one cell fail the
+/// permutation argument; both surface as unrelated-looking copy-constraint failures, so
+/// call this before proving to get a clear error instead.
+pub fn validate_instance<F: FieldExt>(layout: Layout, instance: &[F]) -> Result<(), ApSumError> {
+    let expected = required_instance_len(layout);
+    if instance.len() != expected {
+        return Err(ApSumError::InstanceLength {
+            expected,
+            actual: instance.len(),
+        });
+    }
+    for &(first_row, second_row) in aliased_rows(layout) {
+        if instance[first_row] != instance[second_row] {
+            return Err(ApSumError::ConflictingInstance {
+                first_row,
+                second_row,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Builds an instance vector in the order circuits expect, whatever order the values are
+/// supplied in.
+#[derive(Clone, Debug, Default)]
+pub struct InstanceBuilder<F: FieldExt> {
+    first: Option<F>,
+    sum: Option<F>,
+    last_term: Option<F>,
+    partial_sums: Vec<F>,
+}
+
+impl<F: FieldExt> InstanceBuilder<F> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn first(mut self, first: F) -> Self {
+        self.first = Some(first);
+        self
+    }
+
+    pub fn sum(mut self, sum: F) -> Self {
+        self.sum = Some(sum);
+        self
+    }
+
+    pub fn last_term(mut self, last_term: F) -> Self {
+        self.last_term = Some(last_term);
+        self
+    }
+
+    pub fn partial_sums(mut self, partial_sums: &[F]) -> Self {
+        self.partial_sums = partial_sums.to_vec();
+        self
+    }
+
+    pub fn build(self) -> Vec<F> {
+        self.first
+            .into_iter()
+            .chain(self.sum)
+            .chain(self.last_term)
+            .chain(self.partial_sums)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ApSumLastTermCircuit, ApSumPartialSumsCircuit};
+    use halo2_proofs::{dev::MockProver, halo2curves::pasta::Fp};
+
+    #[test]
+    fn build_orders_values_by_layout() {
+        let instance = InstanceBuilder::new()
+            .last_term(Fp::from(5))
+            .sum(Fp::from(15))
+            .first(Fp::from(1))
+            .build();
+        assert_eq!(instance, vec![Fp::from(1), Fp::from(15), Fp::from(5)]);
+    }
+
+    #[test]
+    fn sum_and_last_term_instance_satisfies_circuit() {
+        let k = 5;
+        let circuit = ApSumLastTermCircuit::<1, 5>;
+        // 1 + 2 + 3 + 4 + 5 = 15, last term 5
+        let instance = InstanceBuilder::new()
+            .first(Fp::from(1))
+            .sum(Fp::from(15))
+            .last_term(Fp::from(5))
+            .build();
+        let prover = MockProver::run(k, &circuit, vec![instance]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn partial_sums_instance_satisfies_circuit() {
+        let k = 5;
+        let circuit = ApSumPartialSumsCircuit::<1, 4>;
+        let instance = InstanceBuilder::new()
+            .first(Fp::from(1))
+            .partial_sums(&[Fp::from(1), Fp::from(3), Fp::from(6), Fp::from(10)])
+            .build();
+        validate_instance(Layout::PartialSums { count: 4 }, &instance).unwrap();
+        let prover = MockProver::run(k, &circuit, vec![instance]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn conflicting_first_partial_sum_is_rejected() {
+        let k = 5;
+        // sum_0 is a copy of a_0, so row 1 must repeat the first term
+        let instance = InstanceBuilder::new()
+            .first(Fp::from(1))
+            .partial_sums(&[Fp::from(2), Fp::from(3), Fp::from(6), Fp::from(10)])
+            .build();
+        assert!(matches!(
+            validate_instance(Layout::PartialSums { count: 4 }, &instance),
+            Err(ApSumError::ConflictingInstance {
+                first_row: 0,
+                second_row: 1
+            })
+        ));
+        let prover = MockProver::run(k, &ApSumPartialSumsCircuit::<1, 4>, vec![instance]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn short_partial_sums_instance_is_rejected() {
+        let instance = InstanceBuilder::new()
+            .first(Fp::from(1))
+            .sum(Fp::from(10))
+            .build();
+        assert!(matches!(
+            validate_instance(Layout::PartialSums { count: 4 }, &instance),
+            Err(ApSumError::InstanceLength {
+                expected: 5,
+                actual: 2
+            })
+        ));
+    }
+}
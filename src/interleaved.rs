@@ -0,0 +1,194 @@
+//! Sum of two interleaved arithmetic progressions.
+//!
+//! The step into an odd row is `STEP_A` and the step into an even row is `STEP_B`, so the
+//! terms alternate between the two increments. For first term `1`, `STEP_A = 2`,
+//! `STEP_B = 3` and count `4`, the terms are `1, 3, 6, 8` and the circuit computes `18`.
+//!
+//! Instance layout: `[first, sum]`.
+
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+use std::marker::PhantomData;
+
+#[derive(Clone, Debug)]
+pub struct InterleavedApSumConfig {
+    // [a_n, sum_n]
+    advice: [Column<Advice>; 2],
+    s_odd: Selector,
+    s_even: Selector,
+    instance: Column<Instance>,
+}
+
+struct InterleavedApSumChip<F, const STEP_A: u128, const STEP_B: u128, const COUNT: usize> {
+    config: InterleavedApSumConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const STEP_A: u128, const STEP_B: u128, const COUNT: usize>
+    InterleavedApSumChip<F, STEP_A, STEP_B, COUNT>
+{
+    fn construct(config: InterleavedApSumConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 2],
+        instance: Column<Instance>,
+    ) -> InterleavedApSumConfig {
+        let s_odd = meta.selector();
+        let s_even = meta.selector();
+
+        meta.enable_equality(advice[0]);
+        meta.enable_equality(advice[1]);
+        meta.enable_equality(instance);
+
+        // |  advice[0]   |    advice[1]   | s_odd | s_even
+        // -------------------------------------------------
+        // |     a_0      |     sum_0      |       |
+        // |     a_1      |     sum_1      |   s   |
+        // |     a_2      |     sum_2      |       |   s
+        // |     a_3      |     sum_3      |   s   |
+        // |     ...      |     ...        |  ...  |  ...
+        meta.create_gate("interleaved step and sum", |meta| {
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let sum = meta.query_advice(advice[1], Rotation::cur());
+            let prev_a = meta.query_advice(advice[0], Rotation::prev());
+            let prev_sum = meta.query_advice(advice[1], Rotation::prev());
+            let s_odd = meta.query_selector(s_odd);
+            let s_even = meta.query_selector(s_even);
+            let sum_constraint = a.clone() + prev_sum - sum;
+            let diff = a - prev_a;
+            vec![
+                // sum == a + prev_sum
+                s_odd.clone() * sum_constraint.clone(),
+                s_even.clone() * sum_constraint,
+                // a == prev_a + STEP_A on odd rows
+                s_odd * (diff.clone() - Expression::Constant(F::from_u128(STEP_A))),
+                // a == prev_a + STEP_B on even rows
+                s_even * (diff - Expression::Constant(F::from_u128(STEP_B))),
+            ]
+        });
+
+        InterleavedApSumConfig {
+            advice,
+            s_odd,
+            s_even,
+            instance,
+        }
+    }
+
+    fn assign(&self, mut layouter: impl Layouter<F>) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "interleaved AP sum table",
+            |mut region| {
+                let a_column = self.config.advice[0];
+                let sum_column = self.config.advice[1];
+
+                // Copy first instance into both a_0 and sum_0. No selector needed for first row.
+                let mut a_cell = region.assign_advice_from_instance(
+                    || "a",
+                    self.config.instance,
+                    0,
+                    a_column,
+                    0,
+                )?;
+                let mut sum_cell = region.assign_advice_from_instance(
+                    || "sum",
+                    self.config.instance,
+                    0,
+                    sum_column,
+                    0,
+                )?;
+
+                let (step_a, step_b) = (F::from_u128(STEP_A), F::from_u128(STEP_B));
+                for row in 1..COUNT {
+                    let step = if row % 2 == 1 {
+                        self.config.s_odd.enable(&mut region, row)?;
+                        step_a
+                    } else {
+                        self.config.s_even.enable(&mut region, row)?;
+                        step_b
+                    };
+
+                    let new_a_val = a_cell.value().map(|a| *a + step);
+                    a_cell = region.assign_advice(|| "a", a_column, row, || new_a_val)?;
+
+                    let new_sum = sum_cell
+                        .value()
+                        .and_then(|sum| new_a_val.map(|new_a| new_a + sum));
+                    sum_cell = region.assign_advice(|| "sum", sum_column, row, || new_sum)?;
+                }
+
+                Ok(sum_cell)
+            },
+        )
+    }
+
+    fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+#[derive(Default)]
+pub struct InterleavedApSumCircuit<const STEP_A: u128, const STEP_B: u128, const COUNT: usize>;
+
+impl<F: FieldExt, const STEP_A: u128, const STEP_B: u128, const COUNT: usize> Circuit<F>
+    for InterleavedApSumCircuit<STEP_A, STEP_B, COUNT>
+{
+    type Config = InterleavedApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+
+        InterleavedApSumChip::<_, STEP_A, STEP_B, COUNT>::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = InterleavedApSumChip::<_, STEP_A, STEP_B, COUNT>::construct(config);
+        let sum_cell = chip.assign(layouter.namespace(|| "interleaved AP sum table"))?;
+        chip.expose_public(layouter.namespace(|| "output"), &sum_cell, 1)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, halo2curves::pasta::Fp};
+
+    #[test]
+    fn interleaved_steps_two_and_three_count_four_works() {
+        let k = 4;
+        let circuit = InterleavedApSumCircuit::<2, 3, 4>;
+        // 1 + 3 + 6 + 8 = 18
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(1), Fp::from(18)]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn single_step_sum_is_rejected() {
+        let k = 4;
+        let circuit = InterleavedApSumCircuit::<2, 3, 4>;
+        // 1 + 3 + 5 + 7 = 16 would hold if every step were 2
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(1), Fp::from(16)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,192 @@
+//! Deterministic textual description of a circuit's constraint system.
+//!
+//! [`describe`] lists the column counts and every gate with the degree of each of its
+//! constraints. Comparing it against a committed snapshot catches accidental changes to a
+//! circuit's `configure`. [`ApSumCircuit::gate_polynomials`] renders the base gate's
+//! constraints themselves for external tooling, and [`dump_table`] renders the witness
+//! table for terminal debugging.
+
+use crate::{ApSumCircuit, ApSumError};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    plonk::{Circuit, ConstraintSystem, Expression},
+};
+use std::fmt::Write;
+
+/// Describes the constraint system `C` configures.
+#[must_use]
+pub fn describe<F: FieldExt, C: Circuit<F>>() -> String {
+    let mut meta = ConstraintSystem::<F>::default();
+    C::configure(&mut meta);
+
+    let mut out = String::new();
+    // Writing to a String cannot fail.
+    let _ = writeln!(out, "advice columns: {}", meta.num_advice_columns());
+    let _ = writeln!(out, "fixed columns: {}", meta.num_fixed_columns());
+    let _ = writeln!(out, "instance columns: {}", meta.num_instance_columns());
+    let _ = writeln!(out, "selectors: {}", meta.num_selectors());
+    let _ = writeln!(out, "lookups: {}", meta.lookups().len());
+    let _ = writeln!(out, "degree: {}", meta.degree());
+    for gate in meta.gates() {
+        let degrees: Vec<_> = gate.polynomials().iter().map(|p| p.degree()).collect();
+        let _ = writeln!(out, "gate {:?}: degrees {:?}", gate.name(), degrees);
+    }
+    out
+}
+
+/// Renders the witness table of the progression `first, first + step, ...` of `count`
+/// terms as ASCII, one line per row in the single-region layout of [`ApSumCircuit`]: the
+/// first-term selector on row 0, and the sum and step selectors on every later row.
+///
+/// Fails with [`ApSumError::Overflow`] if a term or running sum overflows `u128`.
+pub fn dump_table(first: u128, step: u128, count: usize) -> Result<String, ApSumError> {
+    let mut rows = Vec::with_capacity(count);
+    let (mut a, mut sum) = (first, first);
+    for row in 0..count {
+        if row > 0 {
+            a = a.checked_add(step).ok_or(ApSumError::Overflow)?;
+            sum = sum.checked_add(a).ok_or(ApSumError::Overflow)?;
+        }
+        // The first-term gate ties a_0 to sum_0; the step and sum gate is off on row 0.
+        let [first_term, step_and_sum] = if row == 0 { ["s", ""] } else { ["", "s"] };
+        rows.push([
+            row.to_string(),
+            a.to_string(),
+            sum.to_string(),
+            first_term.to_string(),
+            step_and_sum.to_string(),
+            step_and_sum.to_string(),
+        ]);
+    }
+
+    let header = [
+        "row",
+        "a_n",
+        "sum_n",
+        "first_term_selector",
+        "sum_selector",
+        "step_selector",
+    ];
+    let mut widths = header.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let mut out = String::new();
+    let line = |out: &mut String, cells: &[&str]| {
+        let cells: Vec<_> = cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{cell:>width$}"))
+            .collect();
+        // Writing to a String cannot fail.
+        let _ = writeln!(out, "{}", cells.join(" | ").trim_end());
+    };
+    line(&mut out, &header);
+    let rule: Vec<_> = widths.iter().map(|width| "-".repeat(*width)).collect();
+    let _ = writeln!(out, "{}", rule.join("-+-"));
+    for row in &rows {
+        let cells: Vec<_> = row.iter().map(String::as_str).collect();
+        line(&mut out, &cells);
+    }
+    out
+}
+
+impl<const STEP: u128, const COUNT: usize> ApSumCircuit<STEP, COUNT> {
+    /// Renders the constraint polynomials of the step and sum gate and of the first-term
+    /// gate, which must each vanish.
+    ///
+    /// The polynomials are read back from the configured constraint system, so they are
+    /// exactly the expressions passed to `create_gate`. Cells are named `a` and `sum`
+    /// (`prev_a` and `prev_sum` on the previous row), every selector `s` and the step
+    /// constant `STEP`.
+    pub fn gate_polynomials<F: FieldExt>() -> Vec<String> {
+        let mut meta = ConstraintSystem::<F>::default();
+        <Self as Circuit<F>>::configure(&mut meta);
+        meta.gates()
+            .iter()
+            .flat_map(|gate| gate.polynomials())
+            .map(render::<F, STEP>)
+            .collect()
+    }
+}
+
+fn render<F: FieldExt, const STEP: u128>(expression: &Expression<F>) -> String {
+    expression.evaluate(
+        &|constant| {
+            if constant == F::from_u128(STEP) {
+                "STEP".to_owned()
+            } else {
+                format!("{:?}", constant)
+            }
+        },
+        &|_| "s".to_owned(),
+        &|query| format!("fixed_{}", query.column_index()),
+        &|query| {
+            let name = if query.column_index() == 0 {
+                "a"
+            } else {
+                "sum"
+            };
+            if query.rotation().0 == -1 {
+                format!("prev_{}", name)
+            } else {
+                name.to_owned()
+            }
+        },
+        &|query| format!("instance_{}", query.column_index()),
+        &|_| "challenge".to_owned(),
+        &|a| format!("-{}", a),
+        &|a, b| format!("({} + {})", a, b),
+        &|a, b| format!("{} * {}", a, b),
+        &|a, scalar| format!("{} * {:?}", a, scalar),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ApSumCircuit;
+    use halo2_proofs::halo2curves::pasta::Fp;
+
+    #[test]
+    fn gate_polynomials_name_the_gate_cells() {
+        let polynomials = ApSumCircuit::<1, 5>::gate_polynomials::<Fp>();
+        assert_eq!(polynomials.len(), 3);
+        // s * (sum == a + prev_sum), s * (a == prev_a + STEP), s * (a_0 == sum_0)
+        assert_eq!(polynomials[0], "s * ((a + prev_sum) + -sum)");
+        assert_eq!(polynomials[1], "s * ((a + -prev_a) + -STEP)");
+        assert_eq!(polynomials[2], "s * (a + -sum)");
+        for name in ["a", "prev_sum", "sum", "STEP"] {
+            assert!(polynomials.iter().any(|p| p.contains(name)));
+        }
+    }
+
+    #[test]
+    fn dump_table_has_header_and_one_line_per_row() {
+        let dump = dump_table(1, 1, 3).unwrap();
+        let lines: Vec<_> = dump.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "row | a_n | sum_n | first_term_selector | sum_selector | step_selector",
+                "----+-----+-------+---------------------+--------------+--------------",
+                "  0 |   1 |     1 |                   s |              |",
+                "  1 |   2 |     3 |                     |            s |             s",
+                "  2 |   3 |     6 |                     |            s |             s",
+            ]
+        );
+        assert!(matches!(
+            dump_table(u128::MAX, 1, 2),
+            Err(ApSumError::Overflow)
+        ));
+    }
+
+    #[test]
+    fn ap_sum_layout_matches_snapshot() {
+        let snapshot = include_str!("../tests/snapshots/ap_sum_layout.txt");
+        assert_eq!(describe::<Fp, ApSumCircuit<1, 5>>(), snapshot);
+    }
+}
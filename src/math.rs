@@ -0,0 +1,76 @@
+//! Closed-form sums of arithmetic progressions, computed outside any circuit.
+
+use crate::ApSumError;
+use halo2_proofs::arithmetic::FieldExt;
+
+/// Computes the integer sum of the `count`-term progression `first, first + step, ...`.
+pub fn ap_sum(first: u128, step: u128, count: usize) -> Result<u128, ApSumError> {
+    let count = count as u128;
+    // count * first + step * count * (count - 1) / 2
+    let steps = (count * count.saturating_sub(1) / 2)
+        .checked_mul(step)
+        .ok_or(ApSumError::Overflow)?;
+    count
+        .checked_mul(first)
+        .and_then(|base| base.checked_add(steps))
+        .ok_or(ApSumError::Overflow)
+}
+
+/// Computes the sum of the `count`-term progression `first, first + step, ...` with
+/// signed terms, mapped into `F` so that a negative sum `-s` becomes `-F::from(s)`.
+///
+/// Use this to build instances for descending progressions.
+pub fn ap_sum_signed<F: FieldExt>(first: i128, step: i128, count: usize) -> Result<F, ApSumError> {
+    let count = i128::try_from(count).map_err(|_| ApSumError::Overflow)?;
+    // count * first + step * count * (count - 1) / 2
+    let sum = count
+        .checked_mul((count - 1).max(0))
+        .and_then(|pairs| (pairs / 2).checked_mul(step))
+        .and_then(|steps| count.checked_mul(first)?.checked_add(steps))
+        .ok_or(ApSumError::Overflow)?;
+    let magnitude = F::from_u128(sum.unsigned_abs());
+    Ok(if sum < 0 { -magnitude } else { magnitude })
+}
+
+/// Computes the sum of the `count`-term progression `first, first + step, ...` entirely in
+/// `F`, dividing by two with the field inverse.
+///
+/// Unlike [`ap_sum`], this handles terms anywhere in the field, e.g. near the modulus.
+#[must_use]
+pub fn ap_sum_field<F: FieldExt>(first: F, step: F, count: usize) -> F {
+    let count = F::from(count as u64);
+    // count * first + step * count * (count - 1) / 2
+    count * first + step * count * (count - F::one()) * F::TWO_INV
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::halo2curves::pasta::Fp;
+
+    #[test]
+    fn ap_sum_matches_closed_form() {
+        assert_eq!(ap_sum(1, 1, 5).unwrap(), 15);
+        assert_eq!(ap_sum(1, 3, 4).unwrap(), 22);
+        assert_eq!(ap_sum(7, 2, 0).unwrap(), 0);
+        assert!(matches!(ap_sum(u128::MAX, 1, 2), Err(ApSumError::Overflow)));
+    }
+
+    #[test]
+    fn ap_sum_signed_maps_negative_values_into_the_field() {
+        // 10 + 7 + 4 + 1 = 22
+        assert_eq!(ap_sum_signed::<Fp>(10, -3, 4).unwrap(), Fp::from(22));
+        // 1 - 2 - 5 - 8 = -14
+        assert_eq!(ap_sum_signed::<Fp>(1, -3, 4).unwrap(), -Fp::from(14));
+    }
+
+    #[test]
+    fn ap_sum_field_matches_iterative_sum() {
+        // Terms near the modulus, which u128 arithmetic cannot represent.
+        let first = -Fp::from(3);
+        let step = Fp::from(u64::MAX);
+        let iterative = (0..5u64).fold(Fp::zero(), |sum, n| sum + first + step * Fp::from(n));
+        assert_eq!(ap_sum_field(first, step, 5), iterative);
+        assert_eq!(ap_sum_field(Fp::from(1), Fp::from(1), 5), Fp::from(15));
+    }
+}
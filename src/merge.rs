@@ -0,0 +1,392 @@
+//! Sum of the sorted merge of two arithmetic progressions.
+//!
+//! The merged sequence interleaves the terms of `x_n = first_x + n * STEP_X` (`COUNT_X`
+//! terms) and `y_n = first_y + n * STEP_Y` (`COUNT_Y` terms). Each row takes the next
+//! unconsumed term of one progression, chosen by a witnessed `from_y` bit, and advances
+//! only that progression's cursor; a final count check ensures both progressions are
+//! consumed exactly. Each consecutive pair of merged terms is then proven non-decreasing
+//! with a range-checked comparison, so the merge is globally sorted.
+//!
+//! Merged terms must be below `2^RANGE_BITS`.
+//!
+//! Instance layout: `[first_x, first_y, sum]`.
+
+use crate::{range::ComparisonConfig, ApSumError};
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+use std::marker::PhantomData;
+
+/// Bits of the range check bounding each gap between merged terms.
+pub const RANGE_BITS: usize = 8;
+
+#[derive(Clone, Debug)]
+pub struct MergeApSumConfig {
+    // [m_n, sum_n, from_y_n, x_n, y_n, taken_y_n]
+    advice: [Column<Advice>; 6],
+    s_row: Selector,
+    s_first: Selector,
+    s_next: Selector,
+    s_last: Selector,
+    comparison: ComparisonConfig<RANGE_BITS>,
+    instance: Column<Instance>,
+}
+
+struct MergeApSumChip<
+    F,
+    const STEP_X: u128,
+    const COUNT_X: usize,
+    const STEP_Y: u128,
+    const COUNT_Y: usize,
+> {
+    config: MergeApSumConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<
+        F: FieldExt,
+        const STEP_X: u128,
+        const COUNT_X: usize,
+        const STEP_Y: u128,
+        const COUNT_Y: usize,
+    > MergeApSumChip<F, STEP_X, COUNT_X, STEP_Y, COUNT_Y>
+{
+    fn construct(config: MergeApSumConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 6],
+        instance: Column<Instance>,
+    ) -> MergeApSumConfig {
+        let s_row = meta.selector();
+        let s_first = meta.selector();
+        let s_next = meta.selector();
+        let s_last = meta.selector();
+
+        for column in &advice[..5] {
+            meta.enable_equality(*column);
+        }
+        meta.enable_equality(instance);
+
+        // | advice[0] | advice[1] | advice[2] | advice[3] | advice[4] | advice[5] |
+        // |    m_n    |   sum_n   | from_y_n  |    x_n    |    y_n    | taken_y_n |
+        // -------------------------------------------------------------------------
+        // every row: from_y is boolean, m == from_y ? y : x              (s_row)
+        // row 0:     sum == m, taken_y == from_y                         (s_first)
+        // row n:     x, y advance by the step of the previous pick,
+        //            sum += m, taken_y += from_y                         (s_next)
+        // last row:  taken_y == COUNT_Y                                  (s_last)
+        meta.create_gate("pick", |meta| {
+            let m = meta.query_advice(advice[0], Rotation::cur());
+            let from_y = meta.query_advice(advice[2], Rotation::cur());
+            let x = meta.query_advice(advice[3], Rotation::cur());
+            let y = meta.query_advice(advice[4], Rotation::cur());
+            let s = meta.query_selector(s_row);
+            vec![
+                s.clone() * from_y.clone() * (Expression::Constant(F::one()) - from_y.clone()),
+                // m == x + from_y * (y - x)
+                s * (x.clone() + from_y * (y - x) - m),
+            ]
+        });
+
+        meta.create_gate("first row", |meta| {
+            let m = meta.query_advice(advice[0], Rotation::cur());
+            let sum = meta.query_advice(advice[1], Rotation::cur());
+            let from_y = meta.query_advice(advice[2], Rotation::cur());
+            let taken_y = meta.query_advice(advice[5], Rotation::cur());
+            let s = meta.query_selector(s_first);
+            vec![s.clone() * (m - sum), s * (from_y - taken_y)]
+        });
+
+        meta.create_gate("advance and sum", |meta| {
+            let m = meta.query_advice(advice[0], Rotation::cur());
+            let sum = meta.query_advice(advice[1], Rotation::cur());
+            let from_y = meta.query_advice(advice[2], Rotation::cur());
+            let x = meta.query_advice(advice[3], Rotation::cur());
+            let y = meta.query_advice(advice[4], Rotation::cur());
+            let taken_y = meta.query_advice(advice[5], Rotation::cur());
+            let prev_sum = meta.query_advice(advice[1], Rotation::prev());
+            let prev_from_y = meta.query_advice(advice[2], Rotation::prev());
+            let prev_x = meta.query_advice(advice[3], Rotation::prev());
+            let prev_y = meta.query_advice(advice[4], Rotation::prev());
+            let prev_taken_y = meta.query_advice(advice[5], Rotation::prev());
+            let s = meta.query_selector(s_next);
+            vec![
+                // x == prev_x + (1 - prev_from_y) * STEP_X
+                s.clone()
+                    * (prev_x
+                        + (Expression::Constant(F::one()) - prev_from_y.clone())
+                            * Expression::Constant(F::from_u128(STEP_X))
+                        - x),
+                // y == prev_y + prev_from_y * STEP_Y
+                s.clone() * (prev_y + prev_from_y * Expression::Constant(F::from_u128(STEP_Y)) - y),
+                s.clone() * (prev_sum + m - sum),
+                s * (prev_taken_y + from_y - taken_y),
+            ]
+        });
+
+        meta.create_gate("both consumed", |meta| {
+            let taken_y = meta.query_advice(advice[5], Rotation::cur());
+            let s = meta.query_selector(s_last);
+            vec![s * (taken_y - Expression::Constant(F::from(COUNT_Y as u64)))]
+        });
+
+        // Comparisons reuse the m, sum and from_y columns in their own regions.
+        let comparison = ComparisonConfig::configure(meta, [advice[0], advice[1]], advice[2]);
+
+        MergeApSumConfig {
+            advice,
+            s_row,
+            s_first,
+            s_next,
+            s_last,
+            comparison,
+            instance,
+        }
+    }
+
+    /// Assigns the merge table, returning the merged term cells and the sum cell.
+    fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        order: &[Value<bool>],
+    ) -> Result<(Vec<AssignedCell<F, F>>, AssignedCell<F, F>), Error> {
+        layouter.assign_region(
+            || "merge AP sum table",
+            |mut region| {
+                // Both progressions empty leave no row for the final count check.
+                let last_row = order.len().checked_sub(1).ok_or(Error::Synthesis)?;
+                let [m_column, sum_column, from_y_column, x_column, y_column, taken_y_column] =
+                    self.config.advice;
+                let (step_x, step_y) = (F::from_u128(STEP_X), F::from_u128(STEP_Y));
+
+                let mut x_cell = region.assign_advice_from_instance(
+                    || "x",
+                    self.config.instance,
+                    0,
+                    x_column,
+                    0,
+                )?;
+                let mut y_cell = region.assign_advice_from_instance(
+                    || "y",
+                    self.config.instance,
+                    1,
+                    y_column,
+                    0,
+                )?;
+
+                let mut terms = Vec::with_capacity(order.len());
+                let mut sum = Value::known(F::zero());
+                let mut taken_y = Value::known(F::zero());
+                let mut sum_cell = None;
+                for (row, from_y) in order.iter().enumerate() {
+                    self.config.s_row.enable(&mut region, row)?;
+                    if row == 0 {
+                        self.config.s_first.enable(&mut region, row)?;
+                    } else {
+                        self.config.s_next.enable(&mut region, row)?;
+                        let prev_from_y = order[row - 1];
+                        let x = x_cell.value().zip(prev_from_y).map(|(x, from_y)| {
+                            if from_y {
+                                *x
+                            } else {
+                                *x + step_x
+                            }
+                        });
+                        x_cell = region.assign_advice(|| "x", x_column, row, || x)?;
+                        let y = y_cell.value().zip(prev_from_y).map(|(y, from_y)| {
+                            if from_y {
+                                *y + step_y
+                            } else {
+                                *y
+                            }
+                        });
+                        y_cell = region.assign_advice(|| "y", y_column, row, || y)?;
+                    }
+
+                    let bit = from_y.map(|from_y| F::from(u64::from(from_y)));
+                    region.assign_advice(|| "from_y", from_y_column, row, || bit)?;
+                    let m = x_cell
+                        .value()
+                        .zip(y_cell.value())
+                        .zip(*from_y)
+                        .map(|((x, y), from_y)| if from_y { *y } else { *x });
+                    terms.push(region.assign_advice(|| "m", m_column, row, || m)?);
+
+                    sum = sum + m;
+                    sum_cell = Some(region.assign_advice(|| "sum", sum_column, row, || sum)?);
+                    taken_y = taken_y + bit;
+                    region.assign_advice(|| "taken_y", taken_y_column, row, || taken_y)?;
+                }
+                self.config.s_last.enable(&mut region, last_row)?;
+
+                Ok((terms, sum_cell.ok_or(Error::Synthesis)?))
+            },
+        )
+    }
+
+    /// Constrains each merged term to be at most the next one.
+    fn assert_sorted(
+        &self,
+        mut layouter: impl Layouter<F>,
+        terms: &[AssignedCell<F, F>],
+    ) -> Result<(), Error> {
+        for (index, pair) in terms.windows(2).enumerate() {
+            self.config.comparison.assert_less_or_equal(
+                layouter.namespace(|| format!("m_{index} <= m_{}", index + 1)),
+                &pair[0],
+                &pair[1],
+            )?;
+        }
+        Ok(())
+    }
+
+    fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+/// Proves the sum of the sorted merge of two progressions.
+///
+/// `order[n]` is `true` when the `n`-th merged term is taken from the `y` progression.
+pub struct MergeApSumCircuit<
+    const STEP_X: u128,
+    const COUNT_X: usize,
+    const STEP_Y: u128,
+    const COUNT_Y: usize,
+> {
+    pub order: Vec<Value<bool>>,
+}
+
+impl<const STEP_X: u128, const COUNT_X: usize, const STEP_Y: u128, const COUNT_Y: usize>
+    MergeApSumCircuit<STEP_X, COUNT_X, STEP_Y, COUNT_Y>
+{
+    /// Builds the witness for the sorted merge of the progressions starting at `first_x`
+    /// and `first_y`, taking from `x` on ties.
+    ///
+    /// Returns [`ApSumError::Overflow`] if a compared term does not fit in a `u128`.
+    pub fn sorted(first_x: u128, first_y: u128) -> Result<Self, ApSumError> {
+        let term = |first: u128, taken: usize, step: u128| {
+            (taken as u128)
+                .checked_mul(step)
+                .and_then(|steps| steps.checked_add(first))
+                .ok_or(ApSumError::Overflow)
+        };
+        let (mut taken_x, mut taken_y) = (0, 0);
+        let order = (0..COUNT_X + COUNT_Y)
+            .map(|_| {
+                let from_y = if taken_x == COUNT_X {
+                    true
+                } else if taken_y == COUNT_Y {
+                    false
+                } else {
+                    term(first_y, taken_y, STEP_Y)? < term(first_x, taken_x, STEP_X)?
+                };
+                if from_y {
+                    taken_y += 1;
+                } else {
+                    taken_x += 1;
+                }
+                Ok(Value::known(from_y))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { order })
+    }
+}
+
+impl<
+        F: FieldExt,
+        const STEP_X: u128,
+        const COUNT_X: usize,
+        const STEP_Y: u128,
+        const COUNT_Y: usize,
+    > Circuit<F> for MergeApSumCircuit<STEP_X, COUNT_X, STEP_Y, COUNT_Y>
+{
+    type Config = MergeApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            order: vec![Value::unknown(); COUNT_X + COUNT_Y],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [(); 6].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+
+        MergeApSumChip::<_, STEP_X, COUNT_X, STEP_Y, COUNT_Y>::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        if self.order.len() != COUNT_X + COUNT_Y {
+            return Err(Error::Synthesis);
+        }
+        let chip = MergeApSumChip::<_, STEP_X, COUNT_X, STEP_Y, COUNT_Y>::construct(config.clone());
+        config
+            .comparison
+            .load_table(layouter.namespace(|| "range table"))?;
+        let (terms, sum) = chip.assign(layouter.namespace(|| "merge AP sum table"), &self.order)?;
+        chip.assert_sorted(layouter.namespace(|| "sorted"), &terms)?;
+        chip.expose_public(layouter.namespace(|| "output"), &sum, 2)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, halo2curves::pasta::Fp};
+
+    type Merge = MergeApSumCircuit<2, 3, 2, 3>;
+
+    #[test]
+    fn odds_and_evens_merge_sorted() {
+        let k = 9;
+        // [1, 3, 5] and [2, 4, 6] merge into [1, 2, 3, 4, 5, 6], summing to 21
+        let circuit = Merge::sorted(1, 2).unwrap();
+        let instance = vec![Fp::from(1), Fp::from(2), Fp::from(21)];
+        let prover = MockProver::run(k, &circuit, vec![instance]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn unsorted_merge_is_rejected() {
+        let k = 9;
+        // [1, 3, 5, 2, 4, 6] has the right terms and sum but is not sorted
+        let circuit = Merge {
+            order: [false, false, false, true, true, true]
+                .map(Value::known)
+                .to_vec(),
+        };
+        let instance = vec![Fp::from(1), Fp::from(2), Fp::from(21)];
+        let prover = MockProver::run(k, &circuit, vec![instance]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn bad_inputs_are_errors_not_panics() {
+        let circuit = Merge {
+            order: vec![Value::known(false); 5],
+        };
+        let instance = vec![Fp::from(1), Fp::from(2), Fp::from(21)];
+        assert!(MockProver::run(9, &circuit, vec![instance]).is_err());
+
+        assert!(matches!(
+            Merge::sorted(u128::MAX, u128::MAX),
+            Err(ApSumError::Overflow)
+        ));
+    }
+}
@@ -0,0 +1,299 @@
+//! Proof that the AP sum is a leaf of a public Poseidon Merkle tree.
+//!
+//! Internal nodes are `Poseidon(left, right)` as computed by [`crate::commitment::hash_two`].
+//! The circuit computes the sum as [`crate::ApSumCircuit`] does and walks a witnessed
+//! authentication path up to the root, which is constrained to the instance. The path and
+//! the leaf position stay private, so the proof only shows the sum is one of the leaves.
+//!
+//! Instance layout: `[first, root]`.
+
+use crate::{
+    commitment::{assign_hash_two, configure_poseidon, hash_two},
+    ApSumChip, ApSumConfig,
+};
+use halo2_gadgets::poseidon::Pow5Config;
+use halo2_proofs::{circuit::*, halo2curves::pasta::Fp, plonk::*, poly::Rotation};
+
+/// A Poseidon Merkle tree over a power-of-two number of leaves.
+#[derive(Clone, Debug)]
+pub struct MerkleTree {
+    // levels[0] are the leaves, the last level is the root
+    levels: Vec<Vec<Fp>>,
+}
+
+impl MerkleTree {
+    /// Builds the tree over `leaves`, or returns `None` if the number of leaves is not a
+    /// power of two.
+    pub fn new(leaves: Vec<Fp>) -> Option<Self> {
+        if !leaves.len().is_power_of_two() {
+            return None;
+        }
+        let mut levels = vec![leaves];
+        while levels[levels.len() - 1].len() > 1 {
+            let next = levels[levels.len() - 1]
+                .chunks(2)
+                .map(|pair| hash_two(pair[0], pair[1]))
+                .collect();
+            levels.push(next);
+        }
+        Some(Self { levels })
+    }
+
+    pub fn leaf(&self, index: usize) -> Fp {
+        self.levels[0][index]
+    }
+
+    pub fn root(&self) -> Fp {
+        self.levels[self.levels.len() - 1][0]
+    }
+
+    /// Number of hashes from a leaf to the root.
+    pub fn depth(&self) -> usize {
+        self.levels.len() - 1
+    }
+
+    /// Returns the authentication path of the leaf at `index`, from the leaf level up.
+    ///
+    /// Each entry is the sibling node and whether the current node is the right child.
+    pub fn path(&self, index: usize) -> Vec<(Fp, bool)> {
+        let mut index = index;
+        self.levels[..self.depth()]
+            .iter()
+            .map(|level| {
+                let entry = (level[index ^ 1], index & 1 == 1);
+                index >>= 1;
+                entry
+            })
+            .collect()
+    }
+}
+
+/// Which child of its parent a node on an authentication path is.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Position {
+    /// Witnessed, so the leaf index stays private.
+    Witnessed(Value<bool>),
+    /// Assigned from a constant, binding the path to a known leaf index.
+    Constant(bool),
+}
+
+/// Columns and gate for walking an authentication path up to its root.
+#[derive(Clone, Debug)]
+pub(crate) struct MerklePathConfig {
+    poseidon: Pow5Config<Fp, 3, 2>,
+    // [cur, sibling, is_right, left, right]
+    swap: [Column<Advice>; 5],
+    s_swap: Selector,
+}
+
+impl MerklePathConfig {
+    pub(crate) fn configure(meta: &mut ConstraintSystem<Fp>) -> Self {
+        let poseidon = configure_poseidon(meta);
+        let swap = [(); 5].map(|_| meta.advice_column());
+        let s_swap = meta.selector();
+        for column in [swap[0], swap[2], swap[3], swap[4]] {
+            meta.enable_equality(column);
+        }
+
+        // |  cur  | sibling | is_right | left | right | s_swap
+        // ------------------------------------------------------
+        // |   c   |    s    |    b     |  l   |   r   |   s
+        //
+        // (l, r) == (c, s) if b == 0, (s, c) if b == 1
+        meta.create_gate("conditional swap", |meta| {
+            let cur = meta.query_advice(swap[0], Rotation::cur());
+            let sibling = meta.query_advice(swap[1], Rotation::cur());
+            let bit = meta.query_advice(swap[2], Rotation::cur());
+            let left = meta.query_advice(swap[3], Rotation::cur());
+            let right = meta.query_advice(swap[4], Rotation::cur());
+            let s = meta.query_selector(s_swap);
+            vec![
+                s.clone() * bit.clone() * (Expression::Constant(Fp::one()) - bit.clone()),
+                s.clone() * (cur.clone() + bit.clone() * (sibling.clone() - cur.clone()) - left),
+                s * (sibling.clone() + bit * (cur - sibling) - right),
+            ]
+        });
+
+        Self {
+            poseidon,
+            swap,
+            s_swap,
+        }
+    }
+
+    /// Hashes `cur` with `sibling` in the order given by `is_right`, returning the parent.
+    pub(crate) fn assign_level(
+        &self,
+        mut layouter: impl Layouter<Fp>,
+        cur: &AssignedCell<Fp, Fp>,
+        sibling: Value<Fp>,
+        is_right: Position,
+    ) -> Result<AssignedCell<Fp, Fp>, Error> {
+        let [cur_column, sibling_column, bit_column, left_column, right_column] = self.swap;
+        let (left, right) = layouter.assign_region(
+            || "conditional swap",
+            |mut region| {
+                self.s_swap.enable(&mut region, 0)?;
+                let cur = cur.copy_advice(|| "cur", &mut region, cur_column, 0)?;
+                region.assign_advice(|| "sibling", sibling_column, 0, || sibling)?;
+                let is_right = match is_right {
+                    Position::Witnessed(is_right) => {
+                        region.assign_advice(
+                            || "is_right",
+                            bit_column,
+                            0,
+                            || is_right.map(|b| Fp::from(u64::from(b))),
+                        )?;
+                        is_right
+                    }
+                    Position::Constant(is_right) => {
+                        region.assign_advice_from_constant(
+                            || "is_right",
+                            bit_column,
+                            0,
+                            Fp::from(u64::from(is_right)),
+                        )?;
+                        Value::known(is_right)
+                    }
+                };
+
+                let ordered = cur.value().copied().zip(sibling).zip(is_right).map(
+                    |((cur, sibling), is_right)| {
+                        if is_right {
+                            (sibling, cur)
+                        } else {
+                            (cur, sibling)
+                        }
+                    },
+                );
+                let left =
+                    region.assign_advice(|| "left", left_column, 0, || ordered.map(|o| o.0))?;
+                let right =
+                    region.assign_advice(|| "right", right_column, 0, || ordered.map(|o| o.1))?;
+                Ok((left, right))
+            },
+        )?;
+
+        assign_hash_two(&self.poseidon, layouter.namespace(|| "hash"), [left, right])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MerkleApSumConfig {
+    ap_sum: ApSumConfig,
+    path: MerklePathConfig,
+}
+
+/// Proves the AP sum is a leaf of a Merkle tree of depth `DEPTH`.
+pub struct MerkleApSumCircuit<const STEP: u128, const COUNT: usize, const DEPTH: usize> {
+    pub siblings: [Value<Fp>; DEPTH],
+    /// Whether the node at each level is the right child of its parent.
+    pub positions: [Value<bool>; DEPTH],
+}
+
+impl<const STEP: u128, const COUNT: usize, const DEPTH: usize>
+    MerkleApSumCircuit<STEP, COUNT, DEPTH>
+{
+    /// Builds the circuit from a path returned by [`MerkleTree::path`], or returns `None`
+    /// if the path length is not `DEPTH`.
+    pub fn from_path(path: &[(Fp, bool)]) -> Option<Self> {
+        if path.len() != DEPTH {
+            return None;
+        }
+        let mut circuit = Self::default();
+        for (level, (sibling, is_right)) in path.iter().enumerate() {
+            circuit.siblings[level] = Value::known(*sibling);
+            circuit.positions[level] = Value::known(*is_right);
+        }
+        Some(circuit)
+    }
+}
+
+impl<const STEP: u128, const COUNT: usize, const DEPTH: usize> Default
+    for MerkleApSumCircuit<STEP, COUNT, DEPTH>
+{
+    fn default() -> Self {
+        Self {
+            siblings: [Value::unknown(); DEPTH],
+            positions: [Value::unknown(); DEPTH],
+        }
+    }
+}
+
+impl<const STEP: u128, const COUNT: usize, const DEPTH: usize> Circuit<Fp>
+    for MerkleApSumCircuit<STEP, COUNT, DEPTH>
+{
+    type Config = MerkleApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let ap_sum = ApSumChip::<_, STEP, COUNT>::configure(meta, advice, instance);
+        let path = MerklePathConfig::configure(meta);
+
+        MerkleApSumConfig { ap_sum, path }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fp>,
+    ) -> Result<(), Error> {
+        let chip = ApSumChip::<_, STEP, COUNT>::construct(config.ap_sum.clone());
+        let cells = chip.assign(layouter.namespace(|| "AP sum table"))?;
+
+        let mut node = cells.sum.into_inner();
+        for (level, (sibling, is_right)) in self.siblings.iter().zip(&self.positions).enumerate() {
+            node = config.path.assign_level(
+                layouter.namespace(|| format!("level {}", level)),
+                &node,
+                *sibling,
+                Position::Witnessed(*is_right),
+            )?;
+        }
+        chip.expose_public(layouter.namespace(|| "root"), &node, 1)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::dev::MockProver;
+
+    fn sums_tree() -> MerkleTree {
+        MerkleTree::new([10, 15, 21, 28].map(Fp::from).to_vec()).unwrap()
+    }
+
+    #[test]
+    fn sum_fifteen_is_a_member() {
+        let k = 8;
+        let tree = sums_tree();
+        // 1 + 2 + 3 + 4 + 5 = 15 is the leaf at index 1
+        let circuit = MerkleApSumCircuit::<1, 5, 2>::from_path(&tree.path(1)).unwrap();
+        let instance = vec![Fp::from(1), tree.root()];
+        let prover = MockProver::run(k, &circuit, vec![instance]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn malformed_tree_and_path_are_none() {
+        assert!(MerkleTree::new([10, 15, 21].map(Fp::from).to_vec()).is_none());
+        assert!(MerkleApSumCircuit::<1, 5, 3>::from_path(&sums_tree().path(1)).is_none());
+    }
+
+    #[test]
+    fn path_of_another_leaf_is_rejected() {
+        let k = 8;
+        let tree = sums_tree();
+        let circuit = MerkleApSumCircuit::<1, 5, 2>::from_path(&tree.path(2)).unwrap();
+        let instance = vec![Fp::from(1), tree.root()];
+        let prover = MockProver::run(k, &circuit, vec![instance]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,226 @@
+//! AP sum that also exposes the smallest and largest term.
+//!
+//! The step is a signed const generic. Terms are monotonic, so for a non-negative step
+//! the minimum is `a_0` and the maximum `a_{COUNT-1}`, and for a negative step the two
+//! swap. [`MinMaxApSumChip::expose_min_max`] copy-constrains the matching end cells to
+//! the instance, so neither is recomputed in-circuit. A negative term is the field
+//! element `-|a_n|`, as for [`crate::ap_sum_signed`].
+//!
+//! Instance layout: `[first, sum, min, max]`.
+
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+use std::marker::PhantomData;
+
+/// Maps a signed integer into the field, negatives as `-|value|`.
+fn signed<F: FieldExt>(value: i128) -> F {
+    let magnitude = F::from_u128(value.unsigned_abs());
+    if value < 0 {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MinMaxApSumConfig {
+    // [a_n, sum_n]
+    advice: [Column<Advice>; 2],
+    selector: Selector,
+    instance: Column<Instance>,
+}
+
+/// Cells at the two ends of an assigned table.
+struct MinMaxCells<F: FieldExt> {
+    first_term: AssignedCell<F, F>,
+    last_term: AssignedCell<F, F>,
+    sum: AssignedCell<F, F>,
+}
+
+struct MinMaxApSumChip<F, const STEP: i128, const COUNT: usize> {
+    config: MinMaxApSumConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const STEP: i128, const COUNT: usize> MinMaxApSumChip<F, STEP, COUNT> {
+    fn construct(config: MinMaxApSumConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 2],
+        instance: Column<Instance>,
+    ) -> MinMaxApSumConfig {
+        let selector = meta.selector();
+
+        meta.enable_equality(advice[0]);
+        meta.enable_equality(advice[1]);
+        meta.enable_equality(instance);
+
+        // |  advice[0]   |    advice[1]   | selector
+        // ------------------------------------------
+        // |     a_0      |     sum_0      |
+        // |     a_1      |     sum_1      |    s
+        // |     ...      |     ...        |    s
+        meta.create_gate("signed step and sum", |meta| {
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let sum = meta.query_advice(advice[1], Rotation::cur());
+            let prev_a = meta.query_advice(advice[0], Rotation::prev());
+            let prev_sum = meta.query_advice(advice[1], Rotation::prev());
+            let s = meta.query_selector(selector);
+            vec![
+                // sum == a + prev_sum
+                s.clone() * (a.clone() + prev_sum - sum),
+                // a == prev_a + STEP
+                s * (a - prev_a - Expression::Constant(signed::<F>(STEP))),
+            ]
+        });
+
+        MinMaxApSumConfig {
+            advice,
+            selector,
+            instance,
+        }
+    }
+
+    fn assign(&self, mut layouter: impl Layouter<F>) -> Result<MinMaxCells<F>, Error> {
+        layouter.assign_region(
+            || "signed AP sum table",
+            |mut region| {
+                let a_column = self.config.advice[0];
+                let sum_column = self.config.advice[1];
+                let step = signed::<F>(STEP);
+
+                // Copy first instance into both a_0 and sum_0. No selector needed for first row.
+                let first_term = region.assign_advice_from_instance(
+                    || "a",
+                    self.config.instance,
+                    0,
+                    a_column,
+                    0,
+                )?;
+                let mut sum_cell = region.assign_advice_from_instance(
+                    || "sum",
+                    self.config.instance,
+                    0,
+                    sum_column,
+                    0,
+                )?;
+
+                let mut a_cell = first_term.clone();
+                for row in 1..COUNT {
+                    self.config.selector.enable(&mut region, row)?;
+
+                    let new_a_val = a_cell.value().map(|a| *a + step);
+                    a_cell = region.assign_advice(|| "a", a_column, row, || new_a_val)?;
+
+                    let new_sum = sum_cell.value().copied() + new_a_val;
+                    sum_cell = region.assign_advice(|| "sum", sum_column, row, || new_sum)?;
+                }
+
+                Ok(MinMaxCells {
+                    first_term,
+                    last_term: a_cell,
+                    sum: sum_cell,
+                })
+            },
+        )
+    }
+
+    fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+
+    /// Constrains the smallest term to `min_row` and the largest to `max_row`, taking the
+    /// step's sign into account.
+    fn expose_min_max(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cells: &MinMaxCells<F>,
+        min_row: usize,
+        max_row: usize,
+    ) -> Result<(), Error> {
+        let (min, max) = if STEP < 0 {
+            (&cells.last_term, &cells.first_term)
+        } else {
+            (&cells.first_term, &cells.last_term)
+        };
+        self.expose_public(layouter.namespace(|| "min"), min, min_row)?;
+        self.expose_public(layouter.namespace(|| "max"), max, max_row)
+    }
+}
+
+#[derive(Default)]
+pub struct MinMaxApSumCircuit<const STEP: i128, const COUNT: usize>;
+
+impl<F: FieldExt, const STEP: i128, const COUNT: usize> Circuit<F>
+    for MinMaxApSumCircuit<STEP, COUNT>
+{
+    type Config = MinMaxApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+
+        MinMaxApSumChip::<_, STEP, COUNT>::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = MinMaxApSumChip::<_, STEP, COUNT>::construct(config);
+        let cells = chip.assign(layouter.namespace(|| "signed AP sum table"))?;
+        chip.expose_public(layouter.namespace(|| "sum"), &cells.sum, 1)?;
+        chip.expose_min_max(layouter.namespace(|| "min and max"), &cells, 2, 3)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, halo2curves::pasta::Fp};
+
+    fn instance(first: u64, sum: u64, min: u64, max: u64) -> Vec<Vec<Fp>> {
+        vec![[first, sum, min, max].map(Fp::from).to_vec()]
+    }
+
+    #[test]
+    fn step_two_count_four_exposes_min_one_and_max_seven() {
+        let k = 4;
+        let circuit = MinMaxApSumCircuit::<2, 4>;
+        // 1 + 3 + 5 + 7 = 16
+        let prover = MockProver::run(k, &circuit, instance(1, 16, 1, 7)).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(k, &circuit, instance(1, 16, 7, 1)).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn negative_step_swaps_min_and_max() {
+        let k = 4;
+        let circuit = MinMaxApSumCircuit::<-2, 4>;
+        // 7 + 5 + 3 + 1 = 16
+        let prover = MockProver::run(k, &circuit, instance(7, 16, 1, 7)).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(k, &circuit, instance(7, 16, 7, 1)).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
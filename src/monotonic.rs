@@ -0,0 +1,159 @@
+//! AP sum with a range-checked proof that the terms strictly increase.
+//!
+//! The step gate alone accepts any `STEP` that is valid in the field, including one that
+//! is "negative", i.e. wraps around the modulus. This circuit additionally proves
+//! `a_{n-1} < a_n` for every pair of consecutive terms by range-checking
+//! `a_n - a_{n-1} - 1` to `[0, 2^RANGE_BITS)`, so every step must lie in
+//! `[1, 2^RANGE_BITS]`.
+//!
+//! Instance layout: `[first, sum]`.
+
+use crate::{range::ComparisonConfig, ApSumChip, ApSumConfig};
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*};
+
+/// Bits of the range check bounding each step.
+pub const RANGE_BITS: usize = 8;
+
+#[derive(Clone, Debug)]
+pub struct MonotonicApSumConfig {
+    ap_sum: ApSumConfig,
+    comparison: ComparisonConfig<RANGE_BITS>,
+}
+
+/// Constrains each of `terms` to be strictly less than the next one.
+fn assert_increasing<F: FieldExt>(
+    comparison: &ComparisonConfig<RANGE_BITS>,
+    mut layouter: impl Layouter<F>,
+    terms: &[AssignedCell<F, F>],
+) -> Result<(), Error> {
+    for (index, pair) in terms.windows(2).enumerate() {
+        comparison.assert_less_than(
+            layouter.namespace(|| format!("a_{} < a_{}", index, index + 1)),
+            &pair[0],
+            &pair[1],
+        )?;
+    }
+    Ok(())
+}
+
+fn configure_comparison<F: FieldExt>(
+    meta: &mut ConstraintSystem<F>,
+    advice: [Column<Advice>; 2],
+) -> ComparisonConfig<RANGE_BITS> {
+    let diff = meta.advice_column();
+    ComparisonConfig::configure(meta, advice, diff)
+}
+
+#[derive(Default)]
+pub struct MonotonicApSumCircuit<const STEP: u128, const COUNT: usize>;
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> Circuit<F>
+    for MonotonicApSumCircuit<STEP, COUNT>
+{
+    type Config = MonotonicApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+
+        MonotonicApSumConfig {
+            ap_sum: ApSumChip::<_, STEP, COUNT>::configure(meta, advice, instance),
+            comparison: configure_comparison(meta, advice),
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = ApSumChip::<_, STEP, COUNT>::construct(config.ap_sum);
+        config
+            .comparison
+            .load_table(layouter.namespace(|| "range table"))?;
+        let cells = chip.assign(layouter.namespace(|| "AP sum table"))?;
+        assert_increasing(
+            &config.comparison,
+            layouter.namespace(|| "monotonicity"),
+            &cells.terms,
+        )?;
+        chip.expose_public(layouter.namespace(|| "output"), cells.sum.assigned(), 1)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, halo2curves::pasta::Fp};
+
+    /// Feeds arbitrary terms to the monotonicity check alone.
+    #[derive(Default)]
+    struct TermsCircuit {
+        terms: Vec<Value<Fp>>,
+    }
+
+    impl Circuit<Fp> for TermsCircuit {
+        type Config = ([Column<Advice>; 2], ComparisonConfig<RANGE_BITS>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                terms: vec![Value::unknown(); self.terms.len()],
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let advice = [meta.advice_column(), meta.advice_column()];
+            meta.enable_equality(advice[0]);
+            meta.enable_equality(advice[1]);
+            (advice, configure_comparison(meta, advice))
+        }
+
+        fn synthesize(
+            &self,
+            (advice, comparison): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            comparison.load_table(layouter.namespace(|| "range table"))?;
+            let terms = layouter.assign_region(
+                || "terms",
+                |mut region| {
+                    self.terms
+                        .iter()
+                        .enumerate()
+                        .map(|(row, term)| region.assign_advice(|| "a", advice[0], row, || *term))
+                        .collect::<Result<Vec<_>, _>>()
+                },
+            )?;
+            assert_increasing(&comparison, layouter.namespace(|| "monotonicity"), &terms)
+        }
+    }
+
+    #[test]
+    fn positive_step_is_increasing() {
+        let k = 9;
+        // 1 + 4 + 7 + 10 = 22
+        let circuit = MonotonicApSumCircuit::<3, 4>;
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(1), Fp::from(22)]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn wrapping_step_is_rejected() {
+        let k = 9;
+        // A step of -1, i.e. p - 1, passes a step gate but wraps each term downwards.
+        let step = -Fp::one();
+        let terms = [Fp::from(5), Fp::from(5) + step, Fp::from(5) + step + step];
+        let circuit = TermsCircuit {
+            terms: terms.iter().copied().map(Value::known).collect(),
+        };
+        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,68 @@
+//! AP sum whose gate is driven by a fixed column rather than a selector.
+//!
+//! Selectors are compressed into fixed columns by halo2 anyway; circuits that track their
+//! fixed-column budget explicitly can use this variant to see the column it costs. The
+//! fixed column is `1` on rows `1..COUNT` and `0` on row `0`.
+//!
+//! Instance layout: `[first, sum]`.
+
+use crate::{ApSumChip, ApSumConfig};
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*};
+
+#[derive(Default)]
+pub struct NoSelectorApSumCircuit<const STEP: u128, const COUNT: usize>;
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> Circuit<F>
+    for NoSelectorApSumCircuit<STEP, COUNT>
+{
+    type Config = ApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+
+        ApSumChip::<_, STEP, COUNT>::configure_with_fixed(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = ApSumChip::<_, STEP, COUNT>::construct(config);
+        let cells = chip.assign(layouter.namespace(|| "AP sum table"))?;
+        chip.expose_public(layouter.namespace(|| "output"), cells.sum.assigned(), 1)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ApSumCircuit;
+    use halo2_proofs::{dev::MockProver, halo2curves::pasta::Fp};
+
+    #[test]
+    fn fixed_column_matches_selector_variant() {
+        let k = 4;
+        // 1 + 2 + 3 + 4 + 5 = 15
+        for sum in [15, 16] {
+            let instance = vec![vec![Fp::from(1), Fp::from(sum)]];
+            let with_selector = MockProver::run(k, &ApSumCircuit::<1, 5>, instance.clone())
+                .unwrap()
+                .verify()
+                .is_ok();
+            let with_fixed = MockProver::run(k, &NoSelectorApSumCircuit::<1, 5>, instance)
+                .unwrap()
+                .verify()
+                .is_ok();
+            assert_eq!(with_fixed, with_selector);
+            assert_eq!(with_fixed, sum == 15);
+        }
+    }
+}
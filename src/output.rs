@@ -0,0 +1,141 @@
+//! AP sum with a choice of public output.
+//!
+//! [`OutputApSumCircuit`] exposes either the raw sum, as [`crate::ApSumCircuit`] does, or
+//! `Poseidon(first, sum)`, as [`crate::packed::PackedApSumCircuit`] does, depending on its
+//! [`OutputMode`]. The Poseidon chip is configured in both modes so the circuit has one
+//! constraint system; the mode only changes what is copied to the instance.
+//!
+//! Instance layout: `[first, sum]` or `[first, Poseidon(first, sum)]`.
+
+use crate::{
+    commitment::{assign_hash_two, configure_poseidon, hash_two},
+    ApSumChip, ApSumConfig,
+};
+use halo2_gadgets::poseidon::Pow5Config;
+use halo2_proofs::{circuit::*, halo2curves::pasta::Fp, plonk::*};
+
+/// The public value a circuit exposes in place of the sum.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputMode {
+    /// The sum itself.
+    #[default]
+    Raw,
+    /// `Poseidon(first, sum)`, which keeps the sum private.
+    Hashed,
+}
+
+impl OutputMode {
+    /// Builds the instance for a progression starting at `first` with sum `sum`.
+    pub fn instance(self, first: Fp, sum: Fp) -> Vec<Fp> {
+        match self {
+            OutputMode::Raw => vec![first, sum],
+            OutputMode::Hashed => vec![first, hash_two(first, sum)],
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct OutputApSumConfig {
+    ap_sum: ApSumConfig,
+    poseidon: Pow5Config<Fp, 3, 2>,
+}
+
+#[derive(Clone, Default)]
+pub struct OutputApSumCircuit<const STEP: u128, const COUNT: usize> {
+    mode: OutputMode,
+}
+
+impl<const STEP: u128, const COUNT: usize> OutputApSumCircuit<STEP, COUNT> {
+    /// Returns a circuit exposing the raw sum.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn output_mode(mut self, mode: OutputMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn mode(&self) -> OutputMode {
+        self.mode
+    }
+}
+
+impl<const STEP: u128, const COUNT: usize> Circuit<Fp> for OutputApSumCircuit<STEP, COUNT> {
+    type Config = OutputApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        // The mode shapes the copy constraints, not the witness.
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+
+        OutputApSumConfig {
+            ap_sum: ApSumChip::<_, STEP, COUNT>::configure_sum_only(meta, advice, instance),
+            poseidon: configure_poseidon(meta),
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fp>,
+    ) -> Result<(), Error> {
+        let chip = ApSumChip::<_, STEP, COUNT>::construct(config.ap_sum);
+        let cells = chip.assign(layouter.namespace(|| "AP sum table"))?;
+        let output = match self.mode {
+            OutputMode::Raw => cells.sum.into_inner(),
+            OutputMode::Hashed => {
+                // sum_0 is copied from the first instance row.
+                let first = cells.partial_sums[0].clone();
+                assign_hash_two(
+                    &config.poseidon,
+                    layouter.namespace(|| "hash output"),
+                    [first, cells.sum.into_inner()],
+                )?
+            }
+        };
+        chip.expose_public(layouter.namespace(|| "output"), &output, 1)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::dev::MockProver;
+
+    fn run(mode: OutputMode, instance: Vec<Fp>) -> bool {
+        let k = 7;
+        let circuit = OutputApSumCircuit::<1, 5>::new().output_mode(mode);
+        MockProver::run(k, &circuit, vec![instance])
+            .unwrap()
+            .verify()
+            .is_ok()
+    }
+
+    #[test]
+    fn raw_mode_exposes_the_sum() {
+        // 1 + 2 + 3 + 4 + 5 = 15
+        let instance = OutputMode::Raw.instance(Fp::from(1), Fp::from(15));
+        assert_eq!(instance, [Fp::from(1), Fp::from(15)]);
+        assert!(run(OutputMode::Raw, instance));
+        let hashed = OutputMode::Hashed.instance(Fp::from(1), Fp::from(15));
+        assert!(!run(OutputMode::Raw, hashed));
+    }
+
+    #[test]
+    fn hashed_mode_exposes_the_hash_of_first_and_sum() {
+        let instance = OutputMode::Hashed.instance(Fp::from(1), Fp::from(15));
+        assert_eq!(instance[1], hash_two(Fp::from(1), Fp::from(15)));
+        assert!(run(OutputMode::Hashed, instance));
+        let raw = OutputMode::Raw.instance(Fp::from(1), Fp::from(15));
+        assert!(!run(OutputMode::Hashed, raw));
+        let wrong = OutputMode::Hashed.instance(Fp::from(1), Fp::from(16));
+        assert!(!run(OutputMode::Hashed, wrong));
+    }
+}
@@ -0,0 +1,109 @@
+//! AP sum whose public output is a Poseidon hash packed next to the input.
+//!
+//! The first term and `Poseidon(first, sum)` share the one instance column, so a
+//! verifier stores a single column of two rows while the sum itself stays private. The
+//! hash binds the sum to the first term it was computed from.
+//!
+//! Instance layout: `[first, Poseidon(first, sum)]`, at [`FIRST_ROW`] and [`HASH_ROW`].
+
+use crate::{
+    commitment::{assign_hash_two, configure_poseidon, hash_two},
+    ApSumChip, ApSumConfig,
+};
+use halo2_gadgets::poseidon::Pow5Config;
+use halo2_proofs::{circuit::*, halo2curves::pasta::Fp, plonk::*};
+
+/// Instance row of the first term.
+pub const FIRST_ROW: usize = 0;
+/// Instance row of the hashed output.
+pub const HASH_ROW: usize = 1;
+
+/// Builds the packed instance for a progression starting at `first` with sum `sum`.
+#[must_use]
+pub fn packed_instance(first: Fp, sum: Fp) -> Vec<Fp> {
+    vec![first, hash_two(first, sum)]
+}
+
+/// Reads the hashed output back from a packed instance.
+#[must_use]
+pub fn hash_from_instance(instance: &[Fp]) -> Option<Fp> {
+    instance.get(HASH_ROW).copied()
+}
+
+#[derive(Clone, Debug)]
+pub struct PackedApSumConfig {
+    ap_sum: ApSumConfig,
+    poseidon: Pow5Config<Fp, 3, 2>,
+}
+
+#[derive(Default)]
+pub struct PackedApSumCircuit<const STEP: u128, const COUNT: usize>;
+
+impl<const STEP: u128, const COUNT: usize> Circuit<Fp> for PackedApSumCircuit<STEP, COUNT> {
+    type Config = PackedApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+
+        PackedApSumConfig {
+            ap_sum: ApSumChip::<_, STEP, COUNT>::configure(meta, advice, instance),
+            poseidon: configure_poseidon(meta),
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fp>,
+    ) -> Result<(), Error> {
+        let chip = ApSumChip::<_, STEP, COUNT>::construct(config.ap_sum);
+        let cells = chip.assign(layouter.namespace(|| "AP sum table"))?;
+        // a_0 is copied from the instance at FIRST_ROW.
+        let first = cells.terms[0].clone();
+        let hash = assign_hash_two(
+            &config.poseidon,
+            layouter.namespace(|| "hash output"),
+            [first, cells.sum.into_inner()],
+        )?;
+        chip.expose_public(layouter.namespace(|| "output"), &hash, HASH_ROW)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::dev::MockProver;
+
+    #[test]
+    fn packed_layout_verifies_in_one_instance_column() {
+        let k = 7;
+        let mut meta = ConstraintSystem::<Fp>::default();
+        PackedApSumCircuit::<1, 5>::configure(&mut meta);
+        assert_eq!(meta.num_instance_columns(), 1);
+
+        // 1 + 2 + 3 + 4 + 5 = 15
+        let instance = packed_instance(Fp::from(1), Fp::from(15));
+        let prover = MockProver::run(k, &PackedApSumCircuit::<1, 5>, vec![instance]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn hash_read_back_matches_off_circuit() {
+        let instance = packed_instance(Fp::from(1), Fp::from(15));
+        assert_eq!(
+            hash_from_instance(&instance),
+            Some(hash_two(Fp::from(1), Fp::from(15)))
+        );
+
+        let wrong = packed_instance(Fp::from(1), Fp::from(16));
+        let prover = MockProver::run(7, &PackedApSumCircuit::<1, 5>, vec![wrong]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
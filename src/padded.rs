@@ -0,0 +1,217 @@
+//! AP sum whose table can be padded to a power-of-two number of rows.
+//!
+//! With [`PaddedApSumCircuit::pad_to_pow2`] set, the `COUNT` terms are followed by inert
+//! rows up to `COUNT.next_power_of_two()`. Their own gate repeats the previous term and
+//! running sum instead of stepping and accumulating, so the exposed sum equals the sum of
+//! the `COUNT` terms alone. Tables of different lengths then occupy the same number of
+//! rows.
+//!
+//! Instance layout: `[first, sum]`.
+
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+use std::marker::PhantomData;
+
+#[derive(Clone, Debug)]
+pub struct PaddedApSumConfig {
+    // [a_n, sum_n]
+    advice: [Column<Advice>; 2],
+    s_active: Selector,
+    s_pad: Selector,
+    instance: Column<Instance>,
+}
+
+struct PaddedApSumChip<F, const STEP: u128, const COUNT: usize> {
+    config: PaddedApSumConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> PaddedApSumChip<F, STEP, COUNT> {
+    fn construct(config: PaddedApSumConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 2],
+        instance: Column<Instance>,
+    ) -> PaddedApSumConfig {
+        let s_active = meta.selector();
+        let s_pad = meta.selector();
+
+        meta.enable_equality(advice[0]);
+        meta.enable_equality(advice[1]);
+        meta.enable_equality(instance);
+
+        // |  advice[0]   |    advice[1]   | s_active | s_pad
+        // ---------------------------------------------------
+        // |     a_0      |     sum_0      |          |
+        // |     a_1      |     sum_1      |    s     |
+        // |     ...      |     ...        |    s     |
+        // |  a_{COUNT-1} | sum_{COUNT-1}  |    s     |
+        // |  a_{COUNT-1} | sum_{COUNT-1}  |          |   s
+        // |     ...      |     ...        |          |   s
+        meta.create_gate("padded step and sum", |meta| {
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let sum = meta.query_advice(advice[1], Rotation::cur());
+            let prev_a = meta.query_advice(advice[0], Rotation::prev());
+            let prev_sum = meta.query_advice(advice[1], Rotation::prev());
+            let s_active = meta.query_selector(s_active);
+            let s_pad = meta.query_selector(s_pad);
+            vec![
+                // sum == a + prev_sum
+                s_active.clone() * (a.clone() + prev_sum.clone() - sum.clone()),
+                // a == prev_a + STEP
+                s_active * (a.clone() - prev_a.clone() - Expression::Constant(F::from_u128(STEP))),
+                // padding repeats the previous row
+                s_pad.clone() * (sum - prev_sum),
+                s_pad * (a - prev_a),
+            ]
+        });
+
+        PaddedApSumConfig {
+            advice,
+            s_active,
+            s_pad,
+            instance,
+        }
+    }
+
+    /// Assigns the `COUNT` terms followed by inert rows up to `rows`, returning the sum.
+    fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        rows: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "padded AP sum table",
+            |mut region| {
+                let a_column = self.config.advice[0];
+                let sum_column = self.config.advice[1];
+                let step = F::from_u128(STEP);
+
+                // Copy first instance into both a_0 and sum_0. No selector needed for first row.
+                let mut a_cell = region.assign_advice_from_instance(
+                    || "a",
+                    self.config.instance,
+                    0,
+                    a_column,
+                    0,
+                )?;
+                let mut sum_cell = region.assign_advice_from_instance(
+                    || "sum",
+                    self.config.instance,
+                    0,
+                    sum_column,
+                    0,
+                )?;
+
+                for row in 1..rows {
+                    let (new_a, new_sum) = if row < COUNT {
+                        self.config.s_active.enable(&mut region, row)?;
+                        let new_a = a_cell.value().map(|a| *a + step);
+                        (new_a, sum_cell.value().copied() + new_a)
+                    } else {
+                        self.config.s_pad.enable(&mut region, row)?;
+                        (a_cell.value().copied(), sum_cell.value().copied())
+                    };
+                    a_cell = region.assign_advice(|| "a", a_column, row, || new_a)?;
+                    sum_cell = region.assign_advice(|| "sum", sum_column, row, || new_sum)?;
+                }
+
+                Ok(sum_cell)
+            },
+        )
+    }
+
+    fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct PaddedApSumCircuit<const STEP: u128, const COUNT: usize> {
+    /// Pads the table to `COUNT.next_power_of_two()` rows. The exposed sum is the same
+    /// either way.
+    pub pad_to_pow2: bool,
+}
+
+impl<const STEP: u128, const COUNT: usize> PaddedApSumCircuit<STEP, COUNT> {
+    /// Returns the number of table rows the circuit lays out.
+    pub fn table_rows(&self) -> usize {
+        if self.pad_to_pow2 {
+            COUNT.next_power_of_two()
+        } else {
+            COUNT
+        }
+    }
+}
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> Circuit<F>
+    for PaddedApSumCircuit<STEP, COUNT>
+{
+    type Config = PaddedApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        // Padding shapes the table, not the witness.
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+
+        PaddedApSumChip::<_, STEP, COUNT>::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = PaddedApSumChip::<_, STEP, COUNT>::construct(config);
+        let sum_cell = chip.assign(
+            layouter.namespace(|| "padded AP sum table"),
+            self.table_rows(),
+        )?;
+        chip.expose_public(layouter.namespace(|| "output"), &sum_cell, 1)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, halo2curves::pasta::Fp};
+
+    #[test]
+    fn count_five_padded_to_eight_keeps_sum_fifteen() {
+        let k = 4;
+        let circuit = PaddedApSumCircuit::<1, 5> { pad_to_pow2: true };
+        assert_eq!(circuit.table_rows(), 8);
+        // 1 + 2 + 3 + 4 + 5 = 15, padding adds nothing
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(1), Fp::from(15)]]).unwrap();
+        prover.assert_satisfied();
+
+        // 1 + ... + 8 = 36 would hold if the padding rows stepped
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(1), Fp::from(36)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn unpadded_table_has_count_rows() {
+        let k = 4;
+        let circuit = PaddedApSumCircuit::<1, 5> { pad_to_pow2: false };
+        assert_eq!(circuit.table_rows(), 5);
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(1), Fp::from(15)]]).unwrap();
+        prover.assert_satisfied();
+    }
+}
@@ -0,0 +1,213 @@
+//! Sums of two parallel arithmetic progressions `(x_n, y_n)` in one table.
+//!
+//! Both components share rows and a selector, so correlated progressions cost one region
+//! rather than two circuits.
+//!
+//! Instance layout: `[first_x, sum_x, first_y, sum_y]`.
+
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+use std::marker::PhantomData;
+
+#[derive(Clone, Debug)]
+pub struct PairApSumConfig {
+    // [ax_n, sumx_n, ay_n, sumy_n]
+    advice: [Column<Advice>; 4],
+    selector: Selector,
+    instance: Column<Instance>,
+}
+
+struct PairApSumChip<F, const STEP_X: u128, const STEP_Y: u128, const COUNT: usize> {
+    config: PairApSumConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const STEP_X: u128, const STEP_Y: u128, const COUNT: usize>
+    PairApSumChip<F, STEP_X, STEP_Y, COUNT>
+{
+    fn construct(config: PairApSumConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 4],
+        instance: Column<Instance>,
+    ) -> PairApSumConfig {
+        let selector = meta.selector();
+
+        for column in advice {
+            meta.enable_equality(column);
+        }
+        meta.enable_equality(instance);
+
+        // | advice[0] | advice[1] | advice[2] | advice[3] | selector
+        // -----------------------------------------------------------
+        // |   ax_0    |  sumx_0   |   ay_0    |  sumy_0   |
+        // |   ax_1    |  sumx_1   |   ay_1    |  sumy_1   |    s
+        // |   ...     |   ...     |   ...     |   ...     |    s
+        for (name, a_column, sum_column, step) in [
+            ("x step and sum", advice[0], advice[1], STEP_X),
+            ("y step and sum", advice[2], advice[3], STEP_Y),
+        ] {
+            meta.create_gate(name, |meta| {
+                let a = meta.query_advice(a_column, Rotation::cur());
+                let sum = meta.query_advice(sum_column, Rotation::cur());
+                let prev_a = meta.query_advice(a_column, Rotation::prev());
+                let prev_sum = meta.query_advice(sum_column, Rotation::prev());
+                let s = meta.query_selector(selector);
+                vec![
+                    // sum == a + prev_sum
+                    s.clone() * (a.clone() + prev_sum - sum),
+                    // a == prev_a + step
+                    s * (a - prev_a - Expression::Constant(F::from_u128(step))),
+                ]
+            });
+        }
+
+        PairApSumConfig {
+            advice,
+            selector,
+            instance,
+        }
+    }
+
+    /// Assigns both components, returning the `(sum_x, sum_y)` cells.
+    fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        layouter.assign_region(
+            || "pair AP sum table",
+            |mut region| {
+                for row in 1..COUNT {
+                    self.config.selector.enable(&mut region, row)?;
+                }
+
+                let sum_x = self.assign_component(&mut region, 0, STEP_X)?;
+                let sum_y = self.assign_component(&mut region, 1, STEP_Y)?;
+                Ok((sum_x, sum_y))
+            },
+        )
+    }
+
+    /// Assigns the `a` and `sum` columns of one component, returning its sum cell.
+    fn assign_component(
+        &self,
+        region: &mut Region<'_, F>,
+        component: usize,
+        step: u128,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let a_column = self.config.advice[2 * component];
+        let sum_column = self.config.advice[2 * component + 1];
+        let first_row = 2 * component;
+        let step = F::from_u128(step);
+
+        // Copy the component's first instance into both a_0 and sum_0.
+        let mut a_cell = region.assign_advice_from_instance(
+            || "a",
+            self.config.instance,
+            first_row,
+            a_column,
+            0,
+        )?;
+        let mut sum_cell = region.assign_advice_from_instance(
+            || "sum",
+            self.config.instance,
+            first_row,
+            sum_column,
+            0,
+        )?;
+
+        for row in 1..COUNT {
+            let new_a_val = a_cell.value().map(|a| *a + step);
+            a_cell = region.assign_advice(|| "a", a_column, row, || new_a_val)?;
+
+            let new_sum = sum_cell
+                .value()
+                .and_then(|sum| new_a_val.map(|new_a| new_a + sum));
+            sum_cell = region.assign_advice(|| "sum", sum_column, row, || new_sum)?;
+        }
+
+        Ok(sum_cell)
+    }
+
+    fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+#[derive(Default)]
+pub struct PairApSumCircuit<const STEP_X: u128, const STEP_Y: u128, const COUNT: usize>;
+
+impl<F: FieldExt, const STEP_X: u128, const STEP_Y: u128, const COUNT: usize> Circuit<F>
+    for PairApSumCircuit<STEP_X, STEP_Y, COUNT>
+{
+    type Config = PairApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [(); 4].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+
+        PairApSumChip::<_, STEP_X, STEP_Y, COUNT>::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = PairApSumChip::<_, STEP_X, STEP_Y, COUNT>::construct(config);
+        let (sum_x, sum_y) = chip.assign(layouter.namespace(|| "pair AP sum table"))?;
+        chip.expose_public(layouter.namespace(|| "sum x"), &sum_x, 1)?;
+        chip.expose_public(layouter.namespace(|| "sum y"), &sum_y, 3)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{
+        dev::{MockProver, VerifyFailure},
+        halo2curves::pasta::Fp,
+    };
+
+    #[test]
+    fn pair_steps_one_and_two_count_four_works() {
+        let k = 4;
+        let circuit = PairApSumCircuit::<1, 2, 4>;
+        // x: 1 + 2 + 3 + 4 = 10, y: 1 + 3 + 5 + 7 = 16
+        let instance = vec![Fp::from(1), Fp::from(10), Fp::from(1), Fp::from(16)];
+        let prover = MockProver::run(k, &circuit, vec![instance]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn swapped_sums_are_rejected() {
+        let k = 4;
+        let circuit = PairApSumCircuit::<1, 2, 4>;
+        let instance = vec![Fp::from(1), Fp::from(16), Fp::from(1), Fp::from(10)];
+        let prover = MockProver::run(k, &circuit, vec![instance.clone()]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // Both tables are consistent; only the copies of the sums to the instance fail.
+        let failures = crate::testing::analyze(&circuit, instance);
+        assert!(!failures.is_empty());
+        assert!(failures
+            .iter()
+            .all(|failure| matches!(failure, VerifyFailure::Permutation { .. })));
+    }
+}
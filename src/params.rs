@@ -0,0 +1,65 @@
+//! Compile-time validation of the const-generic circuit parameters.
+//!
+//! `STEP` and `COUNT` are easy to swap or mistype, and a mistake only shows up as a wrong
+//! sum at proving time. Referencing [`ApSumCircuit::ASSERT`] evaluates [`check`] during
+//! compilation instead, so bad parameters fail the build. Valid parameters compile
+//!
+//! ```
+//! use arithmetic_progression_sum::ApSumCircuit;
+//!
+//! let () = ApSumCircuit::<1, 5>::ASSERT;
+//! ```
+//!
+//! while a zero count does not:
+//!
+//! ```compile_fail
+//! use arithmetic_progression_sum::ApSumCircuit;
+//!
+//! let () = ApSumCircuit::<1, 0>::ASSERT;
+//! ```
+//!
+//! The check is opt-in: it only runs for parameters whose `ASSERT` is referenced. Its
+//! messages are tested by calling [`check`] at run time, which does not depend on how the
+//! compiler words a failed constant evaluation.
+
+use crate::ApSumCircuit;
+
+impl<const STEP: u128, const COUNT: usize> ApSumCircuit<STEP, COUNT> {
+    /// Fails to compile when `STEP` and `COUNT` are rejected by [`check`].
+    pub const ASSERT: () = check::<STEP, COUNT>();
+}
+
+/// Panics, at compile time when evaluated in a const context, if `COUNT` is `0` or the
+/// integer sum of the steps overflows `u128`.
+pub const fn check<const STEP: u128, const COUNT: usize>() {
+    assert!(COUNT > 0, "COUNT must be at least 1");
+    let count = COUNT as u128;
+    let steps = count * (count - 1) / 2;
+    assert!(
+        STEP == 0 || steps <= u128::MAX / STEP,
+        "STEP * COUNT * (COUNT - 1) / 2 overflows u128"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_parameters_pass() {
+        check::<1, 5>();
+        check::<0, 1000>();
+    }
+
+    #[test]
+    #[should_panic(expected = "COUNT must be at least 1")]
+    fn zero_count_is_rejected() {
+        check::<1, 0>();
+    }
+
+    #[test]
+    #[should_panic(expected = "overflows u128")]
+    fn overflowing_sum_is_rejected() {
+        check::<{ u128::MAX }, 3>();
+    }
+}
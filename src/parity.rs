@@ -0,0 +1,246 @@
+//! Sum of only the even- or odd-indexed terms of an arithmetic progression.
+//!
+//! The `a` column advances by `STEP` on every row as in [`crate::ApSumCircuit`], but a
+//! fixed `mask` column decides which rows contribute to the running sum. For
+//! [`Parity::Even`] and step `1`, count `6`, the circuit computes `1 + 3 + 5 = 9`.
+//!
+//! Instance layout: `[first, sum]`.
+
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+use std::marker::PhantomData;
+
+/// Which term indices contribute to the sum.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Parity {
+    /// `a_0 + a_2 + a_4 + ...`
+    #[default]
+    Even,
+    /// `a_1 + a_3 + a_5 + ...`
+    Odd,
+}
+
+impl Parity {
+    fn includes(self, index: usize) -> bool {
+        match self {
+            Parity::Even => index % 2 == 0,
+            Parity::Odd => index % 2 == 1,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ParityApSumConfig {
+    // [a_n, sum_n]
+    advice: [Column<Advice>; 2],
+    mask: Column<Fixed>,
+    s_first: Selector,
+    s_next: Selector,
+    instance: Column<Instance>,
+}
+
+struct ParityApSumChip<F, const STEP: u128, const COUNT: usize> {
+    config: ParityApSumConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> ParityApSumChip<F, STEP, COUNT> {
+    fn construct(config: ParityApSumConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 2],
+        instance: Column<Instance>,
+    ) -> ParityApSumConfig {
+        let mask = meta.fixed_column();
+        let s_first = meta.selector();
+        let s_next = meta.selector();
+
+        meta.enable_equality(advice[0]);
+        meta.enable_equality(advice[1]);
+        meta.enable_equality(instance);
+
+        // |  advice[0]   |    advice[1]   |  mask  | s_first | s_next
+        // ------------------------------------------------------------
+        // |     a_0      |     sum_0      |  m_0   |    s    |
+        // |     a_1      |     sum_1      |  m_1   |         |   s
+        // |     a_2      |     sum_2      |  m_2   |         |   s
+        // |     ...      |     ...        |  ...   |         |   s
+        meta.create_gate("masked first term", |meta| {
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let sum = meta.query_advice(advice[1], Rotation::cur());
+            let m = meta.query_fixed(mask, Rotation::cur());
+            let s = meta.query_selector(s_first);
+            // sum_0 == mask_0 * a_0
+            vec![s * (m * a - sum)]
+        });
+
+        meta.create_gate("step and masked sum", |meta| {
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let sum = meta.query_advice(advice[1], Rotation::cur());
+            let m = meta.query_fixed(mask, Rotation::cur());
+            let prev_a = meta.query_advice(advice[0], Rotation::prev());
+            let prev_sum = meta.query_advice(advice[1], Rotation::prev());
+            let s = meta.query_selector(s_next);
+            vec![
+                // sum == prev_sum + mask * a
+                s.clone() * (m * a.clone() + prev_sum - sum),
+                // a == prev_a + STEP
+                s * (a - prev_a - Expression::Constant(F::from_u128(STEP))),
+            ]
+        });
+
+        ParityApSumConfig {
+            advice,
+            mask,
+            s_first,
+            s_next,
+            instance,
+        }
+    }
+
+    fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        parity: Parity,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "masked AP sum table",
+            |mut region| {
+                let a_column = self.config.advice[0];
+                let sum_column = self.config.advice[1];
+                let mask_value = |row: usize| F::from(u64::from(parity.includes(row)));
+                let step = F::from_u128(STEP);
+
+                self.config.s_first.enable(&mut region, 0)?;
+                region.assign_fixed(
+                    || "mask",
+                    self.config.mask,
+                    0,
+                    || Value::known(mask_value(0)),
+                )?;
+                let mut a_cell = region.assign_advice_from_instance(
+                    || "a",
+                    self.config.instance,
+                    0,
+                    a_column,
+                    0,
+                )?;
+                let mut sum_cell = region.assign_advice(
+                    || "sum",
+                    sum_column,
+                    0,
+                    || a_cell.value().map(|a| *a * mask_value(0)),
+                )?;
+
+                for row in 1..COUNT {
+                    self.config.s_next.enable(&mut region, row)?;
+                    region.assign_fixed(
+                        || "mask",
+                        self.config.mask,
+                        row,
+                        || Value::known(mask_value(row)),
+                    )?;
+
+                    let new_a_val = a_cell.value().map(|a| *a + step);
+                    a_cell = region.assign_advice(|| "a", a_column, row, || new_a_val)?;
+
+                    let new_sum = sum_cell
+                        .value()
+                        .zip(new_a_val)
+                        .map(|(sum, a)| *sum + a * mask_value(row));
+                    sum_cell = region.assign_advice(|| "sum", sum_column, row, || new_sum)?;
+                }
+
+                Ok(sum_cell)
+            },
+        )
+    }
+
+    fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+#[derive(Default)]
+pub struct ParityApSumCircuit<const STEP: u128, const COUNT: usize> {
+    pub parity: Parity,
+}
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> Circuit<F>
+    for ParityApSumCircuit<STEP, COUNT>
+{
+    type Config = ParityApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            parity: self.parity,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+
+        ParityApSumChip::<_, STEP, COUNT>::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = ParityApSumChip::<_, STEP, COUNT>::construct(config);
+        let sum_cell = chip.assign(layouter.namespace(|| "masked AP sum table"), self.parity)?;
+        chip.expose_public(layouter.namespace(|| "output"), &sum_cell, 1)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, halo2curves::pasta::Fp};
+
+    #[test]
+    fn even_indexed_step_one_count_six_works() {
+        let k = 5;
+        let circuit = ParityApSumCircuit::<1, 6> {
+            parity: Parity::Even,
+        };
+        // 1 + 3 + 5 = 9
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(1), Fp::from(9)]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn odd_indexed_step_one_count_six_works() {
+        let k = 5;
+        let circuit = ParityApSumCircuit::<1, 6> {
+            parity: Parity::Odd,
+        };
+        // 2 + 4 + 6 = 12
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(1), Fp::from(12)]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn full_sum_is_rejected() {
+        let k = 5;
+        let circuit = ParityApSumCircuit::<1, 6> {
+            parity: Parity::Even,
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(1), Fp::from(21)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
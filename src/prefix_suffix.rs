@@ -0,0 +1,153 @@
+//! Proof that a prefix sum of a progression equals one of its suffix sums.
+//!
+//! [`PrefixSuffixApSumCircuit`] lays out the full table and constrains
+//! `sum_{n-1} == sum_{COUNT-1} - sum_{COUNT-m-1}`, i.e. the first `n` terms add up to the
+//! same value as the last `m`. The check is a gate over copies of the three running sums,
+//! so none of the sums is exposed. As for windows, `n` and `m` shape the copy
+//! constraints, so each pair has its own verifying key.
+//!
+//! Instance layout: `[first]`.
+
+use crate::{chip::ApSumCells, ApSumChip, ApSumConfig};
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+
+#[derive(Clone, Debug)]
+pub struct PrefixSuffixApSumConfig {
+    ap_sum: ApSumConfig,
+    // [prefix, before, total]
+    balance: [Column<Advice>; 3],
+    s_balance: Selector,
+}
+
+impl PrefixSuffixApSumConfig {
+    /// Constrains the sum of the first `n` terms of `cells` to equal the sum of the last
+    /// `m`.
+    ///
+    /// Fails with [`Error::Synthesis`] unless `1 <= n, m <= COUNT`.
+    fn expose_prefix_suffix_equal<F: FieldExt>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cells: &ApSumCells<F>,
+        n: usize,
+        m: usize,
+    ) -> Result<(), Error> {
+        let count = cells.partial_sums.len();
+        if n == 0 || m == 0 || n > count || m > count {
+            return Err(Error::Synthesis);
+        }
+
+        let prefix = &cells.partial_sums[n - 1];
+        let total = &cells.partial_sums[count - 1];
+        layouter.assign_region(
+            || "prefix suffix balance",
+            |mut region| match (count - m).checked_sub(1) {
+                // A suffix of every term is the total itself.
+                None => region.constrain_equal(prefix.cell(), total.cell()),
+                Some(before) => {
+                    self.s_balance.enable(&mut region, 0)?;
+                    let [prefix_column, before_column, total_column] = self.balance;
+                    prefix.copy_advice(|| "prefix", &mut region, prefix_column, 0)?;
+                    let before = &cells.partial_sums[before];
+                    before.copy_advice(|| "before", &mut region, before_column, 0)?;
+                    total.copy_advice(|| "total", &mut region, total_column, 0)?;
+                    Ok(())
+                }
+            },
+        )
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct PrefixSuffixApSumCircuit<const STEP: u128, const COUNT: usize> {
+    /// Number of leading terms in the prefix.
+    pub n: usize,
+    /// Number of trailing terms in the suffix.
+    pub m: usize,
+}
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> Circuit<F>
+    for PrefixSuffixApSumCircuit<STEP, COUNT>
+{
+    type Config = PrefixSuffixApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        // The prefix and suffix lengths shape the copy constraints, not the witness.
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let ap_sum = ApSumChip::<_, STEP, COUNT>::configure_sum_only(meta, advice, instance);
+
+        let balance = [(); 3].map(|_| meta.advice_column());
+        for column in balance {
+            meta.enable_equality(column);
+        }
+        let s_balance = meta.selector();
+
+        // | prefix | before | total | s_balance
+        // --------------------------------------
+        // |   p    |   b    |   t   |     s       p == t - b
+        meta.create_gate("prefix suffix balance", |meta| {
+            let prefix = meta.query_advice(balance[0], Rotation::cur());
+            let before = meta.query_advice(balance[1], Rotation::cur());
+            let total = meta.query_advice(balance[2], Rotation::cur());
+            let s = meta.query_selector(s_balance);
+            vec![s * (prefix + before - total)]
+        });
+
+        PrefixSuffixApSumConfig {
+            ap_sum,
+            balance,
+            s_balance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = ApSumChip::<_, STEP, COUNT>::construct(config.ap_sum.clone());
+        let cells = chip.assign(layouter.namespace(|| "AP sum table"))?;
+        config.expose_prefix_suffix_equal(
+            layouter.namespace(|| "prefix equals suffix"),
+            &cells,
+            self.n,
+            self.m,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, halo2curves::pasta::Fp};
+
+    fn run(n: usize, m: usize) -> bool {
+        let k = 5;
+        let circuit = PrefixSuffixApSumCircuit::<1, 6> { n, m };
+        MockProver::run(k, &circuit, vec![vec![Fp::from(1)]])
+            .unwrap()
+            .verify()
+            .is_ok()
+    }
+
+    #[test]
+    fn matching_prefix_and_suffix_hold() {
+        // 1 + 2 + 3 + 4 + 5 = 15 = 4 + 5 + 6
+        assert!(run(5, 3));
+        // every term on both sides
+        assert!(run(6, 6));
+    }
+
+    #[test]
+    fn differing_prefix_and_suffix_are_rejected() {
+        // 1 + 2 + 3 + 4 = 10 != 15 = 4 + 5 + 6
+        assert!(!run(4, 3));
+        // 1 + ... + 5 = 15 != 21 = 1 + ... + 6
+        assert!(!run(5, 6));
+    }
+}
@@ -0,0 +1,14 @@
+//! Common imports for using the crate.
+//!
+//! ```
+//! use arithmetic_progression_sum::prelude::*;
+//!
+//! // 1 + 2 + 3 + 4 + 5 = 15
+//! let sum = ap_sum(1, 1, 5).unwrap();
+//! let circuit = ApSumCircuit::<1, 5>;
+//! let prover = MockProver::run(5, &circuit, vec![vec![Fp::from(1), Fp::from_u128(sum)]]).unwrap();
+//! prover.assert_satisfied();
+//! ```
+
+pub use crate::{ap_sum, ApSumCircuit, ApSumError};
+pub use halo2_proofs::{arithmetic::FieldExt, dev::MockProver, halo2curves::pasta::Fp};
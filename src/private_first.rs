@@ -0,0 +1,197 @@
+//! AP sum with a private first term and a public step.
+//!
+//! The first term is a witness and never appears in the instance; the step is read from
+//! the instance instead of being a const generic. Row 0 copies the step into a `step`
+//! column, and every later row repeats it, so the step gate uses the public value.
+//!
+//! Instance layout: `[step, sum]`.
+
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+use std::marker::PhantomData;
+
+#[derive(Clone, Debug)]
+pub struct PrivateFirstApSumConfig {
+    // [a_n, sum_n, step_n]
+    advice: [Column<Advice>; 3],
+    s_first: Selector,
+    s_next: Selector,
+    instance: Column<Instance>,
+}
+
+struct PrivateFirstApSumChip<F, const COUNT: usize> {
+    config: PrivateFirstApSumConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const COUNT: usize> PrivateFirstApSumChip<F, COUNT> {
+    fn construct(config: PrivateFirstApSumConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 3],
+        instance: Column<Instance>,
+    ) -> PrivateFirstApSumConfig {
+        let s_first = meta.selector();
+        let s_next = meta.selector();
+
+        meta.enable_equality(advice[1]);
+        meta.enable_equality(advice[2]);
+        meta.enable_equality(instance);
+
+        // | advice[0] | advice[1] | advice[2] | s_first | s_next
+        // -------------------------------------------------------
+        // |    a_0    |   sum_0   |   step    |    s    |
+        // |    a_1    |   sum_1   |   step    |         |   s
+        // |    ...    |    ...    |   ...     |         |   s
+        meta.create_gate("first row", |meta| {
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let sum = meta.query_advice(advice[1], Rotation::cur());
+            let s = meta.query_selector(s_first);
+            vec![s * (a - sum)]
+        });
+
+        meta.create_gate("public step and sum", |meta| {
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let sum = meta.query_advice(advice[1], Rotation::cur());
+            let step = meta.query_advice(advice[2], Rotation::cur());
+            let prev_a = meta.query_advice(advice[0], Rotation::prev());
+            let prev_sum = meta.query_advice(advice[1], Rotation::prev());
+            let prev_step = meta.query_advice(advice[2], Rotation::prev());
+            let s = meta.query_selector(s_next);
+            vec![
+                // sum == a + prev_sum
+                s.clone() * (a.clone() + prev_sum - sum),
+                // a == prev_a + step
+                s.clone() * (a - prev_a - step.clone()),
+                // step == prev_step
+                s * (step - prev_step),
+            ]
+        });
+
+        PrivateFirstApSumConfig {
+            advice,
+            s_first,
+            s_next,
+            instance,
+        }
+    }
+
+    /// Assigns the table from the witnessed `first`, returning the sum cell.
+    fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        first: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "private first AP sum table",
+            |mut region| {
+                let [a_column, sum_column, step_column] = self.config.advice;
+                self.config.s_first.enable(&mut region, 0)?;
+
+                let step = region.assign_advice_from_instance(
+                    || "step",
+                    self.config.instance,
+                    0,
+                    step_column,
+                    0,
+                )?;
+                let step = step.value().copied();
+                let mut a = first;
+                region.assign_advice(|| "a", a_column, 0, || a)?;
+                let mut sum_cell = region.assign_advice(|| "sum", sum_column, 0, || a)?;
+
+                for row in 1..COUNT {
+                    self.config.s_next.enable(&mut region, row)?;
+                    region.assign_advice(|| "step", step_column, row, || step)?;
+
+                    a = a + step;
+                    region.assign_advice(|| "a", a_column, row, || a)?;
+                    let sum = sum_cell.value().copied() + a;
+                    sum_cell = region.assign_advice(|| "sum", sum_column, row, || sum)?;
+                }
+
+                Ok(sum_cell)
+            },
+        )
+    }
+
+    fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+#[derive(Default)]
+pub struct PrivateFirstApSumCircuit<F: FieldExt, const COUNT: usize> {
+    /// The hidden first term.
+    pub first: Value<F>,
+}
+
+impl<F: FieldExt, const COUNT: usize> Circuit<F> for PrivateFirstApSumCircuit<F, COUNT> {
+    type Config = PrivateFirstApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [(); 3].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+
+        PrivateFirstApSumChip::<_, COUNT>::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = PrivateFirstApSumChip::<_, COUNT>::construct(config);
+        let sum = chip.assign(
+            layouter.namespace(|| "private first AP sum table"),
+            self.first,
+        )?;
+        chip.expose_public(layouter.namespace(|| "output"), &sum, 1)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, halo2curves::pasta::Fp};
+
+    fn run(first: u64, instance: Vec<Fp>) -> bool {
+        let circuit = PrivateFirstApSumCircuit::<_, 4> {
+            first: Value::known(Fp::from(first)),
+        };
+        MockProver::run(4, &circuit, vec![instance])
+            .unwrap()
+            .verify()
+            .is_ok()
+    }
+
+    #[test]
+    fn hidden_first_term_three_step_two_sums_to_24() {
+        // 3 + 5 + 7 + 9 = 24; the instance holds only the step and the sum.
+        let instance = vec![Fp::from(2), Fp::from(24)];
+        assert!(!instance.contains(&Fp::from(3)));
+        assert!(run(3, instance));
+    }
+
+    #[test]
+    fn sum_of_other_step_is_rejected() {
+        // 3 + 6 + 9 + 12 = 30 would need step 3.
+        assert!(!run(3, vec![Fp::from(2), Fp::from(30)]));
+    }
+}
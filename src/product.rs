@@ -0,0 +1,195 @@
+//! Running sum and running product of an arithmetic progression.
+//!
+//! Each row holds the term `a_n`, the running sum and the running product, with
+//! `prod == prev_prod * a` alongside the usual sum gate. For terms `1, 2, 3, 4` the
+//! circuit computes the sum `10` and the product `24`.
+//!
+//! Instance layout: `[first, sum, product]`.
+
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+use std::marker::PhantomData;
+
+#[derive(Clone, Debug)]
+pub struct ProductApSumConfig {
+    // [a_n, sum_n, prod_n]
+    advice: [Column<Advice>; 3],
+    s_first: Selector,
+    s_next: Selector,
+    instance: Column<Instance>,
+}
+
+pub struct ProductApSumChip<F, const STEP: u128, const COUNT: usize> {
+    config: ProductApSumConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> ProductApSumChip<F, STEP, COUNT> {
+    pub fn construct(config: ProductApSumConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 3],
+        instance: Column<Instance>,
+    ) -> ProductApSumConfig {
+        let s_first = meta.selector();
+        let s_next = meta.selector();
+
+        for column in advice {
+            meta.enable_equality(column);
+        }
+        meta.enable_equality(instance);
+
+        // | advice[0] | advice[1] | advice[2] | s_first | s_next
+        // -------------------------------------------------------
+        // |    a_0    |    a_0    |    a_0    |    s    |
+        // |    a_1    |   sum_1   |  prod_1   |         |   s
+        // |    ...    |    ...    |    ...    |         |   s
+        meta.create_gate("first term", |meta| {
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let sum = meta.query_advice(advice[1], Rotation::cur());
+            let prod = meta.query_advice(advice[2], Rotation::cur());
+            let s = meta.query_selector(s_first);
+            vec![s.clone() * (a.clone() - sum), s * (a - prod)]
+        });
+
+        meta.create_gate("step, sum and product", |meta| {
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let sum = meta.query_advice(advice[1], Rotation::cur());
+            let prod = meta.query_advice(advice[2], Rotation::cur());
+            let prev_a = meta.query_advice(advice[0], Rotation::prev());
+            let prev_sum = meta.query_advice(advice[1], Rotation::prev());
+            let prev_prod = meta.query_advice(advice[2], Rotation::prev());
+            let s = meta.query_selector(s_next);
+            vec![
+                // a == prev_a + STEP
+                s.clone() * (a.clone() - prev_a - Expression::Constant(F::from_u128(STEP))),
+                // sum == a + prev_sum
+                s.clone() * (a.clone() + prev_sum - sum),
+                // prod == prev_prod * a
+                s * (prev_prod * a - prod),
+            ]
+        });
+
+        ProductApSumConfig {
+            advice,
+            s_first,
+            s_next,
+            instance,
+        }
+    }
+
+    /// Assigns the table, returning the `(sum, product)` cells.
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        layouter.assign_region(
+            || "product AP sum table",
+            |mut region| {
+                let [a_column, sum_column, prod_column] = self.config.advice;
+                let step = F::from_u128(STEP);
+
+                let mut a_cell = region.assign_advice_from_instance(
+                    || "a",
+                    self.config.instance,
+                    0,
+                    a_column,
+                    0,
+                )?;
+                let first = a_cell.value().copied();
+                let mut sum_cell = region.assign_advice(|| "sum", sum_column, 0, || first)?;
+                let mut prod_cell = region.assign_advice(|| "prod", prod_column, 0, || first)?;
+                self.config.s_first.enable(&mut region, 0)?;
+
+                for row in 1..COUNT {
+                    self.config.s_next.enable(&mut region, row)?;
+
+                    let new_a_val = a_cell.value().map(|a| *a + step);
+                    a_cell = region.assign_advice(|| "a", a_column, row, || new_a_val)?;
+
+                    let new_sum = sum_cell.value().copied() + new_a_val;
+                    sum_cell = region.assign_advice(|| "sum", sum_column, row, || new_sum)?;
+
+                    let new_prod = prod_cell.value().copied() * new_a_val;
+                    prod_cell = region.assign_advice(|| "prod", prod_column, row, || new_prod)?;
+                }
+
+                Ok((sum_cell, prod_cell))
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+#[derive(Default)]
+pub struct ProductApSumCircuit<const STEP: u128, const COUNT: usize>;
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> Circuit<F>
+    for ProductApSumCircuit<STEP, COUNT>
+{
+    type Config = ProductApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [(); 3].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+
+        ProductApSumChip::<_, STEP, COUNT>::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = ProductApSumChip::<_, STEP, COUNT>::construct(config);
+        let (sum, product) = chip.assign(layouter.namespace(|| "product AP sum table"))?;
+        chip.expose_public(layouter.namespace(|| "sum"), &sum, 1)?;
+        chip.expose_public(layouter.namespace(|| "product"), &product, 2)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, halo2curves::pasta::Fp};
+
+    fn run(instance: [u64; 3]) -> bool {
+        let circuit = ProductApSumCircuit::<1, 4>;
+        let instance = instance.map(Fp::from).to_vec();
+        MockProver::run(4, &circuit, vec![instance])
+            .unwrap()
+            .verify()
+            .is_ok()
+    }
+
+    #[test]
+    fn terms_one_to_four_give_sum_ten_and_product_24() {
+        assert!(run([1, 10, 24]));
+    }
+
+    #[test]
+    fn each_output_is_checked() {
+        assert!(!run([1, 10, 25]));
+        assert!(!run([1, 11, 24]));
+        assert!(!run([1, 24, 10]));
+    }
+}
@@ -0,0 +1,95 @@
+//! Per-phase timings for the `profile` feature.
+
+use crate::proof::{prove, verify, Params, Proof};
+use halo2_proofs::{
+    dev::MockProver,
+    halo2curves::pasta::{EqAffine, Fp},
+    plonk::{Circuit, ConstraintSystem, Error, ProvingKey},
+    poly::commitment::Params as _,
+};
+use rand_core::{CryptoRng, RngCore};
+use std::time::{Duration, Instant};
+
+/// Wall-clock time spent in each proving phase.
+#[derive(Clone, Debug, Default)]
+pub struct ProfileReport {
+    /// `Circuit::configure` on a fresh constraint system.
+    pub configure: Duration,
+    /// Witness assignment alone, measured as a `MockProver` run.
+    pub assign: Duration,
+    /// Proof creation, which repeats witness assignment.
+    pub prove: Duration,
+    /// Verification of the created proof.
+    pub verify: Duration,
+    /// Whether the created proof verified against the instance.
+    pub verified: bool,
+}
+
+/// Proves `circuit` like [`prove`], drawing blinding factors from `rng`, and times each
+/// phase.
+///
+/// The proof is verified as part of profiling and the outcome recorded in
+/// [`ProfileReport::verified`]; a proof that does not verify is still returned.
+pub fn prove_profiled<C: Circuit<Fp>>(
+    params: &Params,
+    pk: &ProvingKey<EqAffine>,
+    circuit: C,
+    instance: &[Fp],
+    rng: impl RngCore + CryptoRng,
+) -> Result<(Proof, ProfileReport), Error> {
+    let start = Instant::now();
+    let mut meta = ConstraintSystem::default();
+    C::configure(&mut meta);
+    let configure = start.elapsed();
+
+    let start = Instant::now();
+    MockProver::run(params.k(), &circuit, vec![instance.to_vec()])?;
+    let assign = start.elapsed();
+
+    let start = Instant::now();
+    let proof = prove(params, pk, circuit, instance, rng)?;
+    let prove = start.elapsed();
+
+    let start = Instant::now();
+    let verified = verify(params, pk.get_vk(), &proof, instance);
+    let verify = start.elapsed();
+
+    Ok((
+        proof,
+        ProfileReport {
+            configure,
+            assign,
+            prove,
+            verify,
+            verified,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{proof::setup, ApSumCircuit};
+    use rand_core::OsRng;
+
+    #[test]
+    fn report_is_populated() {
+        let (params, pk) = setup(5, &ApSumCircuit::<1, 5>).unwrap();
+        let instance = [Fp::from(1), Fp::from(15)];
+        let (proof, report) =
+            prove_profiled(&params, &pk, ApSumCircuit::<1, 5>, &instance, OsRng).unwrap();
+        assert!(report.verified);
+        assert!(verify(&params, pk.get_vk(), &proof, &instance));
+        assert!(report.prove > Duration::ZERO);
+        assert!(report.verify > Duration::ZERO);
+    }
+
+    #[test]
+    fn wrong_instance_is_reported_unverified() {
+        let (params, pk) = setup(5, &ApSumCircuit::<1, 5>).unwrap();
+        let instance = [Fp::from(1), Fp::from(16)];
+        let (_, report) =
+            prove_profiled(&params, &pk, ApSumCircuit::<1, 5>, &instance, OsRng).unwrap();
+        assert!(!report.verified);
+    }
+}
@@ -0,0 +1,110 @@
+//! Proving with progress reports for long witness assignments.
+//!
+//! [`ProgressApSumCircuit`] lays out the same table as [`crate::ApSumCircuit`] but reports
+//! the fraction of rows assigned at every chunk boundary, so a proving key generated for
+//! `ApSumCircuit<STEP, COUNT>` also proves it.
+//!
+//! Instance layout: `[first, sum]`.
+
+use crate::{
+    proof::{prove, Params, Proof},
+    ApSumChip, ApSumConfig, ApSumError,
+};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::*,
+    halo2curves::pasta::{EqAffine, Fp},
+    plonk::*,
+};
+use rand_core::{CryptoRng, RngCore};
+use std::cell::RefCell;
+
+#[derive(Default)]
+pub struct ProgressApSumCircuit<'a, const STEP: u128, const COUNT: usize> {
+    /// Called with the fraction of rows assigned, from `0.0` up to `1.0`.
+    pub on_progress: Option<&'a RefCell<dyn FnMut(f32) + 'a>>,
+}
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> Circuit<F>
+    for ProgressApSumCircuit<'_, STEP, COUNT>
+{
+    type Config = ApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+
+        ApSumChip::<_, STEP, COUNT>::configure_sum_only(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = ApSumChip::<_, STEP, COUNT>::construct(config);
+        let table = layouter.namespace(|| "AP sum table");
+        let cells = match self.on_progress {
+            Some(on_progress) => {
+                chip.assign_with_progress(table, &|fraction| (on_progress.borrow_mut())(fraction))
+            }
+            None => chip.assign(table),
+        }?;
+        chip.expose_public(layouter.namespace(|| "output"), cells.sum.assigned(), 1)?;
+        Ok(())
+    }
+}
+
+/// Proves `ApSumCircuit<STEP, COUNT>` against `instance`, calling `on_progress` with the
+/// fraction of witness rows assigned at every chunk boundary.
+///
+/// Blinding factors are drawn from `rng`, as in [`prove`].
+pub fn prove_with_progress<const STEP: u128, const COUNT: usize>(
+    params: &Params,
+    pk: &ProvingKey<EqAffine>,
+    instance: &[Fp],
+    on_progress: impl FnMut(f32),
+    rng: impl RngCore + CryptoRng,
+) -> Result<Proof, ApSumError> {
+    let on_progress = RefCell::new(on_progress);
+    let circuit = ProgressApSumCircuit::<STEP, COUNT> {
+        on_progress: Some(&on_progress),
+    };
+    prove(params, pk, circuit, instance, rng).map_err(ApSumError::Proving)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ap_sum,
+        proof::{setup, verify},
+        ApSumCircuit,
+    };
+    use rand_core::OsRng;
+
+    #[test]
+    fn progress_increases_to_one_across_chunks() {
+        // Three chunks of assignment.
+        const COUNT: usize = 3000;
+        let k = 12;
+        let (params, pk) = setup(k, &ApSumCircuit::<1, COUNT>).unwrap();
+        let instance = vec![Fp::from(1), Fp::from_u128(ap_sum(1, 1, COUNT).unwrap())];
+
+        let mut reports = vec![];
+        let proof =
+            prove_with_progress::<1, COUNT>(&params, &pk, &instance, |p| reports.push(p), OsRng)
+                .unwrap();
+        assert!(verify(&params, pk.get_vk(), &proof, &instance));
+
+        assert!(reports.len() >= 3, "{reports:?}");
+        assert_eq!(reports.first(), Some(&0.0));
+        assert_eq!(reports.last(), Some(&1.0));
+        assert!(reports.windows(2).all(|w| w[0] < w[1]), "{reports:?}");
+    }
+}
@@ -0,0 +1,145 @@
+//! A uniform interface over the circuit variants.
+//!
+//! Circuits take their first term from the instance, so a circuit alone does not describe
+//! a progression. [`Progression`] pairs a circuit with its first term and implements
+//! [`ProgressionSum`] for each variant, letting generic code handle any of them.
+
+use crate::{instance::InstanceBuilder, ApSumCircuit, ApSumError, ApSumLastTermCircuit};
+use halo2_proofs::{arithmetic::FieldExt, dev::MockProver, plonk::Circuit};
+
+pub trait ProgressionSum<F: FieldExt> {
+    /// Returns the term at `index`, starting from 0.
+    fn term(&self, index: usize) -> F;
+
+    /// Returns the sum the circuit is expected to expose.
+    fn expected_sum(&self) -> F;
+
+    /// Returns the instance column the circuit is satisfied by.
+    ///
+    /// Fails with [`ApSumError::NoTerms`] if the layout exposes the last term of an empty
+    /// progression.
+    fn build_instance(&self) -> Result<Vec<F>, ApSumError>;
+
+    /// Runs the circuit under `MockProver` with [`ProgressionSum::build_instance`].
+    ///
+    /// Fails with the error of [`ProgressionSum::build_instance`], with [`ApSumError::Proving`] if synthesis fails at size `2^k`, and with
+    /// [`ApSumError::Unsatisfied`] if the circuit is not satisfied.
+    fn mock_prove(&self, k: u32) -> Result<(), ApSumError>;
+}
+
+/// A circuit variant together with its first term.
+pub struct Progression<C, F> {
+    pub circuit: C,
+    pub first: F,
+}
+
+impl<C, F> Progression<C, F> {
+    pub fn new(circuit: C, first: F) -> Self {
+        Self { circuit, first }
+    }
+}
+
+fn ap_term<F: FieldExt>(first: F, step: u128, index: usize) -> F {
+    first + F::from_u128(step) * F::from(index as u64)
+}
+
+fn ap_field_sum<F: FieldExt>(first: F, step: u128, count: usize) -> F {
+    (0..count).fold(F::zero(), |sum, index| sum + ap_term(first, step, index))
+}
+
+fn run_mock<F: FieldExt, C: Circuit<F>>(
+    k: u32,
+    circuit: &C,
+    instance: Vec<F>,
+) -> Result<(), ApSumError> {
+    MockProver::run(k, circuit, vec![instance])
+        .map_err(ApSumError::Proving)?
+        .verify()
+        .map_err(ApSumError::Unsatisfied)
+}
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> ProgressionSum<F>
+    for Progression<ApSumCircuit<STEP, COUNT>, F>
+{
+    fn term(&self, index: usize) -> F {
+        ap_term(self.first, STEP, index)
+    }
+
+    fn expected_sum(&self) -> F {
+        ap_field_sum(self.first, STEP, COUNT)
+    }
+
+    fn build_instance(&self) -> Result<Vec<F>, ApSumError> {
+        Ok(InstanceBuilder::new()
+            .first(self.first)
+            .sum(self.expected_sum())
+            .build())
+    }
+
+    fn mock_prove(&self, k: u32) -> Result<(), ApSumError> {
+        run_mock(k, &self.circuit, self.build_instance()?)
+    }
+}
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> ProgressionSum<F>
+    for Progression<ApSumLastTermCircuit<STEP, COUNT>, F>
+{
+    fn term(&self, index: usize) -> F {
+        ap_term(self.first, STEP, index)
+    }
+
+    fn expected_sum(&self) -> F {
+        ap_field_sum(self.first, STEP, COUNT)
+    }
+
+    fn build_instance(&self) -> Result<Vec<F>, ApSumError> {
+        let last = COUNT.checked_sub(1).ok_or(ApSumError::NoTerms)?;
+        Ok(InstanceBuilder::new()
+            .first(self.first)
+            .sum(self.expected_sum())
+            .last_term(self.term(last))
+            .build())
+    }
+
+    fn mock_prove(&self, k: u32) -> Result<(), ApSumError> {
+        run_mock(k, &self.circuit, self.build_instance()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::halo2curves::pasta::Fp;
+
+    #[test]
+    fn mixed_variants_are_satisfied() {
+        let progressions: Vec<Box<dyn ProgressionSum<Fp>>> = vec![
+            Box::new(Progression::new(ApSumCircuit::<1, 5>, Fp::from(1))),
+            Box::new(Progression::new(ApSumCircuit::<3, 4>, Fp::from(2))),
+            Box::new(Progression::new(ApSumLastTermCircuit::<2, 4>, Fp::from(1))),
+        ];
+        for progression in progressions {
+            assert!(progression.mock_prove(5).is_ok());
+        }
+    }
+
+    #[test]
+    fn terms_and_sum_follow_step() {
+        let progression = Progression::new(ApSumLastTermCircuit::<2, 4>, Fp::from(1));
+        assert_eq!(progression.term(3), Fp::from(7));
+        assert_eq!(progression.expected_sum(), Fp::from(16));
+        assert_eq!(
+            progression.build_instance().unwrap(),
+            vec![Fp::from(1), Fp::from(16), Fp::from(7)]
+        );
+    }
+
+    #[test]
+    fn empty_progression_is_an_error() {
+        let progression = Progression::new(ApSumLastTermCircuit::<2, 0>, Fp::from(1));
+        assert!(matches!(
+            progression.build_instance(),
+            Err(ApSumError::NoTerms)
+        ));
+    }
+}
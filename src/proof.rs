@@ -0,0 +1,593 @@
+//! Real proving and verification over the Pasta curves.
+//!
+//! Proofs use the IPA commitment scheme on `EqAffine`. The Fiat-Shamir transcript is
+//! selected by the caller: Blake2b is the native choice for Pasta verifiers, Keccak256
+//! suits EVM verifiers. A proof is only valid under the transcript it was created with.
+
+use crate::{
+    ap_sum,
+    instance::{validate_instance, InstanceBuilder, Layout},
+    sizing::minimum_k,
+    ApSumError,
+};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    halo2curves::pasta::{EqAffine, Fp},
+    plonk::{
+        create_proof, keygen_pk, keygen_vk, verify_proof, Circuit, Error, ProvingKey, VerifyingKey,
+    },
+    poly::{
+        commitment::{Params as _, ParamsProver},
+        ipa::{
+            commitment::{IPACommitmentScheme, ParamsIPA},
+            multiopen::{ProverIPA, VerifierIPA},
+            strategy::{AccumulatorStrategy, SingleStrategy},
+        },
+        VerificationStrategy,
+    },
+    transcript::{
+        Blake2bRead, Blake2bWrite, Challenge255, EncodedChallenge, Keccak256Read, Keccak256Write,
+        Transcript, TranscriptReadBuffer, TranscriptWrite, TranscriptWriterBuffer,
+    },
+};
+use rand_core::{CryptoRng, RngCore};
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Write},
+    path::Path,
+};
+
+pub type Params = ParamsIPA<EqAffine>;
+
+/// Blake2b transcript for proving (Pasta-native verifiers).
+pub type Blake2bProverTranscript = Blake2bWrite<Vec<u8>, EqAffine, Challenge255<EqAffine>>;
+/// Blake2b transcript for verifying.
+pub type Blake2bVerifierTranscript<'a> = Blake2bRead<&'a [u8], EqAffine, Challenge255<EqAffine>>;
+/// Keccak256 transcript for proving (EVM verifiers).
+pub type KeccakProverTranscript = Keccak256Write<Vec<u8>, EqAffine, Challenge255<EqAffine>>;
+/// Keccak256 transcript for verifying.
+pub type KeccakVerifierTranscript<'a> = Keccak256Read<&'a [u8], EqAffine, Challenge255<EqAffine>>;
+
+/// Serialized proof bytes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Proof(Vec<u8>);
+
+impl Proof {
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+/// Writes `params` to `path`, for distribution to verifiers alongside the app.
+pub fn save_params(path: impl AsRef<Path>, params: &Params) -> Result<(), ApSumError> {
+    let mut writer = BufWriter::new(File::create(path).map_err(ApSumError::Io)?);
+    params.write(&mut writer).map_err(ApSumError::Io)?;
+    writer.flush().map_err(ApSumError::Io)
+}
+
+/// Reads params written by [`save_params`].
+///
+/// Verification only succeeds with the same params the prover used.
+pub fn load_params(path: impl AsRef<Path>) -> Result<Params, ApSumError> {
+    let mut reader = BufReader::new(File::open(path).map_err(ApSumError::Io)?);
+    Params::read(&mut reader).map_err(ApSumError::Io)
+}
+
+/// Hashes the serialized `params` with 256-bit Blake2b, so a verifier can tell whether
+/// it holds the params a proof was made with.
+///
+/// Fails with [`ApSumError::Io`] if the params cannot be serialized.
+pub fn params_fingerprint(params: &Params) -> Result<[u8; 32], ApSumError> {
+    let mut state = blake2b_simd::Params::new().hash_length(32).to_state();
+    params.write(&mut state).map_err(ApSumError::Io)?;
+    let mut fingerprint = [0; 32];
+    fingerprint.copy_from_slice(state.finalize().as_bytes());
+    Ok(fingerprint)
+}
+
+/// Generates params of size `2^k` and the proving key for `circuit`.
+pub fn setup<C: Circuit<Fp>>(k: u32, circuit: &C) -> Result<(Params, ProvingKey<EqAffine>), Error> {
+    let params = Params::new(k);
+    let vk = keygen_vk(&params, circuit)?;
+    let pk = keygen_pk(&params, vk, circuit)?;
+    Ok((params, pk))
+}
+
+/// Proves `circuit` against `instance` using the Blake2b transcript.
+///
+/// Blinding factors are drawn from `rng`. Pass `OsRng` in production; a seeded RNG makes
+/// the proof bytes reproducible, which is only appropriate in tests.
+pub fn prove<C: Circuit<Fp>>(
+    params: &Params,
+    pk: &ProvingKey<EqAffine>,
+    circuit: C,
+    instance: &[Fp],
+    rng: impl RngCore + CryptoRng,
+) -> Result<Proof, Error> {
+    prove_with_transcript::<C, Blake2bProverTranscript>(params, pk, circuit, instance, rng)
+}
+
+/// Proves `circuit` against `instance` using the transcript `T`, drawing blinding
+/// factors from `rng`.
+pub fn prove_with_transcript<C, T>(
+    params: &Params,
+    pk: &ProvingKey<EqAffine>,
+    circuit: C,
+    instance: &[Fp],
+    rng: impl RngCore + CryptoRng,
+) -> Result<Proof, Error>
+where
+    C: Circuit<Fp>,
+    T: TranscriptWriterBuffer<Vec<u8>, EqAffine, Challenge255<EqAffine>>,
+{
+    let mut transcript = T::init(vec![]);
+    create_proof::<IPACommitmentScheme<EqAffine>, ProverIPA<EqAffine>, _, _, _, _>(
+        params,
+        pk,
+        &[circuit],
+        &[&[instance]],
+        rng,
+        &mut transcript,
+    )?;
+    Ok(Proof(transcript.finalize()))
+}
+
+/// Transcript wrapper recording every challenge squeezed from the inner transcript.
+struct LoggingTranscript<T> {
+    inner: T,
+    challenges: Vec<Fp>,
+}
+
+impl<T> Transcript<EqAffine, Challenge255<EqAffine>> for LoggingTranscript<T>
+where
+    T: TranscriptWrite<EqAffine, Challenge255<EqAffine>>,
+{
+    fn squeeze_challenge(&mut self) -> Challenge255<EqAffine> {
+        let challenge = self.inner.squeeze_challenge();
+        self.challenges.push(challenge.get_scalar());
+        challenge
+    }
+
+    fn common_point(&mut self, point: EqAffine) -> io::Result<()> {
+        self.inner.common_point(point)
+    }
+
+    fn common_scalar(&mut self, scalar: Fp) -> io::Result<()> {
+        self.inner.common_scalar(scalar)
+    }
+}
+
+impl<T> TranscriptWrite<EqAffine, Challenge255<EqAffine>> for LoggingTranscript<T>
+where
+    T: TranscriptWrite<EqAffine, Challenge255<EqAffine>>,
+{
+    fn write_point(&mut self, point: EqAffine) -> io::Result<()> {
+        self.inner.write_point(point)
+    }
+
+    fn write_scalar(&mut self, scalar: Fp) -> io::Result<()> {
+        self.inner.write_scalar(scalar)
+    }
+}
+
+/// Proves `circuit` with the Blake2b transcript, also returning every Fiat-Shamir
+/// challenge in the order it was squeezed.
+///
+/// Blinding factors are drawn from `rng`, so a seeded `rng` makes the proof and the
+/// challenges reproducible. This is a debugging aid for comparing prover and verifier
+/// transcripts.
+pub fn prove_with_transcript_log<C: Circuit<Fp>>(
+    params: &Params,
+    pk: &ProvingKey<EqAffine>,
+    circuit: C,
+    instance: &[Fp],
+    rng: impl RngCore + CryptoRng,
+) -> Result<(Proof, Vec<Fp>), Error> {
+    let mut transcript = LoggingTranscript {
+        inner: Blake2bProverTranscript::init(vec![]),
+        challenges: vec![],
+    };
+    create_proof::<IPACommitmentScheme<EqAffine>, ProverIPA<EqAffine>, _, _, _, _>(
+        params,
+        pk,
+        &[circuit],
+        &[&[instance]],
+        rng,
+        &mut transcript,
+    )?;
+    Ok((Proof(transcript.inner.finalize()), transcript.challenges))
+}
+
+/// Verifies a Blake2b-transcript `proof` against `instance`.
+#[must_use = "the proof is only valid if this returns `true`"]
+pub fn verify(
+    params: &Params,
+    vk: &VerifyingKey<EqAffine>,
+    proof: &Proof,
+    instance: &[Fp],
+) -> bool {
+    verify_with_transcript::<Blake2bVerifierTranscript>(params, vk, proof, instance)
+}
+
+/// Verifies `proof` as [`verify`] does, after checking that `params` have the
+/// `expected` [`params_fingerprint`].
+///
+/// Fails with [`ApSumError::ParamsFingerprintMismatch`] otherwise, so params meant for
+/// another circuit are caught even when the verifying key was derived from them, and
+/// with the error of [`params_fingerprint`].
+#[must_use = "the proof is only valid if this returns `Ok(true)`"]
+pub fn verify_with_fingerprint(
+    params: &Params,
+    expected: &[u8; 32],
+    vk: &VerifyingKey<EqAffine>,
+    proof: &Proof,
+    instance: &[Fp],
+) -> Result<bool, ApSumError> {
+    if params_fingerprint(params)? != *expected {
+        return Err(ApSumError::ParamsFingerprintMismatch);
+    }
+    Ok(verify(params, vk, proof, instance))
+}
+
+/// Verifies a Blake2b-transcript proof read incrementally from `reader` against
+/// `instance`.
+///
+/// Returns `Ok(false)` if the proof does not satisfy the circuit, and
+/// [`ApSumError::Io`] if it cannot be read, including when it ends early.
+#[must_use = "the proof is only valid if this returns `Ok(true)`"]
+pub fn verify_from_reader(
+    params: &Params,
+    vk: &VerifyingKey<EqAffine>,
+    reader: impl io::Read,
+    instance: &[Fp],
+) -> Result<bool, ApSumError> {
+    let strategy = SingleStrategy::new(params);
+    let mut transcript = Blake2bRead::<_, EqAffine, Challenge255<_>>::init(reader);
+    match verify_proof::<IPACommitmentScheme<EqAffine>, VerifierIPA<EqAffine>, _, _, _>(
+        params,
+        vk,
+        strategy,
+        &[&[instance]],
+        &mut transcript,
+    ) {
+        Ok(_) => Ok(true),
+        Err(Error::Transcript(e)) => Err(ApSumError::Io(e)),
+        Err(_) => Ok(false),
+    }
+}
+
+/// Verifies `proof` for the progression `first, first + step, ...` of `count` terms.
+///
+/// The expected instance is derived with [`ap_sum`], so callers never build it by hand.
+#[must_use = "the proof is only valid if this returns `Ok(true)`"]
+pub fn verify_expecting(
+    params: &Params,
+    vk: &VerifyingKey<EqAffine>,
+    proof: &Proof,
+    first: u128,
+    step: u128,
+    count: usize,
+) -> Result<bool, ApSumError> {
+    let instance = expected_instance(first, step, count)?;
+    Ok(verify(params, vk, proof, &instance))
+}
+
+/// Checks a stored `[first, sum]` instance against the progression without verifying any
+/// proof, as a cheap pre-filter before [`verify`].
+pub fn check_public_inputs(
+    stored_instance: &[Fp],
+    first: u128,
+    step: u128,
+    count: usize,
+) -> Result<(), ApSumError> {
+    validate_instance(Layout::Sum, stored_instance)?;
+    let expected = expected_instance(first, step, count)?;
+    if stored_instance[0] != expected[0] {
+        return Err(ApSumError::FirstTermMismatch);
+    }
+    if stored_instance[1] != expected[1] {
+        return Err(ApSumError::SumMismatch);
+    }
+    Ok(())
+}
+
+fn expected_instance(first: u128, step: u128, count: usize) -> Result<Vec<Fp>, ApSumError> {
+    let sum = ap_sum(first, step, count)?;
+    Ok(InstanceBuilder::new()
+        .first(Fp::from_u128(first))
+        .sum(Fp::from_u128(sum))
+        .build())
+}
+
+/// Verifies `proof` against `instance`, reading it with the transcript `T`.
+#[must_use = "the proof is only valid if this returns `true`"]
+pub fn verify_with_transcript<'a, T>(
+    params: &Params,
+    vk: &VerifyingKey<EqAffine>,
+    proof: &'a Proof,
+    instance: &[Fp],
+) -> bool
+where
+    T: TranscriptReadBuffer<&'a [u8], EqAffine, Challenge255<EqAffine>>,
+{
+    let strategy = SingleStrategy::new(params);
+    let mut transcript = T::init(proof.as_bytes());
+    verify_proof::<IPACommitmentScheme<EqAffine>, VerifierIPA<EqAffine>, _, _, _>(
+        params,
+        vk,
+        strategy,
+        &[&[instance]],
+        &mut transcript,
+    )
+    .is_ok()
+}
+
+/// Bytes of a compressed `EqAffine` point or of an `Fp` scalar in a proof.
+const ELEMENT_SIZE: usize = 32;
+
+/// Estimates the size in bytes of a proof of `ApSumCircuit<_, count>` at size `2^k`.
+///
+/// The proof size does not depend on `count` itself, only on the circuit shape and on
+/// `k`, which is raised to [`minimum_k`] if `count` rows do not fit. The estimate assumes
+/// the base circuit's two advice columns, three selectors and one instance column, with
+/// equality enabled on the sum and instance columns only.
+#[must_use]
+pub fn estimated_proof_size(count: usize, k: u32) -> usize {
+    let k = k.max(minimum_k(count)) as usize;
+    // advice (2), permutation products (2), vanishing random poly (1) and quotient
+    // pieces (2), multiopen quotient (1), IPA random poly (1) and rounds (2 per k)
+    let points = 2 + 2 + 1 + 2 + 1 + 1 + 2 * k;
+    // advice (4), fixed (3) and instance (1) queries, vanishing random eval (1), sigma
+    // (2) and permutation product evals (5), multiopen set evals (4), IPA final scalars (2)
+    let scalars = 4 + 3 + 1 + 1 + 2 + 5 + 4 + 2;
+    (points + scalars) * ELEMENT_SIZE
+}
+
+/// Returns the size of `proof` in bytes.
+#[must_use]
+pub fn actual_proof_size(proof: &Proof) -> usize {
+    proof.as_bytes().len()
+}
+
+/// Verifies Blake2b-transcript `proofs` against their instances as one batch.
+///
+/// The IPA openings of all proofs are accumulated into a single multi-scalar
+/// multiplication that is checked once, amortizing the verifier's most expensive step.
+/// Returns `Ok(false)` if any proof is invalid; instances of the wrong shape for `vk`
+/// are reported as an error.
+#[must_use = "the proof is only valid if this returns `Ok(true)`"]
+pub fn verify_batch(
+    params: &Params,
+    vk: &VerifyingKey<EqAffine>,
+    proofs: &[(Proof, Vec<Fp>)],
+) -> Result<bool, ApSumError> {
+    let mut strategy = AccumulatorStrategy::new(params);
+    for (proof, instance) in proofs {
+        let mut transcript = Blake2bVerifierTranscript::init(proof.as_bytes());
+        strategy = match verify_proof::<IPACommitmentScheme<EqAffine>, VerifierIPA<EqAffine>, _, _, _>(
+            params,
+            vk,
+            strategy,
+            &[&[instance]],
+            &mut transcript,
+        ) {
+            Ok(strategy) => strategy,
+            Err(Error::InvalidInstances) => {
+                return Err(ApSumError::Proving(Error::InvalidInstances))
+            }
+            Err(_) => return Ok(false),
+        };
+    }
+    Ok(strategy.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ApSumCircuit;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+    use rand_core::OsRng;
+
+    const K: u32 = 5;
+
+    fn instance() -> Vec<Fp> {
+        // 1 + 2 + 3 + 4 + 5 = 15
+        vec![Fp::from(1), Fp::from(15)]
+    }
+
+    #[test]
+    fn blake2b_round_trip() {
+        let (params, pk) = setup(K, &ApSumCircuit::<1, 5>).unwrap();
+        let proof = prove_with_transcript::<_, Blake2bProverTranscript>(
+            &params,
+            &pk,
+            ApSumCircuit::<1, 5>,
+            &instance(),
+            OsRng,
+        )
+        .unwrap();
+        assert!(verify_with_transcript::<Blake2bVerifierTranscript>(
+            &params,
+            pk.get_vk(),
+            &proof,
+            &instance()
+        ));
+        assert!(!verify_with_transcript::<KeccakVerifierTranscript>(
+            &params,
+            pk.get_vk(),
+            &proof,
+            &instance()
+        ));
+    }
+
+    #[test]
+    fn keccak_round_trip() {
+        let (params, pk) = setup(K, &ApSumCircuit::<1, 5>).unwrap();
+        let proof = prove_with_transcript::<_, KeccakProverTranscript>(
+            &params,
+            &pk,
+            ApSumCircuit::<1, 5>,
+            &instance(),
+            OsRng,
+        )
+        .unwrap();
+        assert!(verify_with_transcript::<KeccakVerifierTranscript>(
+            &params,
+            pk.get_vk(),
+            &proof,
+            &instance()
+        ));
+        assert!(!verify_with_transcript::<Blake2bVerifierTranscript>(
+            &params,
+            pk.get_vk(),
+            &proof,
+            &instance()
+        ));
+    }
+
+    #[test]
+    fn verify_expecting_derives_instance() {
+        let (params, pk) = setup(K, &ApSumCircuit::<1, 5>).unwrap();
+        let proof = prove(&params, &pk, ApSumCircuit::<1, 5>, &instance(), OsRng).unwrap();
+        assert!(verify_expecting(&params, pk.get_vk(), &proof, 1, 1, 5).unwrap());
+        // count 4 expects sum 10
+        assert!(!verify_expecting(&params, pk.get_vk(), &proof, 1, 1, 4).unwrap());
+    }
+
+    #[test]
+    fn check_public_inputs_accepts_matching_instance() {
+        assert!(check_public_inputs(&instance(), 1, 1, 5).is_ok());
+    }
+
+    #[test]
+    fn check_public_inputs_rejects_tampered_sum() {
+        let tampered = [Fp::from(1), Fp::from(16)];
+        assert!(matches!(
+            check_public_inputs(&tampered, 1, 1, 5),
+            Err(ApSumError::SumMismatch)
+        ));
+    }
+
+    #[test]
+    fn estimated_proof_size_is_close_to_actual() {
+        let (params, pk) = setup(K, &ApSumCircuit::<1, 5>).unwrap();
+        let proof = prove(&params, &pk, ApSumCircuit::<1, 5>, &instance(), OsRng).unwrap();
+        let estimate = estimated_proof_size(5, K);
+        let actual = actual_proof_size(&proof);
+        assert!(
+            actual / 2 <= estimate && estimate <= actual * 2,
+            "estimate {estimate} is not within 2x of actual {actual}"
+        );
+    }
+
+    #[test]
+    fn seeded_proofs_are_byte_identical() {
+        let (params, pk) = setup(K, &ApSumCircuit::<1, 5>).unwrap();
+        let run = || {
+            let rng = ChaCha20Rng::seed_from_u64(7);
+            prove(&params, &pk, ApSumCircuit::<1, 5>, &instance(), rng).unwrap()
+        };
+        let (first, second) = (run(), run());
+        assert_eq!(first, second);
+        assert!(verify(&params, pk.get_vk(), &first, &instance()));
+        assert!(verify(&params, pk.get_vk(), &second, &instance()));
+    }
+
+    #[test]
+    fn transcript_log_is_deterministic_for_a_seeded_rng() {
+        let (params, pk) = setup(K, &ApSumCircuit::<1, 5>).unwrap();
+        let run = || {
+            let rng = ChaCha20Rng::seed_from_u64(0);
+            prove_with_transcript_log(&params, &pk, ApSumCircuit::<1, 5>, &instance(), rng).unwrap()
+        };
+        let (proof, challenges) = run();
+        assert!(!challenges.is_empty());
+        assert_eq!(run(), (proof.clone(), challenges));
+        assert!(verify(&params, pk.get_vk(), &proof, &instance()));
+    }
+
+    #[test]
+    fn batch_of_valid_proofs_verifies() {
+        let (params, pk) = setup(K, &ApSumCircuit::<1, 5>).unwrap();
+        // first terms 1, 2, 3 give sums 15, 20, 25
+        let mut batch: Vec<_> = [(1, 15), (2, 20), (3, 25)]
+            .into_iter()
+            .map(|(first, sum)| {
+                let instance = vec![Fp::from(first), Fp::from(sum)];
+                let proof = prove(&params, &pk, ApSumCircuit::<1, 5>, &instance, OsRng).unwrap();
+                (proof, instance)
+            })
+            .collect();
+        assert!(verify_batch(&params, pk.get_vk(), &batch).unwrap());
+
+        let mut tampered = batch[1].0.clone().into_bytes();
+        let last = tampered.len() - 1;
+        tampered[last] ^= 1;
+        batch[1].0 = Proof::from_bytes(tampered);
+        assert!(!verify_batch(&params, pk.get_vk(), &batch).unwrap());
+    }
+
+    #[test]
+    fn proof_verifies_from_reader() {
+        let (params, pk) = setup(K, &ApSumCircuit::<1, 5>).unwrap();
+        let proof = prove(&params, &pk, ApSumCircuit::<1, 5>, &instance(), OsRng).unwrap();
+        let reader = io::Cursor::new(proof.as_bytes());
+        assert!(verify_from_reader(&params, pk.get_vk(), reader, &instance()).unwrap());
+
+        let truncated = &proof.as_bytes()[..proof.as_bytes().len() / 2];
+        assert!(matches!(
+            verify_from_reader(&params, pk.get_vk(), truncated, &instance()),
+            Err(ApSumError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn proof_verifies_with_reloaded_params() {
+        let (params, pk) = setup(K, &ApSumCircuit::<1, 5>).unwrap();
+        let proof = prove(&params, &pk, ApSumCircuit::<1, 5>, &instance(), OsRng).unwrap();
+
+        let path = std::env::temp_dir().join(format!("ap-sum-params-{}.bin", std::process::id()));
+        save_params(&path, &params).unwrap();
+        let loaded = load_params(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(verify(&loaded.unwrap(), pk.get_vk(), &proof, &instance()));
+    }
+
+    #[test]
+    fn fingerprint_guards_verification() {
+        let (params, pk) = setup(K, &ApSumCircuit::<1, 5>).unwrap();
+        let proof = prove(&params, &pk, ApSumCircuit::<1, 5>, &instance(), OsRng).unwrap();
+        let fingerprint = params_fingerprint(&params).unwrap();
+        assert!(
+            verify_with_fingerprint(&params, &fingerprint, pk.get_vk(), &proof, &instance())
+                .unwrap()
+        );
+
+        let other = params_fingerprint(&Params::new(K + 1)).unwrap();
+        assert!(matches!(
+            verify_with_fingerprint(&params, &other, pk.get_vk(), &proof, &instance()),
+            Err(ApSumError::ParamsFingerprintMismatch)
+        ));
+    }
+
+    #[test]
+    fn wrong_sum_does_not_verify() {
+        let (params, pk) = setup(K, &ApSumCircuit::<1, 5>).unwrap();
+        let proof = prove(&params, &pk, ApSumCircuit::<1, 5>, &instance(), OsRng).unwrap();
+        assert!(!verify(
+            &params,
+            pk.get_vk(),
+            &proof,
+            &[Fp::from(1), Fp::from(16)]
+        ));
+    }
+}
@@ -0,0 +1,164 @@
+//! Sum of a sequence given entirely as public input.
+//!
+//! Each term `a_n` is copied from instance row `n`, and the table only accumulates the
+//! running sum; there is no step gate, so the terms need not form a progression.
+//!
+//! Instance layout: `[a_0, ..., a_{COUNT-1}, sum]`.
+
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+use std::marker::PhantomData;
+
+#[derive(Clone, Debug)]
+pub struct PublicTermsSumConfig {
+    // [a_n, sum_n]
+    advice: [Column<Advice>; 2],
+    selector: Selector,
+    instance: Column<Instance>,
+}
+
+struct PublicTermsSumChip<F, const COUNT: usize> {
+    config: PublicTermsSumConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const COUNT: usize> PublicTermsSumChip<F, COUNT> {
+    fn construct(config: PublicTermsSumConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 2],
+        instance: Column<Instance>,
+    ) -> PublicTermsSumConfig {
+        let selector = meta.selector();
+
+        meta.enable_equality(advice[0]);
+        meta.enable_equality(advice[1]);
+        meta.enable_equality(instance);
+
+        // |  advice[0]   |    advice[1]   | selector
+        // ------------------------------------------
+        // |     a_0      |     sum_0      |
+        // |     a_1      |     sum_1      |    s
+        // |     ...      |     ...        |    s
+        meta.create_gate("sum", |meta| {
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let sum = meta.query_advice(advice[1], Rotation::cur());
+            let prev_sum = meta.query_advice(advice[1], Rotation::prev());
+            let s = meta.query_selector(selector);
+            // sum == a + prev_sum
+            vec![s * (a + prev_sum - sum)]
+        });
+
+        PublicTermsSumConfig {
+            advice,
+            selector,
+            instance,
+        }
+    }
+
+    fn assign(&self, mut layouter: impl Layouter<F>) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "public terms sum table",
+            |mut region| {
+                let a_column = self.config.advice[0];
+                let sum_column = self.config.advice[1];
+
+                // Copy a_0 into both a_0 and sum_0. No selector needed for first row.
+                region.assign_advice_from_instance(|| "a", self.config.instance, 0, a_column, 0)?;
+                let mut sum_cell = region.assign_advice_from_instance(
+                    || "sum",
+                    self.config.instance,
+                    0,
+                    sum_column,
+                    0,
+                )?;
+
+                for row in 1..COUNT {
+                    self.config.selector.enable(&mut region, row)?;
+
+                    let a_cell = region.assign_advice_from_instance(
+                        || "a",
+                        self.config.instance,
+                        row,
+                        a_column,
+                        row,
+                    )?;
+                    let new_sum = sum_cell.value().copied() + a_cell.value().copied();
+                    sum_cell = region.assign_advice(|| "sum", sum_column, row, || new_sum)?;
+                }
+
+                Ok(sum_cell)
+            },
+        )
+    }
+
+    fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+#[derive(Default)]
+pub struct PublicTermsSumCircuit<const COUNT: usize>;
+
+impl<F: FieldExt, const COUNT: usize> Circuit<F> for PublicTermsSumCircuit<COUNT> {
+    type Config = PublicTermsSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+
+        PublicTermsSumChip::<_, COUNT>::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = PublicTermsSumChip::<_, COUNT>::construct(config);
+        let sum_cell = chip.assign(layouter.namespace(|| "public terms sum table"))?;
+        chip.expose_public(layouter.namespace(|| "output"), &sum_cell, COUNT)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, halo2curves::pasta::Fp};
+
+    fn run(instance: [u64; 5]) -> bool {
+        let k = 4;
+        let instance = instance.map(Fp::from).to_vec();
+        MockProver::run(k, &PublicTermsSumCircuit::<4>, vec![instance])
+            .unwrap()
+            .verify()
+            .is_ok()
+    }
+
+    #[test]
+    fn public_terms_two_four_six_eight_sum_to_twenty() {
+        assert!(run([2, 4, 6, 8, 20]));
+    }
+
+    #[test]
+    fn terms_need_not_be_a_progression() {
+        assert!(run([5, 1, 9, 2, 17]));
+        assert!(!run([2, 4, 6, 8, 21]));
+    }
+}
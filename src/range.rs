@@ -0,0 +1,283 @@
+//! Lookup-based range checks and the comparisons built on them.
+//!
+//! [`RangeCheckConfig`] constrains a cell to `[0, 2^RANGE_BITS)` by looking it up in a
+//! fixed table, so a circuit using it needs `k > RANGE_BITS`. [`ComparisonConfig`] proves
+//! `lhs < rhs` or `lhs <= rhs` by range-checking the difference; this is only sound when
+//! both operands are themselves known to be below `2^RANGE_BITS`, e.g. because they are
+//! determined by public inputs. [`GreaterThanChip`] packages the strict comparison as a
+//! chip with its own columns.
+
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+use std::marker::PhantomData;
+
+#[derive(Clone, Copy, Debug)]
+pub struct RangeCheckConfig<const RANGE_BITS: usize> {
+    value: Column<Advice>,
+    q_range: Selector,
+    table: TableColumn,
+}
+
+impl<const RANGE_BITS: usize> RangeCheckConfig<RANGE_BITS> {
+    pub fn configure<F: FieldExt>(meta: &mut ConstraintSystem<F>, value: Column<Advice>) -> Self {
+        let q_range = meta.complex_selector();
+        let table = meta.lookup_table_column();
+
+        meta.lookup("range check", |meta| {
+            let q = meta.query_selector(q_range);
+            let v = meta.query_advice(value, Rotation::cur());
+            // Disabled rows look up 0, which is in the table.
+            vec![(q * v, table)]
+        });
+
+        Self {
+            value,
+            q_range,
+            table,
+        }
+    }
+
+    /// Fills the lookup table with `0..2^RANGE_BITS`. Must be called once per circuit.
+    pub fn load_table<F: FieldExt>(&self, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_table(
+            || "range table",
+            |mut table| {
+                for i in 0..1 << RANGE_BITS {
+                    table.assign_cell(
+                        || "value",
+                        self.table,
+                        i,
+                        || Value::known(F::from(i as u64)),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Assigns `value` at `offset` and range-checks it.
+    pub fn assign<F: FieldExt>(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        value: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.q_range.enable(region, offset)?;
+        region.assign_advice(|| "range checked", self.value, offset, || value)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ComparisonConfig<const RANGE_BITS: usize> {
+    // [lhs, rhs]
+    operands: [Column<Advice>; 2],
+    q_lt: Selector,
+    q_le: Selector,
+    diff: RangeCheckConfig<RANGE_BITS>,
+}
+
+impl<const RANGE_BITS: usize> ComparisonConfig<RANGE_BITS> {
+    /// Configures comparisons over `operands`, which must have equality enabled, using
+    /// `diff` for the range-checked difference.
+    pub fn configure<F: FieldExt>(
+        meta: &mut ConstraintSystem<F>,
+        operands: [Column<Advice>; 2],
+        diff: Column<Advice>,
+    ) -> Self {
+        let q_lt = meta.selector();
+        let q_le = meta.selector();
+        let range = RangeCheckConfig::configure(meta, diff);
+
+        // |  lhs  |  rhs  |  diff  | q_lt | q_le
+        // ---------------------------------------
+        // |   l   |   r   |    d   |  s   |        d == r - l - 1
+        // |   l   |   r   |    d   |      |  s     d == r - l
+        meta.create_gate("comparison", |meta| {
+            let lhs = meta.query_advice(operands[0], Rotation::cur());
+            let rhs = meta.query_advice(operands[1], Rotation::cur());
+            let d = meta.query_advice(diff, Rotation::cur());
+            let q_lt = meta.query_selector(q_lt);
+            let q_le = meta.query_selector(q_le);
+            vec![
+                q_lt * (rhs.clone() - lhs.clone() - Expression::Constant(F::one()) - d.clone()),
+                q_le * (rhs - lhs - d),
+            ]
+        });
+
+        Self {
+            operands,
+            q_lt,
+            q_le,
+            diff: range,
+        }
+    }
+
+    pub fn load_table<F: FieldExt>(&self, layouter: impl Layouter<F>) -> Result<(), Error> {
+        self.diff.load_table(layouter)
+    }
+
+    /// Constrains `lhs < rhs`.
+    pub fn assert_less_than<F: FieldExt>(
+        &self,
+        layouter: impl Layouter<F>,
+        lhs: &AssignedCell<F, F>,
+        rhs: &AssignedCell<F, F>,
+    ) -> Result<(), Error> {
+        self.assign(layouter, lhs, rhs, true)
+    }
+
+    /// Constrains `lhs <= rhs`.
+    pub fn assert_less_or_equal<F: FieldExt>(
+        &self,
+        layouter: impl Layouter<F>,
+        lhs: &AssignedCell<F, F>,
+        rhs: &AssignedCell<F, F>,
+    ) -> Result<(), Error> {
+        self.assign(layouter, lhs, rhs, false)
+    }
+
+    fn assign<F: FieldExt>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        lhs: &AssignedCell<F, F>,
+        rhs: &AssignedCell<F, F>,
+        strict: bool,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "comparison",
+            |mut region| {
+                let selector = if strict { self.q_lt } else { self.q_le };
+                selector.enable(&mut region, 0)?;
+
+                let lhs = lhs.copy_advice(|| "lhs", &mut region, self.operands[0], 0)?;
+                let rhs = rhs.copy_advice(|| "rhs", &mut region, self.operands[1], 0)?;
+                let offset = F::from(u64::from(strict));
+                let diff = rhs
+                    .value()
+                    .zip(lhs.value())
+                    .map(|(rhs, lhs)| *rhs - lhs - offset);
+                self.diff.assign(&mut region, 0, diff)?;
+                Ok(())
+            },
+        )
+    }
+}
+
+/// Proves `lhs > rhs` for cells assigned elsewhere, under the same range assumptions as
+/// [`ComparisonConfig`].
+pub struct GreaterThanChip<F, const RANGE_BITS: usize> {
+    config: ComparisonConfig<RANGE_BITS>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const RANGE_BITS: usize> GreaterThanChip<F, RANGE_BITS> {
+    pub fn construct(config: ComparisonConfig<RANGE_BITS>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Configures the comparison over three fresh advice columns.
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> ComparisonConfig<RANGE_BITS> {
+        let operands = [meta.advice_column(), meta.advice_column()];
+        for column in operands {
+            meta.enable_equality(column);
+        }
+        let diff = meta.advice_column();
+        ComparisonConfig::configure(meta, operands, diff)
+    }
+
+    pub fn load_table(&self, layouter: impl Layouter<F>) -> Result<(), Error> {
+        self.config.load_table(layouter)
+    }
+
+    /// Constrains `lhs > rhs`.
+    pub fn assert_greater_than(
+        &self,
+        layouter: impl Layouter<F>,
+        lhs: &AssignedCell<F, F>,
+        rhs: &AssignedCell<F, F>,
+    ) -> Result<(), Error> {
+        self.config.assert_less_than(layouter, rhs, lhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, halo2curves::pasta::Fp};
+
+    #[derive(Default)]
+    struct CompareCircuit {
+        lhs: Value<Fp>,
+        rhs: Value<Fp>,
+        strict: bool,
+    }
+
+    impl Circuit<Fp> for CompareCircuit {
+        type Config = ComparisonConfig<4>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                strict: self.strict,
+                ..Self::default()
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let operands = [meta.advice_column(), meta.advice_column()];
+            meta.enable_equality(operands[0]);
+            meta.enable_equality(operands[1]);
+            let diff = meta.advice_column();
+            ComparisonConfig::configure(meta, operands, diff)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            config.load_table(layouter.namespace(|| "range table"))?;
+            let (lhs, rhs) = layouter.assign_region(
+                || "operands",
+                |mut region| {
+                    let lhs = region.assign_advice(|| "lhs", config.operands[0], 0, || self.lhs)?;
+                    let rhs = region.assign_advice(|| "rhs", config.operands[1], 0, || self.rhs)?;
+                    Ok((lhs, rhs))
+                },
+            )?;
+            if self.strict {
+                config.assert_less_than(layouter.namespace(|| "lt"), &lhs, &rhs)
+            } else {
+                config.assert_less_or_equal(layouter.namespace(|| "le"), &lhs, &rhs)
+            }
+        }
+    }
+
+    fn compare(lhs: u64, rhs: u64, strict: bool) -> bool {
+        let circuit = CompareCircuit {
+            lhs: Value::known(Fp::from(lhs)),
+            rhs: Value::known(Fp::from(rhs)),
+            strict,
+        };
+        MockProver::run(5, &circuit, vec![])
+            .unwrap()
+            .verify()
+            .is_ok()
+    }
+
+    #[test]
+    fn less_than_works() {
+        assert!(compare(3, 4, true));
+        assert!(!compare(4, 4, true));
+        assert!(!compare(5, 4, true));
+    }
+
+    #[test]
+    fn less_or_equal_works() {
+        assert!(compare(3, 4, false));
+        assert!(compare(4, 4, false));
+        assert!(!compare(5, 4, false));
+    }
+}
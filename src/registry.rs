@@ -0,0 +1,102 @@
+//! Thread-safe in-memory store of proofs, for services built on this crate.
+//!
+//! [`ProofRegistry`] maps a request id to the proof generated for it and the instance it
+//! verifies against. It only holds proofs; transport and eviction are up to the caller.
+
+use crate::proof::Proof;
+use halo2_proofs::halo2curves::pasta::Fp;
+use std::{
+    collections::HashMap,
+    sync::{PoisonError, RwLock},
+};
+
+/// A proof together with its public instance.
+pub type Entry = (Proof, Vec<Fp>);
+
+#[derive(Debug, Default)]
+pub struct ProofRegistry {
+    entries: RwLock<HashMap<String, Entry>>,
+}
+
+impl ProofRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stores `proof` and `instance` under `id`, returning the entry it replaces.
+    pub fn store(&self, id: impl Into<String>, proof: Proof, instance: Vec<Fp>) -> Option<Entry> {
+        // A panic while holding the lock cannot leave the map half-updated, so a
+        // poisoned lock is still safe to use.
+        self.entries
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(id.into(), (proof, instance))
+    }
+
+    /// Returns a copy of the entry stored under `id`, if any.
+    pub fn fetch(&self, id: &str) -> Option<Entry> {
+        self.entries
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(id)
+            .cloned()
+    }
+
+    /// Returns the number of stored entries.
+    pub fn len(&self) -> usize {
+        self.entries
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    fn entry(n: u64) -> Entry {
+        (
+            Proof::from_bytes(n.to_le_bytes().to_vec()),
+            vec![Fp::from(1), Fp::from(n)],
+        )
+    }
+
+    #[test]
+    fn concurrent_stores_are_all_fetchable() {
+        let registry = ProofRegistry::new();
+        thread::scope(|scope| {
+            for n in 0..8 {
+                let registry = &registry;
+                scope.spawn(move || {
+                    let (proof, instance) = entry(n);
+                    registry.store(format!("request-{n}"), proof, instance);
+                    assert_eq!(registry.fetch(&format!("request-{n}")), Some(entry(n)));
+                });
+            }
+        });
+
+        assert_eq!(registry.len(), 8);
+        for n in 0..8 {
+            assert_eq!(registry.fetch(&format!("request-{n}")), Some(entry(n)));
+        }
+    }
+
+    #[test]
+    fn unknown_id_is_a_miss() {
+        let registry = ProofRegistry::new();
+        assert!(registry.is_empty());
+        assert_eq!(registry.fetch("missing"), None);
+
+        let (proof, instance) = entry(1);
+        assert_eq!(registry.store("id", proof, instance), None);
+        let (proof, instance) = entry(2);
+        assert_eq!(registry.store("id", proof, instance), Some(entry(1)));
+        assert_eq!(registry.fetch("missing"), None);
+    }
+}
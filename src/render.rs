@@ -0,0 +1,108 @@
+//! Circuit layout rendering for the `dev-graph` feature.
+//!
+//! The canvas height grows with the number of rows shown, so large tables stay readable.
+//! Canvases beyond [`MAX_DIMENSION`] are refused with [`ApSumError::RenderTooLarge`]
+//! rather than handed to the bitmap backend.
+
+use crate::ApSumError;
+use halo2_proofs::{arithmetic::FieldExt, dev::CircuitLayout, plonk::Circuit};
+use plotters::{coord::Shift, prelude::*};
+use std::path::Path;
+
+const WIDTH: u32 = 1024;
+const PIXELS_PER_ROW: u32 = 16;
+const TITLE_HEIGHT: u32 = 100;
+
+/// Largest canvas side, in pixels, that will be rendered.
+pub const MAX_DIMENSION: u32 = 1 << 15;
+
+/// Returns the `(width, height)` canvas used to show `rows` rows.
+pub fn canvas_size(rows: usize) -> Result<(u32, u32), ApSumError> {
+    u32::try_from(rows)
+        .ok()
+        .and_then(|rows| rows.checked_mul(PIXELS_PER_ROW))
+        .and_then(|height| height.checked_add(TITLE_HEIGHT))
+        .filter(|height| *height <= MAX_DIMENSION)
+        .map(|height| (WIDTH, height))
+        .ok_or(ApSumError::RenderTooLarge { rows })
+}
+
+/// Renders the first `rows` rows of the layout of `circuit` to a PNG at `path`.
+///
+/// `rows` must not exceed `2^k`.
+pub fn render_to_file<F: FieldExt, C: Circuit<F>>(
+    path: impl AsRef<Path>,
+    k: u32,
+    circuit: &C,
+    rows: usize,
+) -> Result<(), ApSumError> {
+    let size = canvas_size(rows)?;
+    let root = BitMapBackend::new(path.as_ref(), size).into_drawing_area();
+    draw::<F, _, _>(root, k, circuit, rows)
+}
+
+/// Renders the first `rows` rows of the layout of `circuit` into an RGB buffer, returned
+/// with its `(width, height)`.
+pub fn render_to_buffer<F: FieldExt, C: Circuit<F>>(
+    k: u32,
+    circuit: &C,
+    rows: usize,
+) -> Result<(Vec<u8>, (u32, u32)), ApSumError> {
+    let (width, height) = canvas_size(rows)?;
+    let mut buffer = vec![0; width as usize * height as usize * 3];
+    {
+        let root = BitMapBackend::with_buffer(&mut buffer, (width, height)).into_drawing_area();
+        draw::<F, _, _>(root, k, circuit, rows)?;
+    }
+    Ok((buffer, (width, height)))
+}
+
+fn draw<F: FieldExt, C: Circuit<F>, DB: DrawingBackend>(
+    root: DrawingArea<DB, Shift>,
+    k: u32,
+    circuit: &C,
+    rows: usize,
+) -> Result<(), ApSumError> {
+    let render_error = |e: DrawingAreaErrorKind<DB::ErrorType>| ApSumError::Render(e.to_string());
+
+    root.fill(&WHITE).map_err(render_error)?;
+    let root = root
+        .titled("AP sum layout", ("sans-serif", 60))
+        .map_err(render_error)?;
+    CircuitLayout::default()
+        .view_height(0..rows)
+        .render::<F, _, _>(k, circuit, &root)
+        .map_err(render_error)?;
+    root.present().map_err(render_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ApSumCircuit;
+    use halo2_proofs::halo2curves::pasta::Fp;
+
+    #[test]
+    fn canvas_grows_with_rows() {
+        let (_, small) = canvas_size(5).unwrap();
+        let (_, large) = canvas_size(500).unwrap();
+        assert!(large > small);
+    }
+
+    #[test]
+    fn oversized_canvas_is_refused() {
+        assert!(matches!(
+            canvas_size(1 << 20),
+            Err(ApSumError::RenderTooLarge { rows }) if rows == 1 << 20
+        ));
+    }
+
+    #[test]
+    fn render_to_buffer_draws_layout() {
+        let circuit = ApSumCircuit::<1, 40>;
+        let (buffer, (width, height)) = render_to_buffer::<Fp, _>(6, &circuit, 40).unwrap();
+        assert_eq!(buffer.len(), width as usize * height as usize * 3);
+        // anything other than the white background was drawn
+        assert!(buffer.iter().any(|byte| *byte != 0xff));
+    }
+}
@@ -0,0 +1,217 @@
+//! Sum of an arithmetic progression whose running sum resets every `reset_period` terms.
+//!
+//! The terms keep progressing by `STEP` across blocks, but on the first row of each block
+//! a reset selector drops `prev_sum` from the sum constraint, so `sum_n = a_n` there. The
+//! running sum at the end of every block is exposed; a shorter last block is exposed
+//! too. For step `1`, count `6` and reset period `3`, the block sums are `1 + 2 + 3 = 6`
+//! and `4 + 5 + 6 = 15`.
+//!
+//! Instance layout: `[first, block_sum_0, block_sum_1, ...]`.
+
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+use std::marker::PhantomData;
+
+#[derive(Clone, Debug)]
+pub struct ResetApSumConfig {
+    // [a_n, sum_n]
+    advice: [Column<Advice>; 2],
+    s_step: Selector,
+    s_reset: Selector,
+    instance: Column<Instance>,
+}
+
+struct ResetApSumChip<F, const STEP: u128, const COUNT: usize> {
+    config: ResetApSumConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> ResetApSumChip<F, STEP, COUNT> {
+    fn construct(config: ResetApSumConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 2],
+        instance: Column<Instance>,
+    ) -> ResetApSumConfig {
+        let s_step = meta.selector();
+        let s_reset = meta.selector();
+
+        meta.enable_equality(advice[0]);
+        meta.enable_equality(advice[1]);
+        meta.enable_equality(instance);
+
+        // |  advice[0]   |    advice[1]   | s_step | s_reset
+        // ---------------------------------------------------
+        // |     a_0      |     sum_0      |        |
+        // |     a_1      |     sum_1      |   s    |
+        // |     a_2      |     sum_2      |   s    |
+        // |     a_3      |     sum_3      |   s    |    s       (reset_period = 3)
+        // |     ...      |     ...        |   s    |   ...
+        meta.create_gate("step and resettable sum", |meta| {
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let sum = meta.query_advice(advice[1], Rotation::cur());
+            let prev_a = meta.query_advice(advice[0], Rotation::prev());
+            let prev_sum = meta.query_advice(advice[1], Rotation::prev());
+            let s = meta.query_selector(s_step);
+            let reset = meta.query_selector(s_reset);
+            vec![
+                // sum == a + prev_sum, or sum == a on a reset row
+                s.clone() * (a.clone() + (Expression::Constant(F::one()) - reset) * prev_sum - sum),
+                // a == prev_a + STEP
+                s * (a - prev_a - Expression::Constant(F::from_u128(STEP))),
+            ]
+        });
+
+        ResetApSumConfig {
+            advice,
+            s_step,
+            s_reset,
+            instance,
+        }
+    }
+
+    /// Assigns the table, returning the running sum cell at the end of each block.
+    ///
+    /// Fails with [`Error::Synthesis`] if `reset_period` is zero.
+    fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        reset_period: usize,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        if reset_period == 0 {
+            return Err(Error::Synthesis);
+        }
+
+        layouter.assign_region(
+            || "reset AP sum table",
+            |mut region| {
+                let a_column = self.config.advice[0];
+                let sum_column = self.config.advice[1];
+                let step = F::from_u128(STEP);
+
+                // Copy first instance into both a_0 and sum_0. No selector needed for first row.
+                let mut a_cell = region.assign_advice_from_instance(
+                    || "a",
+                    self.config.instance,
+                    0,
+                    a_column,
+                    0,
+                )?;
+                let mut sum_cell = region.assign_advice_from_instance(
+                    || "sum",
+                    self.config.instance,
+                    0,
+                    sum_column,
+                    0,
+                )?;
+                let mut block_sums = Vec::with_capacity(COUNT.div_ceil(reset_period));
+
+                for row in 1..COUNT {
+                    if row % reset_period == 0 {
+                        block_sums.push(sum_cell.clone());
+                    }
+
+                    self.config.s_step.enable(&mut region, row)?;
+                    let reset = row % reset_period == 0;
+                    if reset {
+                        self.config.s_reset.enable(&mut region, row)?;
+                    }
+
+                    let new_a_val = a_cell.value().map(|a| *a + step);
+                    a_cell = region.assign_advice(|| "a", a_column, row, || new_a_val)?;
+
+                    let new_sum = if reset {
+                        new_a_val
+                    } else {
+                        sum_cell.value().copied() + new_a_val
+                    };
+                    sum_cell = region.assign_advice(|| "sum", sum_column, row, || new_sum)?;
+                }
+                block_sums.push(sum_cell);
+
+                Ok(block_sums)
+            },
+        )
+    }
+
+    fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct ResetApSumCircuit<const STEP: u128, const COUNT: usize> {
+    /// Number of terms in each block; the running sum restarts at every multiple of it.
+    pub reset_period: usize,
+}
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> Circuit<F>
+    for ResetApSumCircuit<STEP, COUNT>
+{
+    type Config = ResetApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        // The reset period shapes the selectors, not the witness.
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+
+        ResetApSumChip::<_, STEP, COUNT>::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = ResetApSumChip::<_, STEP, COUNT>::construct(config);
+        let block_sums = chip.assign(
+            layouter.namespace(|| "reset AP sum table"),
+            self.reset_period,
+        )?;
+        for (block, cell) in block_sums.iter().enumerate() {
+            chip.expose_public(layouter.namespace(|| "block sum"), cell, block + 1)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, halo2curves::pasta::Fp};
+
+    #[test]
+    fn block_sums_of_step_one_count_six_period_three_work() {
+        let k = 4;
+        let circuit = ResetApSumCircuit::<1, 6> { reset_period: 3 };
+        // 1 + 2 + 3 = 6 and 4 + 5 + 6 = 15
+        let instance = vec![Fp::from(1), Fp::from(6), Fp::from(15)];
+        let prover = MockProver::run(k, &circuit, vec![instance]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn sum_carried_across_the_boundary_is_rejected() {
+        let k = 4;
+        let circuit = ResetApSumCircuit::<1, 6> { reset_period: 3 };
+        // 1 + ... + 6 = 21 would hold without the reset
+        let instance = vec![Fp::from(1), Fp::from(6), Fp::from(21)];
+        let prover = MockProver::run(k, &circuit, vec![instance]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
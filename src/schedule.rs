@@ -0,0 +1,245 @@
+//! Sum of a progression whose step changes at known row thresholds.
+//!
+//! A `step_schedule` of `(row, step)` pairs, sorted by row and starting at row `0`, sets
+//! the step into each row to that of the last entry at or before it. For a tariff that
+//! moves from step `1` to step `3` at row `3`, the schedule is `[(0, 1), (3, 3)]`, and
+//! from first term `1` the terms are `1, 2, 3, 6, 9`. The step of every row is written to
+//! a fixed column that the step gate reads, so the schedule is part of the key.
+//!
+//! Instance layout: `[first, sum]`.
+
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+use std::marker::PhantomData;
+
+#[derive(Clone, Debug)]
+pub struct ScheduledApSumConfig {
+    // [a_n, sum_n]
+    advice: [Column<Advice>; 2],
+    step: Column<Fixed>,
+    selector: Selector,
+    instance: Column<Instance>,
+}
+
+/// Returns the step into `row` under `step_schedule`, or `None` if no entry covers it.
+fn step_at(step_schedule: &[(usize, u128)], row: usize) -> Option<u128> {
+    step_schedule
+        .iter()
+        .take_while(|(start, _)| *start <= row)
+        .last()
+        .map(|(_, step)| *step)
+}
+
+/// Checks that `step_schedule` is sorted by row and starts at row `0`.
+fn is_valid(step_schedule: &[(usize, u128)]) -> bool {
+    matches!(step_schedule.first(), Some((0, _)))
+        && step_schedule.windows(2).all(|pair| pair[0].0 < pair[1].0)
+}
+
+/// Computes the `count` terms from `first` under `step_schedule`.
+///
+/// Returns `None` if the schedule is not sorted from row `0` or a term overflows `u128`.
+pub fn scheduled_terms(
+    first: u128,
+    step_schedule: &[(usize, u128)],
+    count: usize,
+) -> Option<Vec<u128>> {
+    if !is_valid(step_schedule) {
+        return None;
+    }
+    let mut terms = Vec::with_capacity(count);
+    let mut term = first;
+    for row in 0..count {
+        if row > 0 {
+            term = term.checked_add(step_at(step_schedule, row)?)?;
+        }
+        terms.push(term);
+    }
+    Some(terms)
+}
+
+struct ScheduledApSumChip<F, const COUNT: usize> {
+    config: ScheduledApSumConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const COUNT: usize> ScheduledApSumChip<F, COUNT> {
+    fn construct(config: ScheduledApSumConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 2],
+        instance: Column<Instance>,
+    ) -> ScheduledApSumConfig {
+        let step = meta.fixed_column();
+        let selector = meta.selector();
+
+        meta.enable_equality(advice[0]);
+        meta.enable_equality(advice[1]);
+        meta.enable_equality(instance);
+
+        // |  advice[0]   |    advice[1]   |   step   | selector
+        // ------------------------------------------------------
+        // |     a_0      |     sum_0      |          |
+        // |     a_1      |     sum_1      |  step_1  |    s
+        // |     a_2      |     sum_2      |  step_2  |    s
+        // |     ...      |     ...        |   ...    |    s
+        meta.create_gate("scheduled step and sum", |meta| {
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let sum = meta.query_advice(advice[1], Rotation::cur());
+            let prev_a = meta.query_advice(advice[0], Rotation::prev());
+            let prev_sum = meta.query_advice(advice[1], Rotation::prev());
+            let step = meta.query_fixed(step, Rotation::cur());
+            let s = meta.query_selector(selector);
+            vec![
+                // sum == a + prev_sum
+                s.clone() * (a.clone() + prev_sum - sum),
+                // a == prev_a + step_n
+                s * (a - prev_a - step),
+            ]
+        });
+
+        ScheduledApSumConfig {
+            advice,
+            step,
+            selector,
+            instance,
+        }
+    }
+
+    /// Assigns the table with the steps of `step_schedule` in the fixed column.
+    ///
+    /// Fails with [`Error::Synthesis`] if the schedule is not sorted from row `0`.
+    fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        step_schedule: &[(usize, u128)],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        if !is_valid(step_schedule) {
+            return Err(Error::Synthesis);
+        }
+
+        layouter.assign_region(
+            || "scheduled AP sum table",
+            |mut region| {
+                let [a_column, sum_column] = self.config.advice;
+
+                // Copy first instance into both a_0 and sum_0. No selector needed for first row.
+                let mut a_cell = region.assign_advice_from_instance(
+                    || "a",
+                    self.config.instance,
+                    0,
+                    a_column,
+                    0,
+                )?;
+                let mut sum_cell = region.assign_advice_from_instance(
+                    || "sum",
+                    self.config.instance,
+                    0,
+                    sum_column,
+                    0,
+                )?;
+
+                for row in 1..COUNT {
+                    self.config.selector.enable(&mut region, row)?;
+                    let step = step_at(step_schedule, row).ok_or(Error::Synthesis)?;
+                    let step = F::from_u128(step);
+                    region.assign_fixed(|| "step", self.config.step, row, || Value::known(step))?;
+
+                    let new_a_val = a_cell.value().map(|a| *a + step);
+                    a_cell = region.assign_advice(|| "a", a_column, row, || new_a_val)?;
+
+                    let new_sum = sum_cell.value().copied() + new_a_val;
+                    sum_cell = region.assign_advice(|| "sum", sum_column, row, || new_sum)?;
+                }
+
+                Ok(sum_cell)
+            },
+        )
+    }
+
+    fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct ScheduledApSumCircuit<const COUNT: usize> {
+    /// `(row, step)` pairs sorted by row, the first at row `0`.
+    pub step_schedule: Vec<(usize, u128)>,
+}
+
+impl<F: FieldExt, const COUNT: usize> Circuit<F> for ScheduledApSumCircuit<COUNT> {
+    type Config = ScheduledApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        // The schedule fills the fixed column, not the witness.
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+
+        ScheduledApSumChip::<_, COUNT>::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = ScheduledApSumChip::<_, COUNT>::construct(config);
+        let sum_cell = chip.assign(
+            layouter.namespace(|| "scheduled AP sum table"),
+            &self.step_schedule,
+        )?;
+        chip.expose_public(layouter.namespace(|| "output"), &sum_cell, 1)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, halo2curves::pasta::Fp};
+
+    fn tariff() -> ScheduledApSumCircuit<5> {
+        // step 1 up to row 2, then step 3
+        ScheduledApSumCircuit {
+            step_schedule: vec![(0, 1), (3, 3)],
+        }
+    }
+
+    #[test]
+    fn step_changing_at_row_three_works() {
+        let terms = scheduled_terms(1, &tariff().step_schedule, 5).unwrap();
+        assert_eq!(terms, [1, 2, 3, 6, 9]);
+        let sum: u128 = terms.iter().sum();
+        assert_eq!(sum, 21);
+
+        let instance = vec![Fp::from(1), Fp::from_u128(sum)];
+        let prover = MockProver::run(4, &tariff(), vec![instance]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn sum_ignoring_the_schedule_change_is_rejected() {
+        // 1 + 2 + 3 + 4 + 5 = 15 would hold with step 1 throughout
+        let instance = vec![Fp::from(1), Fp::from(15)];
+        let prover = MockProver::run(4, &tariff(), vec![instance]).unwrap();
+        assert!(prover.verify().is_err());
+
+        assert_eq!(scheduled_terms(1, &[(1, 1)], 5), None);
+    }
+}
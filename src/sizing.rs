@@ -0,0 +1,232 @@
+//! Choosing the circuit size `k` from the rows a layout uses.
+//!
+//! halo2 reserves the last rows of every column for blinding factors, and a lookup table
+//! of `2^RANGE_BITS` entries has to fit in the usable rows just like the assigned regions.
+//! The required `k` is therefore driven by whichever of the two is larger.
+//!
+//! A larger `k` than needed only costs proving time, so [`is_k_minimal`] checks a chosen
+//! size for an [`crate::ApSumCircuit`] of `count` terms against [`suggest_k`], and
+//! [`check_row`] rejects a `k` that is too small before halo2 reports it mid-synthesis.
+
+use crate::{
+    chip::{MIN_REGION_ROWS, SUM_ONLY_BOUNDARY_ROWS},
+    ApSumCircuit, ApSumError, DEFAULT_MAX_REGION_ROWS,
+};
+use halo2_proofs::{arithmetic::FieldExt, halo2curves::pasta::Fp, plonk::*};
+
+/// Rows at the end of each column that are not usable when no column is queried at more
+/// than three rotations: halo2 reserves `max(3, queries) + 2 = 5` blinding factors plus
+/// one more row. [`reserved_rows`] derives the reserve of a configured circuit.
+pub const RESERVED_ROWS: usize = 6;
+
+/// Returns the rows halo2 reserves at the end of each column of `C`, its
+/// [`ConstraintSystem::blinding_factors`] plus one.
+#[must_use]
+pub fn reserved_rows<F: FieldExt, C: Circuit<F>>() -> usize {
+    let mut meta = ConstraintSystem::default();
+    C::configure(&mut meta);
+    meta.blinding_factors() + 1
+}
+
+/// Returns the smallest `k` whose `2^k` rows fit `rows` usable rows, reserving
+/// [`RESERVED_ROWS`].
+#[must_use]
+pub const fn minimum_k(rows: usize) -> u32 {
+    minimum_k_reserving(rows, RESERVED_ROWS)
+}
+
+/// Returns the smallest `k` whose `2^k` rows fit `rows` usable rows after `reserved` rows,
+/// as returned by [`reserved_rows`].
+#[must_use]
+pub const fn minimum_k_reserving(rows: usize, reserved: usize) -> u32 {
+    let needed = rows + reserved;
+    let mut k = 0;
+    while (1usize << k) < needed {
+        k += 1;
+    }
+    k
+}
+
+/// Returns the smallest `k` that fits both `rows` usable rows and a range-check table of
+/// `2^range_bits` entries, as used by [`crate::range::RangeCheckConfig`].
+#[must_use]
+pub const fn minimum_k_with_lookup(rows: usize, range_bits: usize) -> u32 {
+    let rows_k = minimum_k(rows);
+    let table_k = minimum_k(1 << range_bits);
+    if rows_k > table_k {
+        rows_k
+    } else {
+        table_k
+    }
+}
+
+/// Returns the rows an [`crate::ApSumCircuit`] of `count` terms assigns when split into
+/// regions of at most `max_region_rows` rows: one per term, plus the two rows each
+/// region after the first carries over from the previous one.
+#[must_use]
+pub const fn table_rows(count: usize, max_region_rows: usize) -> usize {
+    let region_rows = if max_region_rows > MIN_REGION_ROWS {
+        max_region_rows
+    } else {
+        MIN_REGION_ROWS
+    };
+    if count <= region_rows {
+        return count;
+    }
+    let new_rows = region_rows - SUM_ONLY_BOUNDARY_ROWS;
+    count + SUM_ONLY_BOUNDARY_ROWS * (count - region_rows).div_ceil(new_rows)
+}
+
+/// Returns the tightest `k` for an [`crate::ApSumCircuit`] of `count` terms in a single
+/// region, which assigns one row per term.
+#[must_use]
+pub fn suggest_k(count: usize) -> u32 {
+    suggest_k_with_max_region_rows(count, DEFAULT_MAX_REGION_ROWS)
+}
+
+/// Returns the tightest `k` for an [`crate::ApSumCircuit`] of `count` terms split into
+/// regions of at most `max_region_rows` rows, counting the rows of [`table_rows`].
+#[must_use]
+pub fn suggest_k_with_max_region_rows(count: usize, max_region_rows: usize) -> u32 {
+    // The reserve depends on the gates, not on the step, count or split.
+    minimum_k_reserving(
+        table_rows(count, max_region_rows),
+        reserved_rows::<Fp, ApSumCircuit<1, 1>>(),
+    )
+}
+
+/// Returns whether `k` is the tightest size for an [`crate::ApSumCircuit`] of `count`
+/// terms. A `k` too small to fit the table is not minimal either.
+#[must_use]
+pub fn is_k_minimal(count: usize, k: u32) -> bool {
+    k == suggest_k(count)
+}
+
+/// Returns the number of rows of a `2^k` circuit that regions may use, given the
+/// `reserved` rows of [`reserved_rows`].
+#[must_use]
+pub const fn usable_rows(k: u32, reserved: usize) -> usize {
+    (1usize << k).saturating_sub(reserved)
+}
+
+/// Checks that `row` is one of the [`usable_rows`] of a `2^k` circuit, failing with
+/// [`ApSumError::RowOutOfBounds`] otherwise.
+pub fn check_row(row: usize, k: u32, reserved: usize) -> Result<(), ApSumError> {
+    let usable = usable_rows(k, reserved);
+    if row >= usable {
+        return Err(ApSumError::RowOutOfBounds { row, usable });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ap_sum;
+    use halo2_proofs::dev::MockProver;
+
+    /// Asserts that the `COUNT`-term table fits at [`suggest_k`] but not one below it.
+    fn assert_suggested_k_is_tight<const COUNT: usize>() {
+        let k = suggest_k(COUNT);
+        assert!(is_k_minimal(COUNT, k));
+        let sum = ap_sum(1, 1, COUNT).unwrap();
+        let instance = vec![Fp::from(1), Fp::from_u128(sum)];
+        let circuit = ApSumCircuit::<1, COUNT>;
+        MockProver::run(k, &circuit, vec![instance.clone()])
+            .unwrap()
+            .assert_satisfied();
+        assert!(MockProver::run(k - 1, &circuit, vec![instance]).is_err());
+    }
+
+    #[test]
+    fn base_circuit_reserves_the_default_rows() {
+        assert_eq!(reserved_rows::<Fp, ApSumCircuit<1, 5>>(), RESERVED_ROWS);
+    }
+
+    #[test]
+    fn row_usage_drives_k_without_lookups() {
+        assert_eq!(minimum_k(2), 3);
+        assert_eq!(minimum_k(4), 4);
+        assert_eq!(minimum_k(10), 4);
+        assert_eq!(minimum_k(11), 5);
+        assert_eq!(minimum_k(1 << 20), 21);
+    }
+
+    #[test]
+    fn lookup_table_drives_k_for_few_rows() {
+        // COUNT = 4 alone needs k = 4; the 2^16-entry table does not fit below k = 17.
+        assert_eq!(minimum_k_with_lookup(4, 16), 17);
+        assert_eq!(minimum_k_with_lookup(1 << 20, 16), 21);
+    }
+
+    #[test]
+    fn suggested_k_is_minimal_and_larger_k_is_not() {
+        assert!(!is_k_minimal(5, 12));
+        assert_suggested_k_is_tight::<4>();
+        assert_suggested_k_is_tight::<5>();
+        assert_suggested_k_is_tight::<10>();
+        assert_suggested_k_is_tight::<11>();
+        assert_suggested_k_is_tight::<26>();
+        assert_suggested_k_is_tight::<27>();
+    }
+
+    #[test]
+    fn table_beyond_usable_rows_is_reported() {
+        // The gate of the last term is enabled on row 99, past the 10 usable rows of k = 4.
+        assert!(matches!(
+            ApSumCircuit::<1, 100>::check_rows(4),
+            Err(ApSumError::RowOutOfBounds {
+                row: 99,
+                usable: 10
+            })
+        ));
+        assert!(ApSumCircuit::<1, 100>::check_rows(7).is_ok());
+    }
+
+    #[test]
+    fn check_rows_agrees_with_mock_prover_at_the_boundary() {
+        let k = 4;
+        // 1 + 2 + ... + 10 = 55 fills the 10 usable rows exactly.
+        assert!(ApSumCircuit::<1, 10>::check_rows(k).is_ok());
+        MockProver::run(
+            k,
+            &ApSumCircuit::<1, 10>,
+            vec![vec![Fp::from(1), Fp::from(55)]],
+        )
+        .unwrap()
+        .assert_satisfied();
+
+        // One more term does not fit.
+        assert!(ApSumCircuit::<1, 11>::check_rows(k).is_err());
+        assert!(MockProver::run(
+            k,
+            &ApSumCircuit::<1, 11>,
+            vec![vec![Fp::from(1), Fp::from(66)]]
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn table_rows_count_region_boundaries() {
+        assert_eq!(table_rows(10, DEFAULT_MAX_REGION_ROWS), 10);
+        assert_eq!(table_rows(10, 10), 10);
+        assert_eq!(table_rows(10, 3), 24);
+        assert_eq!(table_rows(10, 4), 16);
+        // Regions are never smaller than three rows.
+        assert_eq!(table_rows(10, 1), 24);
+        assert_eq!(suggest_k_with_max_region_rows(10, 3), 5);
+    }
+
+    #[test]
+    fn split_check_rows_agrees_with_mock_prover() {
+        let circuit = ApSumCircuit::<1, 10, 3>;
+        let instance = vec![Fp::from(1), Fp::from(55)];
+        // The unsplit table fits at k = 4; its 24 split rows do not.
+        assert!(ApSumCircuit::<1, 10, 3>::check_rows(4).is_err());
+        assert!(MockProver::run(4, &circuit, vec![instance.clone()]).is_err());
+        assert!(ApSumCircuit::<1, 10, 3>::check_rows(5).is_ok());
+        MockProver::run(5, &circuit, vec![instance])
+            .unwrap()
+            .assert_satisfied();
+    }
+}
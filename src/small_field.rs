@@ -0,0 +1,70 @@
+//! Expected sums over small prime fields.
+//!
+//! The circuits are generic over any `F: FieldExt` and reduce `STEP` with
+//! `F::from_u128`, so over a field with a 64-bit modulus such as Goldilocks
+//! (`2^64 - 2^32 + 1`) a `u128` step is silently reduced and sums wrap after far fewer
+//! terms than over Pasta. The halo2curves release used here ships no small field
+//! implementing `FieldExt`, so a user-supplied one is needed; these helpers compute the
+//! instance such a field expects and check whether a step survives the reduction.
+
+/// The Goldilocks prime `2^64 - 2^32 + 1`.
+pub const GOLDILOCKS_MODULUS: u64 = 0xffff_ffff_0000_0001;
+
+/// Returns whether `step` is below `modulus`, i.e. unchanged by reduction into a field
+/// of that size.
+#[must_use]
+pub fn step_fits(step: u128, modulus: u64) -> bool {
+    step < u128::from(modulus)
+}
+
+/// Computes the sum of the `count`-term progression `first, first + step, ...` modulo
+/// `modulus`, as the circuit computes it over a field of that size.
+#[must_use]
+pub fn ap_sum_mod(first: u128, step: u128, count: usize, modulus: u64) -> u64 {
+    let m = u128::from(modulus);
+    let (first, step) = (first % m, step % m);
+    let mut term = first;
+    let mut sum = 0;
+    for _ in 0..count {
+        sum = (sum + term) % m;
+        term = (term + step) % m;
+    }
+    sum as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ap_sum, ApSumCircuit};
+    use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr};
+
+    #[test]
+    fn small_sums_match_the_integer_sum() {
+        // 1 + 2 + 3 + 4 + 5 = 15
+        assert_eq!(ap_sum_mod(1, 1, 5, GOLDILOCKS_MODULUS), 15);
+        assert_eq!(
+            ap_sum_mod(1, 1, 5, GOLDILOCKS_MODULUS) as u128,
+            ap_sum(1, 1, 5).unwrap()
+        );
+    }
+
+    #[test]
+    fn large_steps_wrap_in_goldilocks() {
+        let step = u128::from(GOLDILOCKS_MODULUS) + 1;
+        assert!(!step_fits(step, GOLDILOCKS_MODULUS));
+        // The step reduces to 1, so the sum is that of step 1.
+        assert_eq!(ap_sum_mod(1, step, 5, GOLDILOCKS_MODULUS), 15);
+        assert_ne!(ap_sum(1, step, 5).unwrap(), 15);
+    }
+
+    #[test]
+    fn circuit_runs_over_another_field() {
+        let k = 4;
+        let instance = vec![
+            Fr::from(1),
+            Fr::from(ap_sum_mod(1, 1, 5, GOLDILOCKS_MODULUS)),
+        ];
+        let prover = MockProver::run(k, &ApSumCircuit::<1, 5>, vec![instance]).unwrap();
+        prover.assert_satisfied();
+    }
+}
@@ -0,0 +1,132 @@
+//! Proof that the AP sum is a perfect square.
+//!
+//! The circuit computes the sum as [`crate::ApSumCircuit`] does and witnesses its square
+//! root `r`, constraining `r * r == sum`. Half of all field elements have a square root
+//! modulo `p`, so `r` is also range-checked below `2^RANGE_BITS`: then `r * r` cannot
+//! wrap, and the claim holds over the integers for sums below `2^(2 * RANGE_BITS)`. The
+//! sum itself stays private.
+//!
+//! Instance layout: `[first]`.
+
+use crate::{range::RangeCheckConfig, ApSumChip, ApSumConfig};
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+
+/// Bits of the range check on the square root.
+pub const RANGE_BITS: usize = 8;
+
+#[derive(Clone, Debug)]
+pub struct SquareApSumConfig {
+    ap_sum: ApSumConfig,
+    // [sum, root]
+    square: [Column<Advice>; 2],
+    s_square: Selector,
+    root: RangeCheckConfig<RANGE_BITS>,
+}
+
+#[derive(Default)]
+pub struct SquareApSumCircuit<F: FieldExt, const STEP: u128, const COUNT: usize> {
+    /// The integer square root of the sum.
+    pub root: Value<F>,
+}
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> Circuit<F>
+    for SquareApSumCircuit<F, STEP, COUNT>
+{
+    type Config = SquareApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            root: Value::unknown(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let ap_sum = ApSumChip::<_, STEP, COUNT>::configure_sum_only(meta, advice, instance);
+
+        // The sum is copied back into its own column, in the square region.
+        let square = [advice[1], meta.advice_column()];
+        let s_square = meta.selector();
+        let root = RangeCheckConfig::configure(meta, square[1]);
+
+        // |  sum  | root | s_square
+        // --------------------------
+        // |   s   |  r   |    s       r * r == s, r < 2^RANGE_BITS
+        meta.create_gate("perfect square", |meta| {
+            let sum = meta.query_advice(square[0], Rotation::cur());
+            let root = meta.query_advice(square[1], Rotation::cur());
+            let s = meta.query_selector(s_square);
+            vec![s * (root.clone() * root - sum)]
+        });
+
+        SquareApSumConfig {
+            ap_sum,
+            square,
+            s_square,
+            root,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config
+            .root
+            .load_table(layouter.namespace(|| "range table"))?;
+        let chip = ApSumChip::<_, STEP, COUNT>::construct(config.ap_sum.clone());
+        let cells = chip.assign(layouter.namespace(|| "AP sum table"))?;
+        layouter.assign_region(
+            || "perfect square",
+            |mut region| {
+                config.s_square.enable(&mut region, 0)?;
+                cells
+                    .sum
+                    .copy_advice(|| "sum", &mut region, config.square[0], 0)?;
+                config.root.assign(&mut region, 0, self.root)?;
+                Ok(())
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{
+        dev::MockProver,
+        halo2curves::{group::ff::Field, pasta::Fp},
+    };
+
+    fn run<const COUNT: usize>(root: Fp) -> bool {
+        let k = 9;
+        let circuit = SquareApSumCircuit::<_, 1, COUNT> {
+            root: Value::known(root),
+        };
+        MockProver::run(k, &circuit, vec![vec![Fp::from(1)]])
+            .unwrap()
+            .verify()
+            .is_ok()
+    }
+
+    #[test]
+    fn sum_thirty_six_is_six_squared() {
+        // 1 + 2 + ... + 8 = 36
+        assert!(run::<8>(Fp::from(6)));
+        assert!(!run::<8>(Fp::from(5)));
+    }
+
+    #[test]
+    fn sum_fifteen_is_not_a_square() {
+        // 1 + 2 + 3 + 4 + 5 = 15 lies between 3^2 and 4^2
+        assert!(!run::<5>(Fp::from(3)));
+        assert!(!run::<5>(Fp::from(4)));
+        // A square root modulo p, if any, is far outside the range check.
+        if let Some(root) = Option::<Fp>::from(Fp::from(15).sqrt()) {
+            assert!(!run::<5>(root));
+        }
+    }
+}
@@ -0,0 +1,146 @@
+//! AP sum proofs bound to their const-generic parameters.
+//!
+//! `STEP` and `COUNT` are baked into the verifying key, so a proof checked against a key
+//! for other parameters merely fails to verify. [`TaggedApSumCircuit`] additionally
+//! exposes [`parameter_tag`], `Poseidon(STEP, COUNT)`, as a constant of the circuit, and
+//! [`verify_tagged`] compares it with the expected parameters before verifying, reporting
+//! [`ApSumError::ParameterMismatch`] instead of a plain rejection.
+//!
+//! Instance layout: `[first, sum, parameter_tag]`.
+
+use crate::{
+    commitment::hash_two,
+    proof::{verify, Params, Proof},
+    ApSumChip, ApSumConfig, ApSumError,
+};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::*,
+    halo2curves::pasta::{EqAffine, Fp},
+    plonk::*,
+};
+
+/// Instance row of the parameter tag.
+pub const TAG_ROW: usize = 2;
+
+/// Computes the domain-separating tag `Poseidon(step, count)`.
+#[must_use]
+pub fn parameter_tag(step: u128, count: usize) -> Fp {
+    hash_two(Fp::from_u128(step), Fp::from(count as u64))
+}
+
+#[derive(Clone, Debug)]
+pub struct TaggedApSumConfig {
+    ap_sum: ApSumConfig,
+    constants: Column<Fixed>,
+}
+
+#[derive(Default)]
+pub struct TaggedApSumCircuit<const STEP: u128, const COUNT: usize>;
+
+impl<const STEP: u128, const COUNT: usize> Circuit<Fp> for TaggedApSumCircuit<STEP, COUNT> {
+    type Config = TaggedApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constants = meta.fixed_column();
+        meta.enable_constant(constants);
+
+        TaggedApSumConfig {
+            ap_sum: ApSumChip::<_, STEP, COUNT>::configure_sum_only(meta, advice, instance),
+            constants,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fp>,
+    ) -> Result<(), Error> {
+        let sum_column = config.ap_sum.advice[1];
+        let chip = ApSumChip::<_, STEP, COUNT>::construct(config.ap_sum);
+        let cells = chip.assign(layouter.namespace(|| "AP sum table"))?;
+        chip.expose_public(layouter.namespace(|| "output"), cells.sum.assigned(), 1)?;
+
+        // The tag is fixed by the circuit, so only a key for these parameters can prove it.
+        let tag = layouter.assign_region(
+            || "parameter tag",
+            |mut region| {
+                region.assign_advice_from_constant(
+                    || "tag",
+                    sum_column,
+                    0,
+                    parameter_tag(STEP, COUNT),
+                )
+            },
+        )?;
+        chip.expose_public(layouter.namespace(|| "parameter tag"), &tag, TAG_ROW)?;
+        Ok(())
+    }
+}
+
+/// Verifies `proof` against `instance` as a proof of `TaggedApSumCircuit<STEP, COUNT>`.
+///
+/// Fails with [`ApSumError::ParameterMismatch`] if the instance's tag belongs to other
+/// parameters, and with [`ApSumError::InstanceLength`] if it has no tag row.
+#[must_use = "the proof is only valid if this returns `Ok(true)`"]
+pub fn verify_tagged<const STEP: u128, const COUNT: usize>(
+    params: &Params,
+    vk: &VerifyingKey<EqAffine>,
+    proof: &Proof,
+    instance: &[Fp],
+) -> Result<bool, ApSumError> {
+    if instance.len() != TAG_ROW + 1 {
+        return Err(ApSumError::InstanceLength {
+            expected: TAG_ROW + 1,
+            actual: instance.len(),
+        });
+    }
+    if instance[TAG_ROW] != parameter_tag(STEP, COUNT) {
+        return Err(ApSumError::ParameterMismatch);
+    }
+    Ok(verify(params, vk, proof, instance))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proof::{prove, setup};
+    use halo2_proofs::dev::MockProver;
+    use rand_core::OsRng;
+
+    const K: u32 = 5;
+
+    fn instance(step: u128, count: usize) -> Vec<Fp> {
+        // 1 + 2 + 3 + 4 + 5 = 15
+        vec![Fp::from(1), Fp::from(15), parameter_tag(step, count)]
+    }
+
+    #[test]
+    fn tag_of_other_parameters_is_rejected() {
+        let prover = MockProver::run(K, &TaggedApSumCircuit::<1, 5>, vec![instance(1, 5)]).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(K, &TaggedApSumCircuit::<1, 5>, vec![instance(2, 5)]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn verifying_as_other_parameters_reports_mismatch() {
+        let (params, pk) = setup(K, &TaggedApSumCircuit::<1, 5>).unwrap();
+        let instance = instance(1, 5);
+        let proof = prove(&params, &pk, TaggedApSumCircuit::<1, 5>, &instance, OsRng).unwrap();
+
+        assert!(verify_tagged::<1, 5>(&params, pk.get_vk(), &proof, &instance).unwrap());
+        assert!(matches!(
+            verify_tagged::<2, 5>(&params, pk.get_vk(), &proof, &instance),
+            Err(ApSumError::ParameterMismatch)
+        ));
+    }
+}
@@ -0,0 +1,377 @@
+//! Test helpers for the `testing` feature, also built for the crate's own tests.
+
+use crate::{
+    proof::{prove, setup, verify},
+    ApSumChip, ApSumCircuit, ApSumConfig,
+};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{FloorPlanner, Layouter, SimpleFloorPlanner, Value},
+    dev::{MockProver, VerifyFailure},
+    halo2curves::pasta::Fp,
+    plonk::{
+        Advice, Any, Assigned, Assignment, Challenge, Circuit, Column, ConstraintSystem, Error,
+        Fixed, Instance, Selector,
+    },
+};
+use rand_core::OsRng;
+
+/// Smallest and largest `k` tried by [`analyze`]. Below `MIN_K` the rows halo2 reserves
+/// for blinding factors would not fit at all.
+const MIN_K: u32 = 4;
+const MAX_K: u32 = 18;
+
+/// Runs `MockProver` on `circuit` at the smallest `k` that fits it and returns the
+/// structured failures, which are empty if the circuit is satisfied.
+///
+/// Negative tests can match the returned [`VerifyFailure`]s against the gate, constraint
+/// and row they expect instead of only checking that verification failed. Panics if
+/// synthesis fails for any reason other than the circuit not fitting.
+pub fn analyze<C: Circuit<Fp>>(circuit: &C, instance: Vec<Fp>) -> Vec<VerifyFailure> {
+    for k in MIN_K..=MAX_K {
+        match MockProver::run(k, circuit, vec![instance.clone()]) {
+            Ok(prover) => return prover.verify().err().unwrap_or_default(),
+            Err(Error::NotEnoughRowsAvailable { .. } | Error::InstanceTooLarge) => continue,
+            Err(e) => panic!("synthesis failed: {e:?}"),
+        }
+    }
+    panic!("circuit does not fit in 2^{MAX_K} rows");
+}
+
+/// Asserts that `MockProver` accepts `circuit` with `instance` exactly when a real proof
+/// of it verifies.
+///
+/// Runs the mock prover, key generation, proving and verification at size `2^k`. A
+/// mismatch usually means synthesis depends on witness values in a way keygen does not
+/// see, e.g. enabling selectors or assigning fixed cells conditionally.
+pub fn assert_mock_and_real_agree<C: Circuit<Fp>>(circuit: C, instance: Vec<Fp>, k: u32) {
+    let mock_ok = MockProver::run(k, &circuit, vec![instance.clone()])
+        .map(|prover| prover.verify().is_ok())
+        .unwrap_or(false);
+
+    let (params, pk) = setup(k, &circuit).expect("key generation failed");
+    let real_ok = prove(&params, &pk, circuit, &instance, OsRng)
+        .map(|proof| verify(&params, pk.get_vk(), &proof, &instance))
+        .unwrap_or(false);
+
+    assert_eq!(
+        mock_ok,
+        real_ok,
+        "MockProver {} the circuit but the real prover {}",
+        if mock_ok { "accepts" } else { "rejects" },
+        if real_ok { "accepts" } else { "rejects" },
+    );
+}
+
+/// Counts the `assign_advice` calls per advice column made by synthesizing `circuit`.
+///
+/// The result is indexed by column index. Cells copied from the instance count as
+/// assignments, and the measuring pass of the floor planner is not counted, so each
+/// assigned cell is counted once. `instance` supplies the values of the first instance
+/// column.
+pub fn advice_assignments<C: Circuit<Fp>>(circuit: &C, instance: Vec<Fp>) -> Vec<usize> {
+    let mut meta = ConstraintSystem::default();
+    let config = C::configure(&mut meta);
+    let mut counter = AdviceCounter {
+        instance,
+        counts: vec![0; meta.num_advice_columns()],
+    };
+    C::FloorPlanner::synthesize(&mut counter, circuit, config, meta.constants().clone())
+        .expect("synthesis failed");
+    counter.counts
+}
+
+/// An [`Assignment`] that only records how many advice cells each column receives.
+struct AdviceCounter {
+    instance: Vec<Fp>,
+    counts: Vec<usize>,
+}
+
+impl Assignment<Fp> for AdviceCounter {
+    fn enter_region<NR, N>(&mut self, _: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+    }
+
+    fn annotate_column<A, AR>(&mut self, _: A, _: Column<Any>)
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+    }
+
+    fn exit_region(&mut self) {}
+
+    fn enable_selector<A, AR>(&mut self, _: A, _: &Selector, _: usize) -> Result<(), Error>
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        Ok(())
+    }
+
+    fn query_instance(&self, _: Column<Instance>, row: usize) -> Result<Value<Fp>, Error> {
+        Ok(self
+            .instance
+            .get(row)
+            .map_or_else(Value::unknown, |value| Value::known(*value)))
+    }
+
+    fn assign_advice<V, VR, A, AR>(
+        &mut self,
+        _: A,
+        column: Column<Advice>,
+        _: usize,
+        _: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<Fp>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.counts[column.index()] += 1;
+        Ok(())
+    }
+
+    fn assign_fixed<V, VR, A, AR>(
+        &mut self,
+        _: A,
+        _: Column<Fixed>,
+        _: usize,
+        _: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<Fp>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        Ok(())
+    }
+
+    fn copy(&mut self, _: Column<Any>, _: usize, _: Column<Any>, _: usize) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn fill_from_row(
+        &mut self,
+        _: Column<Fixed>,
+        _: usize,
+        _: Value<Assigned<Fp>>,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn get_challenge(&self, _: Challenge) -> Value<Fp> {
+        Value::unknown()
+    }
+
+    fn push_namespace<NR, N>(&mut self, _: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+    }
+
+    fn pop_namespace(&mut self, _: Option<String>) {}
+}
+
+/// A single advice cell of the base table to tamper with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Perturbation {
+    /// The term `a_n` at row `n`.
+    Term(usize),
+    /// The running sum `sum_n` at row `n`.
+    Sum(usize),
+}
+
+/// The base table of `first, first + STEP, ...` with one cell optionally increased by one.
+///
+/// Terms and sums are assigned verbatim, so the perturbed cell is caught by the gates and
+/// copy constraints alone. Instance layout: `[first, sum]`.
+pub struct PerturbedApSumCircuit<const STEP: u128, const COUNT: usize> {
+    pub first: Fp,
+    pub perturbation: Option<Perturbation>,
+}
+
+impl<const STEP: u128, const COUNT: usize> Circuit<Fp> for PerturbedApSumCircuit<STEP, COUNT> {
+    type Config = ApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            first: Fp::zero(),
+            perturbation: None,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+
+        ApSumChip::<_, STEP, COUNT>::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fp>,
+    ) -> Result<(), Error> {
+        let step = Fp::from_u128(STEP);
+        let mut terms: Vec<_> = (0..COUNT as u64)
+            .map(|n| self.first + step * Fp::from(n))
+            .collect();
+        let mut sums: Vec<_> = terms
+            .iter()
+            .scan(Fp::zero(), |sum, term| {
+                *sum += *term;
+                Some(*sum)
+            })
+            .collect();
+        match self.perturbation {
+            Some(Perturbation::Term(row)) => terms[row] += Fp::one(),
+            Some(Perturbation::Sum(row)) => sums[row] += Fp::one(),
+            None => {}
+        }
+
+        let known = |values: Vec<Fp>| -> Vec<_> { values.into_iter().map(Value::known).collect() };
+        let chip = ApSumChip::<_, STEP, COUNT>::construct(config);
+        let cells = chip.assign_table(
+            layouter.namespace(|| "AP sum table"),
+            &known(terms),
+            &known(sums),
+            &[],
+        )?;
+        chip.expose_public(layouter.namespace(|| "output"), cells.sum.assigned(), 1)
+    }
+}
+
+/// The base table assigned with `STEP` converted to a field element on every row rather
+/// than once, the chip's assignment before the step was cached.
+///
+/// Only for benchmarking the cached step against; the gates and instance layout
+/// `[first, sum]` are those of [`crate::ApSumCircuit`].
+#[derive(Default)]
+pub struct PerRowStepApSumCircuit<const STEP: u128, const COUNT: usize>;
+
+impl<const STEP: u128, const COUNT: usize> Circuit<Fp> for PerRowStepApSumCircuit<STEP, COUNT> {
+    type Config = ApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+
+        ApSumCircuit::<STEP, COUNT>::configure_with_columns(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fp>,
+    ) -> Result<(), Error> {
+        let chip = ApSumChip::<_, STEP, COUNT>::construct(config);
+        let cells = chip.assign_converting_step_per_row(layouter.namespace(|| "AP sum table"))?;
+        chip.expose_public(layouter.namespace(|| "output"), cells.sum.assigned(), 1)
+    }
+}
+
+/// Returns the single-cell perturbations of the `COUNT`-term table from `first` that
+/// `MockProver` does not catch.
+///
+/// Every cell of the `a` and `sum` columns is increased by one in turn and checked
+/// against the honest instance. An empty result means no cell of the table is free.
+pub fn uncaught_perturbations<const STEP: u128, const COUNT: usize>(
+    first: u64,
+) -> Vec<Perturbation> {
+    let first = Fp::from(first);
+    let sum = (0..COUNT as u64).fold(Fp::zero(), |sum, n| {
+        sum + first + Fp::from_u128(STEP) * Fp::from(n)
+    });
+    let instance = vec![first, sum];
+
+    let honest = PerturbedApSumCircuit::<STEP, COUNT> {
+        first,
+        perturbation: None,
+    };
+    assert!(
+        analyze(&honest, instance.clone()).is_empty(),
+        "honest table is rejected"
+    );
+
+    (0..COUNT)
+        .flat_map(|row| [Perturbation::Term(row), Perturbation::Sum(row)])
+        .filter(|&perturbation| {
+            let circuit = PerturbedApSumCircuit::<STEP, COUNT> {
+                first,
+                perturbation: Some(perturbation),
+            };
+            analyze(&circuit, instance.clone()).is_empty()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn analyze_returns_no_failures_for_a_satisfied_circuit() {
+        assert!(analyze(&ApSumCircuit::<1, 5>, vec![Fp::from(1), Fp::from(15)]).is_empty());
+    }
+
+    #[test]
+    fn analyze_reports_the_copy_to_the_instance() {
+        let failures = analyze(&ApSumCircuit::<1, 5>, vec![Fp::from(1), Fp::from(16)]);
+        assert!(!failures.is_empty());
+        // The table is consistent; only the sum's copy constraint to the instance fails.
+        assert!(failures
+            .iter()
+            .all(|failure| matches!(failure, VerifyFailure::Permutation { .. })));
+    }
+
+    #[test]
+    fn base_circuit_assigns_one_row_per_term() {
+        // Columns are [a, sum]; a_0 and sum_0 are counted with the rest.
+        let expected = ApSumCircuit::<1, 5>::assigned_row_count();
+        assert_eq!(expected, 5);
+        let counts = advice_assignments(&ApSumCircuit::<1, 5>, vec![Fp::from(1), Fp::from(15)]);
+        assert_eq!(counts, [expected, expected]);
+    }
+
+    #[test]
+    fn every_single_cell_perturbation_is_caught() {
+        assert_eq!(uncaught_perturbations::<1, 4>(1), []);
+        assert_eq!(uncaught_perturbations::<3, 4>(2), []);
+    }
+
+    #[test]
+    fn per_row_step_matches_the_cached_step() {
+        let sum = Fp::from_u128(crate::ap_sum(3, 7, 200).unwrap());
+        for instance in [vec![Fp::from(3), sum], vec![Fp::from(3), sum + Fp::one()]] {
+            assert_eq!(
+                analyze(&PerRowStepApSumCircuit::<7, 200>, instance.clone()).is_empty(),
+                analyze(&ApSumCircuit::<7, 200>, instance).is_empty()
+            );
+        }
+        assert!(analyze(&PerRowStepApSumCircuit::<7, 200>, vec![Fp::from(3), sum]).is_empty());
+    }
+
+    #[test]
+    fn base_circuit_agrees_on_valid_instance() {
+        // 1 + 2 + 3 + 4 + 5 = 15
+        assert_mock_and_real_agree(ApSumCircuit::<1, 5>, vec![Fp::from(1), Fp::from(15)], 5);
+    }
+
+    #[test]
+    fn base_circuit_agrees_on_wrong_sum() {
+        assert_mock_and_real_agree(ApSumCircuit::<1, 5>, vec![Fp::from(1), Fp::from(16)], 5);
+    }
+}
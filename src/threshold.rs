@@ -0,0 +1,141 @@
+//! Proof that the running sum first reaches a public target at a hidden count.
+//!
+//! The table and the one-hot selection at a hidden count `k` are those of
+//! [`crate::hidden`], which accumulates the marked running sum `sum_{k-1}` and the one
+//! before it, `sum_{k-2}` (`0` for `k = 1`). The circuit then proves
+//! `sum_{k-2} < T <= sum_{k-1}` with range-checked comparisons, so `k` stays private.
+//!
+//! Running sums and `T` must be below `2^RANGE_BITS`.
+//!
+//! Instance layout: `[first, target]`.
+
+use crate::{
+    hidden::{HiddenCountApSumChip, HiddenCountApSumConfig},
+    range::ComparisonConfig,
+};
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*};
+
+/// Bits of the range check used for the comparisons.
+pub const RANGE_BITS: usize = 8;
+
+/// Instance row of the target.
+const TARGET_ROW: usize = 1;
+
+#[derive(Clone, Debug)]
+pub struct ThresholdApSumConfig {
+    selection: HiddenCountApSumConfig,
+    // the sum column, which also holds the target
+    target: Column<Advice>,
+    comparison: ComparisonConfig<RANGE_BITS>,
+    instance: Column<Instance>,
+}
+
+#[derive(Default)]
+pub struct ThresholdApSumCircuit<const STEP: u128, const MAX_COUNT: usize> {
+    /// The hidden count `k` at which the running sum first reaches the target.
+    pub count: Value<usize>,
+}
+
+impl<F: FieldExt, const STEP: u128, const MAX_COUNT: usize> Circuit<F>
+    for ThresholdApSumCircuit<STEP, MAX_COUNT>
+{
+    type Config = ThresholdApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        // [a_n, sum_n, hit_n, reached_n, before_n, hits_n]
+        let advice = [(); 6].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+        let [a, sum, hit, reached, before, hits] = advice;
+        let selection = HiddenCountApSumChip::<_, STEP, MAX_COUNT>::configure_with_previous(
+            meta,
+            [a, sum, hit, reached, hits],
+            before,
+            instance,
+        );
+
+        // Comparisons reuse the a, sum and hit columns in their own regions.
+        let comparison = ComparisonConfig::configure(meta, [a, sum], hit);
+
+        ThresholdApSumConfig {
+            selection,
+            target: sum,
+            comparison,
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config
+            .comparison
+            .load_table(layouter.namespace(|| "range table"))?;
+        let chip = HiddenCountApSumChip::<_, STEP, MAX_COUNT>::construct(config.selection);
+        let (reached, before) =
+            chip.assign_with_previous(layouter.namespace(|| "threshold AP sum table"), self.count)?;
+        let target = layouter.assign_region(
+            || "target",
+            |mut region| {
+                region.assign_advice_from_instance(
+                    || "target",
+                    config.instance,
+                    TARGET_ROW,
+                    config.target,
+                    0,
+                )
+            },
+        )?;
+        // sum_{k-2} < T <= sum_{k-1}
+        config.comparison.assert_less_than(
+            layouter.namespace(|| "before < target"),
+            &before,
+            &target,
+        )?;
+        config.comparison.assert_less_or_equal(
+            layouter.namespace(|| "target <= reached"),
+            &target,
+            &reached,
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{
+        dev::{MockProver, VerifyFailure},
+        halo2curves::pasta::Fp,
+    };
+
+    fn run(count: usize) -> Result<(), Vec<VerifyFailure>> {
+        let k = 9;
+        let circuit = ThresholdApSumCircuit::<1, 6> {
+            count: Value::known(count),
+        };
+        // running sums: 1, 3, 6, 10, 15, 21
+        MockProver::run(k, &circuit, vec![vec![Fp::from(1), Fp::from(10)]])
+            .unwrap()
+            .verify()
+    }
+
+    #[test]
+    fn target_ten_is_first_reached_at_count_four() {
+        assert_eq!(run(4), Ok(()));
+    }
+
+    #[test]
+    fn wrong_count_is_rejected() {
+        // sum_3 = 10 already reaches the target
+        assert!(run(5).is_err());
+        // sum_2 = 6 does not reach it yet
+        assert!(run(3).is_err());
+    }
+}
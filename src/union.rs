@@ -0,0 +1,176 @@
+//! Sum over the union of two progressions by inclusion-exclusion.
+//!
+//! The circuit lays out three tables, for `A`, `B` and their overlap `A ∩ B`, and a gate
+//! over their final sums enforcing `Σ_{A∪B} = Σ_A + Σ_B − Σ_{A∩B}`. Only the union sum is
+//! exposed as an output. The first terms are public, so the verifier can check that the
+//! declared overlap really is the intersection of `A` and `B`; the circuit does not.
+//!
+//! For `A = 1..=5`, `B = 4..=8` and `A ∩ B = 4..=5` the circuit computes
+//! `15 + 30 - 9 = 36`.
+//!
+//! Instance layout: `[first_a, first_b, first_overlap, union_sum]`.
+
+use crate::{ApSumChip, ApSumConfig};
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+
+#[derive(Clone, Debug)]
+pub struct UnionApSumConfig {
+    a: ApSumConfig,
+    b: ApSumConfig,
+    overlap: ApSumConfig,
+    s_union: Selector,
+}
+
+#[derive(Default)]
+pub struct UnionApSumCircuit<
+    const STEP_A: u128,
+    const COUNT_A: usize,
+    const STEP_B: u128,
+    const COUNT_B: usize,
+    const STEP_OVERLAP: u128,
+    const COUNT_OVERLAP: usize,
+>;
+
+impl<
+        F: FieldExt,
+        const STEP_A: u128,
+        const COUNT_A: usize,
+        const STEP_B: u128,
+        const COUNT_B: usize,
+        const STEP_OVERLAP: u128,
+        const COUNT_OVERLAP: usize,
+    > Circuit<F>
+    for UnionApSumCircuit<STEP_A, COUNT_A, STEP_B, COUNT_B, STEP_OVERLAP, COUNT_OVERLAP>
+{
+    type Config = UnionApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let s_union = meta.selector();
+
+        // The three tables share the columns; each gets its own selectors and step.
+        let a = ApSumChip::<_, STEP_A, COUNT_A>::configure(meta, advice, instance);
+        let b = ApSumChip::<_, STEP_B, COUNT_B>::configure(meta, advice, instance);
+        let overlap =
+            ApSumChip::<_, STEP_OVERLAP, COUNT_OVERLAP>::configure(meta, advice, instance);
+
+        // |  advice[0]   |  advice[1]  | s_union
+        // ----------------------------------------
+        // |    sum_a     |    sum_b    |    s
+        // | sum_overlap  |  sum_union  |
+        meta.create_gate("inclusion-exclusion", |meta| {
+            let sum_a = meta.query_advice(advice[0], Rotation::cur());
+            let sum_b = meta.query_advice(advice[1], Rotation::cur());
+            let sum_overlap = meta.query_advice(advice[0], Rotation::next());
+            let sum_union = meta.query_advice(advice[1], Rotation::next());
+            let s = meta.query_selector(s_union);
+            vec![s * (sum_a + sum_b - sum_overlap - sum_union)]
+        });
+
+        UnionApSumConfig {
+            a,
+            b,
+            overlap,
+            s_union,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let [a_column, sum_column] = config.a.advice;
+        let instance = config.a.instance;
+        let a = ApSumChip::<_, STEP_A, COUNT_A>::construct(config.a);
+        let b = ApSumChip::<_, STEP_B, COUNT_B>::construct(config.b);
+        let overlap = ApSumChip::<_, STEP_OVERLAP, COUNT_OVERLAP>::construct(config.overlap);
+
+        // A reads its first term from instance row 0; B and the overlap start from copies
+        // of rows 1 and 2.
+        let (first_b, first_overlap) = layouter.assign_region(
+            || "first terms",
+            |mut region| {
+                let first_b =
+                    region.assign_advice_from_instance(|| "first b", instance, 1, sum_column, 0)?;
+                let first_overlap = region.assign_advice_from_instance(
+                    || "first overlap",
+                    instance,
+                    2,
+                    sum_column,
+                    1,
+                )?;
+                Ok((first_b, first_overlap))
+            },
+        )?;
+        let cells_a = a.assign(layouter.namespace(|| "A"))?;
+        let cells_b = b.assign_from(layouter.namespace(|| "B"), &first_b)?;
+        let cells_overlap =
+            overlap.assign_from(layouter.namespace(|| "overlap"), &first_overlap)?;
+
+        let union = layouter.assign_region(
+            || "inclusion-exclusion",
+            |mut region| {
+                config.s_union.enable(&mut region, 0)?;
+                let sum_a = cells_a
+                    .sum
+                    .copy_advice(|| "sum a", &mut region, a_column, 0)?;
+                let sum_b = cells_b
+                    .sum
+                    .copy_advice(|| "sum b", &mut region, sum_column, 0)?;
+                let sum_overlap =
+                    cells_overlap
+                        .sum
+                        .copy_advice(|| "sum overlap", &mut region, a_column, 1)?;
+                let union =
+                    sum_a.value().copied() + sum_b.value().copied() - sum_overlap.value().copied();
+                region.assign_advice(|| "sum union", sum_column, 1, || union)
+            },
+        )?;
+        a.expose_public(layouter.namespace(|| "output"), &union, 3)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, halo2curves::pasta::Fp};
+
+    // A = 1..=5, B = 4..=8, A ∩ B = 4..=5
+    type UnionCircuit = UnionApSumCircuit<1, 5, 1, 5, 1, 2>;
+
+    fn run(first_overlap: u64, union_sum: u64) -> bool {
+        let k = 5;
+        let instance = vec![
+            Fp::from(1),
+            Fp::from(4),
+            Fp::from(first_overlap),
+            Fp::from(union_sum),
+        ];
+        MockProver::run(k, &UnionCircuit::default(), vec![instance])
+            .unwrap()
+            .verify()
+            .is_ok()
+    }
+
+    #[test]
+    fn union_sum_of_overlapping_ranges() {
+        // 15 + 30 - (4 + 5) = 36 = 1 + ... + 8
+        assert!(run(4, 36));
+    }
+
+    #[test]
+    fn misspecified_overlap_is_rejected() {
+        // With the overlap declared as 5..=6 the relationship gives 15 + 30 - 11 = 34.
+        assert!(!run(5, 36));
+        assert!(run(5, 34));
+        assert!(!run(4, 35));
+    }
+}
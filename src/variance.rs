@@ -0,0 +1,253 @@
+//! Proof of the variance of an arithmetic progression.
+//!
+//! Alongside the running sum `S`, the table accumulates the running sum of squares `Q`.
+//! The prover witnesses the mean `m` and the variance `v`, and a final row checks
+//! `n * m == S` and `n * v == Q - n * m^2` for `n = COUNT`, which is
+//! `v == Q / n - (S / n)^2` multiplied through by `n`. For terms `1, 3, 5, 7` the mean is
+//! `4` and the variance `5`.
+//!
+//! Instance layout: `[first, variance]`.
+
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+use std::marker::PhantomData;
+
+#[derive(Clone, Debug)]
+pub struct VarianceApSumConfig {
+    // [a_n, sum_n, sq_sum_n, variance]
+    advice: [Column<Advice>; 4],
+    s_first: Selector,
+    s_next: Selector,
+    s_variance: Selector,
+    instance: Column<Instance>,
+}
+
+struct VarianceApSumChip<F, const STEP: u128, const COUNT: usize> {
+    config: VarianceApSumConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> VarianceApSumChip<F, STEP, COUNT> {
+    fn construct(config: VarianceApSumConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 4],
+        instance: Column<Instance>,
+    ) -> VarianceApSumConfig {
+        let s_first = meta.selector();
+        let s_next = meta.selector();
+        let s_variance = meta.selector();
+
+        for column in advice {
+            meta.enable_equality(column);
+        }
+        meta.enable_equality(instance);
+
+        // | advice[0] | advice[1] | advice[2] | advice[3] |
+        // ---------------------------------------------------
+        // |    a_0    |   sum_0   | sq_sum_0  |           |  s_first
+        // |    a_1    |   sum_1   | sq_sum_1  |           |  s_next
+        // |    ...    |    ...    |    ...    |           |  s_next
+        //
+        // |   mean    |     S     |     Q     |  variance |  s_variance (own region)
+        meta.create_gate("first square", |meta| {
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let sq_sum = meta.query_advice(advice[2], Rotation::cur());
+            let s = meta.query_selector(s_first);
+            vec![s * (a.clone() * a - sq_sum)]
+        });
+
+        meta.create_gate("step, sum and sum of squares", |meta| {
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let sum = meta.query_advice(advice[1], Rotation::cur());
+            let sq_sum = meta.query_advice(advice[2], Rotation::cur());
+            let prev_a = meta.query_advice(advice[0], Rotation::prev());
+            let prev_sum = meta.query_advice(advice[1], Rotation::prev());
+            let prev_sq_sum = meta.query_advice(advice[2], Rotation::prev());
+            let s = meta.query_selector(s_next);
+            vec![
+                // sum == a + prev_sum
+                s.clone() * (a.clone() + prev_sum - sum),
+                // sq_sum == a^2 + prev_sq_sum
+                s.clone() * (a.clone() * a.clone() + prev_sq_sum - sq_sum),
+                // a == prev_a + STEP
+                s * (a - prev_a - Expression::Constant(F::from_u128(STEP))),
+            ]
+        });
+
+        meta.create_gate("mean and variance", |meta| {
+            let mean = meta.query_advice(advice[0], Rotation::cur());
+            let sum = meta.query_advice(advice[1], Rotation::cur());
+            let sq_sum = meta.query_advice(advice[2], Rotation::cur());
+            let variance = meta.query_advice(advice[3], Rotation::cur());
+            let n = Expression::Constant(F::from(COUNT as u64));
+            let s = meta.query_selector(s_variance);
+            vec![
+                // n * mean == S
+                s.clone() * (n.clone() * mean.clone() - sum),
+                // n * variance == Q - n * mean^2
+                s * (n.clone() * variance - sq_sum + n * mean.clone() * mean),
+            ]
+        });
+
+        VarianceApSumConfig {
+            advice,
+            s_first,
+            s_next,
+            s_variance,
+            instance,
+        }
+    }
+
+    /// Assigns the table, returning the final `(S, Q)` cells.
+    fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        layouter.assign_region(
+            || "variance AP sum table",
+            |mut region| {
+                let [a_column, sum_column, sq_sum_column, _] = self.config.advice;
+                let step = F::from_u128(STEP);
+
+                let mut a_cell = region.assign_advice_from_instance(
+                    || "a",
+                    self.config.instance,
+                    0,
+                    a_column,
+                    0,
+                )?;
+                let mut sum_cell = region.assign_advice_from_instance(
+                    || "sum",
+                    self.config.instance,
+                    0,
+                    sum_column,
+                    0,
+                )?;
+                let mut sq_sum_cell = region.assign_advice(
+                    || "sq_sum",
+                    sq_sum_column,
+                    0,
+                    || a_cell.value().map(|a| a.square()),
+                )?;
+                self.config.s_first.enable(&mut region, 0)?;
+
+                for row in 1..COUNT {
+                    self.config.s_next.enable(&mut region, row)?;
+
+                    let new_a_val = a_cell.value().map(|a| *a + step);
+                    a_cell = region.assign_advice(|| "a", a_column, row, || new_a_val)?;
+
+                    let new_sum = sum_cell.value().copied() + new_a_val;
+                    sum_cell = region.assign_advice(|| "sum", sum_column, row, || new_sum)?;
+
+                    let new_sq_sum = sq_sum_cell.value().copied() + new_a_val.map(|a| a.square());
+                    sq_sum_cell =
+                        region.assign_advice(|| "sq_sum", sq_sum_column, row, || new_sq_sum)?;
+                }
+
+                Ok((sum_cell, sq_sum_cell))
+            },
+        )
+    }
+
+    /// Witnesses the mean and variance of the sums, returning the variance cell.
+    fn assign_variance(
+        &self,
+        mut layouter: impl Layouter<F>,
+        sum: &AssignedCell<F, F>,
+        sq_sum: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let n_inv: F = Option::from(F::from(COUNT as u64).invert()).ok_or(Error::Synthesis)?;
+
+        layouter.assign_region(
+            || "mean and variance",
+            |mut region| {
+                let [mean_column, sum_column, sq_sum_column, variance_column] = self.config.advice;
+                self.config.s_variance.enable(&mut region, 0)?;
+
+                let sum = sum.copy_advice(|| "sum", &mut region, sum_column, 0)?;
+                let sq_sum = sq_sum.copy_advice(|| "sq_sum", &mut region, sq_sum_column, 0)?;
+
+                let mean = sum.value().map(|sum| *sum * n_inv);
+                region.assign_advice(|| "mean", mean_column, 0, || mean)?;
+                let variance = sq_sum
+                    .value()
+                    .zip(mean)
+                    .map(|(sq_sum, mean)| *sq_sum * n_inv - mean.square());
+                region.assign_advice(|| "variance", variance_column, 0, || variance)
+            },
+        )
+    }
+
+    fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+#[derive(Default)]
+pub struct VarianceApSumCircuit<const STEP: u128, const COUNT: usize>;
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> Circuit<F>
+    for VarianceApSumCircuit<STEP, COUNT>
+{
+    type Config = VarianceApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [(); 4].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+
+        VarianceApSumChip::<_, STEP, COUNT>::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = VarianceApSumChip::<_, STEP, COUNT>::construct(config);
+        let (sum, sq_sum) = chip.assign(layouter.namespace(|| "variance AP sum table"))?;
+        let variance =
+            chip.assign_variance(layouter.namespace(|| "mean and variance"), &sum, &sq_sum)?;
+        chip.expose_public(layouter.namespace(|| "variance"), &variance, 1)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, halo2curves::pasta::Fp};
+
+    #[test]
+    fn step_two_count_four_has_variance_five() {
+        let k = 4;
+        // 1, 3, 5, 7: mean 4, squares sum to 84, 84 / 4 - 4^2 = 5
+        let circuit = VarianceApSumCircuit::<2, 4>;
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(1), Fp::from(5)]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn wrong_variance_is_rejected() {
+        let k = 4;
+        let circuit = VarianceApSumCircuit::<2, 4>;
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(1), Fp::from(6)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
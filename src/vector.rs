@@ -0,0 +1,201 @@
+//! Proof of the sum of a vector committed to by a public Merkle root.
+//!
+//! The terms are the first `COUNT` leaves of a Poseidon [`MerkleTree`] of depth `DEPTH`.
+//! Each term is witnessed, accumulated into a running sum and opened against the root
+//! along its authentication path. The path positions are constants derived from the term's
+//! index, so every leaf is opened exactly once and the terms themselves stay private.
+//!
+//! Instance layout: `[root, sum]`.
+
+use crate::merkle::{MerklePathConfig, MerkleTree, Position};
+use halo2_proofs::{circuit::*, halo2curves::pasta::Fp, plonk::*, poly::Rotation};
+
+#[derive(Clone, Debug)]
+pub struct VectorSumConfig {
+    // [term_n, sum_n]
+    advice: [Column<Advice>; 2],
+    s_first: Selector,
+    s_next: Selector,
+    path: MerklePathConfig,
+    instance: Column<Instance>,
+}
+
+impl VectorSumConfig {
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let s_first = meta.selector();
+        let s_next = meta.selector();
+
+        meta.enable_equality(advice[0]);
+        meta.enable_equality(advice[1]);
+        meta.enable_equality(instance);
+
+        // | advice[0] | advice[1] | s_first | s_next
+        // --------------------------------------------
+        // |  term_0   |   sum_0   |    s    |
+        // |  term_1   |   sum_1   |         |   s
+        // |   ...     |    ...    |         |   s
+        meta.create_gate("vector sum", |meta| {
+            let term = meta.query_advice(advice[0], Rotation::cur());
+            let sum = meta.query_advice(advice[1], Rotation::cur());
+            let prev_sum = meta.query_advice(advice[1], Rotation::prev());
+            let s_first = meta.query_selector(s_first);
+            let s_next = meta.query_selector(s_next);
+            vec![
+                // sum == term
+                s_first * (term.clone() - sum.clone()),
+                // sum == prev_sum + term
+                s_next * (prev_sum + term - sum),
+            ]
+        });
+
+        Self {
+            advice,
+            s_first,
+            s_next,
+            path: MerklePathConfig::configure(meta),
+            instance,
+        }
+    }
+
+    /// Assigns the terms and running sums, returning the term cells and the sum cell.
+    fn assign_sum(
+        &self,
+        mut layouter: impl Layouter<Fp>,
+        terms: &[Value<Fp>],
+    ) -> Result<(Vec<AssignedCell<Fp, Fp>>, AssignedCell<Fp, Fp>), Error> {
+        layouter.assign_region(
+            || "vector sum",
+            |mut region| {
+                let mut term_cells = Vec::with_capacity(terms.len());
+                let mut sum = Value::known(Fp::zero());
+                let mut sum_cell = None;
+                for (row, term) in terms.iter().enumerate() {
+                    if row == 0 {
+                        self.s_first.enable(&mut region, row)?;
+                    } else {
+                        self.s_next.enable(&mut region, row)?;
+                    }
+                    term_cells.push(region.assign_advice(
+                        || "term",
+                        self.advice[0],
+                        row,
+                        || *term,
+                    )?);
+                    sum = sum.zip(*term).map(|(sum, term)| sum + term);
+                    sum_cell = Some(region.assign_advice(|| "sum", self.advice[1], row, || sum)?);
+                }
+                let sum_cell = sum_cell.ok_or(Error::Synthesis)?;
+                Ok((term_cells, sum_cell))
+            },
+        )
+    }
+}
+
+/// Proves the sum of the first `COUNT` leaves of a Merkle tree of depth `DEPTH`.
+pub struct VectorSumCircuit<const COUNT: usize, const DEPTH: usize> {
+    pub terms: [Value<Fp>; COUNT],
+    /// The authentication path siblings of each term, from the leaf level up.
+    pub siblings: [[Value<Fp>; DEPTH]; COUNT],
+}
+
+impl<const COUNT: usize, const DEPTH: usize> VectorSumCircuit<COUNT, DEPTH> {
+    /// Builds the circuit from the first `COUNT` leaves of `tree` and their paths, or
+    /// returns `None` if the tree's depth is not `DEPTH` or it has fewer than `COUNT`
+    /// leaves.
+    pub fn from_tree(tree: &MerkleTree) -> Option<Self> {
+        if tree.depth() != DEPTH || COUNT > 1 << DEPTH {
+            return None;
+        }
+        let mut circuit = Self::default();
+        let rows = circuit.terms.iter_mut().zip(circuit.siblings.iter_mut());
+        for (index, (term, siblings)) in rows.enumerate() {
+            *term = Value::known(tree.leaf(index));
+            for (sibling, (node, _)) in siblings.iter_mut().zip(tree.path(index)) {
+                *sibling = Value::known(node);
+            }
+        }
+        Some(circuit)
+    }
+}
+
+impl<const COUNT: usize, const DEPTH: usize> Default for VectorSumCircuit<COUNT, DEPTH> {
+    fn default() -> Self {
+        Self {
+            terms: [Value::unknown(); COUNT],
+            siblings: [[Value::unknown(); DEPTH]; COUNT],
+        }
+    }
+}
+
+impl<const COUNT: usize, const DEPTH: usize> Circuit<Fp> for VectorSumCircuit<COUNT, DEPTH> {
+    type Config = VectorSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        VectorSumConfig::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fp>,
+    ) -> Result<(), Error> {
+        let (terms, sum) = config.assign_sum(layouter.namespace(|| "vector sum"), &self.terms)?;
+
+        for (index, (term, siblings)) in terms.iter().zip(&self.siblings).enumerate() {
+            let mut node = term.clone();
+            for (level, sibling) in siblings.iter().enumerate() {
+                node = config.path.assign_level(
+                    layouter.namespace(|| format!("term {} level {}", index, level)),
+                    &node,
+                    *sibling,
+                    Position::Constant((index >> level) & 1 == 1),
+                )?;
+            }
+            layouter
+                .namespace(|| format!("root of term {}", index))
+                .constrain_instance(node.cell(), config.instance, 0)?;
+        }
+
+        layouter
+            .namespace(|| "sum")
+            .constrain_instance(sum.cell(), config.instance, 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::dev::MockProver;
+
+    fn tree() -> MerkleTree {
+        // [1, 2, 3, 4, 5] padded with zeros to a power of two
+        MerkleTree::new([1, 2, 3, 4, 5, 0, 0, 0].map(Fp::from).to_vec()).unwrap()
+    }
+
+    #[test]
+    fn committed_vector_sums_to_fifteen() {
+        let k = 11;
+        let tree = tree();
+        let circuit = VectorSumCircuit::<5, 3>::from_tree(&tree).unwrap();
+        let prover = MockProver::run(k, &circuit, vec![vec![tree.root(), Fp::from(15)]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn term_outside_the_commitment_is_rejected() {
+        let k = 11;
+        let tree = tree();
+        let mut circuit = VectorSumCircuit::<5, 3>::from_tree(&tree).unwrap();
+        // claim 6 instead of 5 at index 4, with the sum to match
+        circuit.terms[4] = Value::known(Fp::from(6));
+        let prover = MockProver::run(k, &circuit, vec![vec![tree.root(), Fp::from(16)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
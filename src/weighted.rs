@@ -0,0 +1,214 @@
+//! Weighted sum `w_0 * a_0 + w_1 * a_1 + ...` of an arithmetic progression.
+//!
+//! The weights are public, read from the instance into a `w` column. Weights of `1`
+//! recover [`crate::ApSumCircuit`]; alternating `1` and `-1` give an alternating sum.
+//!
+//! Instance layout: `[first, sum, w_0, ..., w_{COUNT-1}]`.
+
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+use std::marker::PhantomData;
+
+/// Instance row of the first weight.
+const WEIGHTS_OFFSET: usize = 2;
+
+#[derive(Clone, Debug)]
+pub struct WeightedApSumConfig {
+    // [a_n, w_n, sum_n]
+    advice: [Column<Advice>; 3],
+    s_first: Selector,
+    s_next: Selector,
+    instance: Column<Instance>,
+}
+
+struct WeightedApSumChip<F, const STEP: u128, const COUNT: usize> {
+    config: WeightedApSumConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> WeightedApSumChip<F, STEP, COUNT> {
+    fn construct(config: WeightedApSumConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 3],
+        instance: Column<Instance>,
+    ) -> WeightedApSumConfig {
+        let s_first = meta.selector();
+        let s_next = meta.selector();
+
+        for column in advice {
+            meta.enable_equality(column);
+        }
+        meta.enable_equality(instance);
+
+        // |  advice[0]   |  advice[1]  |    advice[2]   | s_first | s_next
+        // -----------------------------------------------------------------
+        // |     a_0      |     w_0     |     sum_0      |    s    |
+        // |     a_1      |     w_1     |     sum_1      |         |   s
+        // |     ...      |     ...     |     ...        |         |   s
+        meta.create_gate("weighted first term", |meta| {
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let w = meta.query_advice(advice[1], Rotation::cur());
+            let sum = meta.query_advice(advice[2], Rotation::cur());
+            let s = meta.query_selector(s_first);
+            // sum_0 == w_0 * a_0
+            vec![s * (w * a - sum)]
+        });
+
+        meta.create_gate("step and weighted sum", |meta| {
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let w = meta.query_advice(advice[1], Rotation::cur());
+            let sum = meta.query_advice(advice[2], Rotation::cur());
+            let prev_a = meta.query_advice(advice[0], Rotation::prev());
+            let prev_sum = meta.query_advice(advice[2], Rotation::prev());
+            let s = meta.query_selector(s_next);
+            vec![
+                // sum == prev_sum + w * a
+                s.clone() * (w * a.clone() + prev_sum - sum),
+                // a == prev_a + STEP
+                s * (a - prev_a - Expression::Constant(F::from_u128(STEP))),
+            ]
+        });
+
+        WeightedApSumConfig {
+            advice,
+            s_first,
+            s_next,
+            instance,
+        }
+    }
+
+    fn assign(&self, mut layouter: impl Layouter<F>) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "weighted AP sum table",
+            |mut region| {
+                let [a_column, w_column, sum_column] = self.config.advice;
+                let step = F::from_u128(STEP);
+
+                let weight = |region: &mut Region<'_, F>, row: usize| {
+                    region.assign_advice_from_instance(
+                        || "w",
+                        self.config.instance,
+                        WEIGHTS_OFFSET + row,
+                        w_column,
+                        row,
+                    )
+                };
+
+                self.config.s_first.enable(&mut region, 0)?;
+                let mut a_cell = region.assign_advice_from_instance(
+                    || "a",
+                    self.config.instance,
+                    0,
+                    a_column,
+                    0,
+                )?;
+                let w_cell = weight(&mut region, 0)?;
+                let mut sum_cell = region.assign_advice(
+                    || "sum",
+                    sum_column,
+                    0,
+                    || a_cell.value().copied() * w_cell.value().copied(),
+                )?;
+
+                for row in 1..COUNT {
+                    self.config.s_next.enable(&mut region, row)?;
+
+                    let new_a_val = a_cell.value().map(|a| *a + step);
+                    a_cell = region.assign_advice(|| "a", a_column, row, || new_a_val)?;
+                    let w_cell = weight(&mut region, row)?;
+
+                    let new_sum = sum_cell.value().copied() + w_cell.value().copied() * new_a_val;
+                    sum_cell = region.assign_advice(|| "sum", sum_column, row, || new_sum)?;
+                }
+
+                Ok(sum_cell)
+            },
+        )
+    }
+
+    fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+#[derive(Default)]
+pub struct WeightedApSumCircuit<const STEP: u128, const COUNT: usize>;
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> Circuit<F>
+    for WeightedApSumCircuit<STEP, COUNT>
+{
+    type Config = WeightedApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [(); 3].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+
+        WeightedApSumChip::<_, STEP, COUNT>::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = WeightedApSumChip::<_, STEP, COUNT>::construct(config);
+        let sum_cell = chip.assign(layouter.namespace(|| "weighted AP sum table"))?;
+        chip.expose_public(layouter.namespace(|| "output"), &sum_cell, 1)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, halo2curves::pasta::Fp};
+
+    #[test]
+    fn weights_one_two_three_over_one_two_three_works() {
+        let k = 4;
+        let circuit = WeightedApSumCircuit::<1, 3>;
+        // 1*1 + 2*2 + 3*3 = 14
+        let instance = vec![
+            Fp::from(1),
+            Fp::from(14),
+            Fp::from(1),
+            Fp::from(2),
+            Fp::from(3),
+        ];
+        let prover = MockProver::run(k, &circuit, vec![instance]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn alternating_weights_work() {
+        let k = 4;
+        let circuit = WeightedApSumCircuit::<1, 4>;
+        // 1 - 2 + 3 - 4 = -2
+        let instance = vec![
+            Fp::from(1),
+            -Fp::from(2),
+            Fp::from(1),
+            -Fp::from(1),
+            Fp::from(1),
+            -Fp::from(1),
+        ];
+        let prover = MockProver::run(k, &circuit, vec![instance]).unwrap();
+        prover.assert_satisfied();
+    }
+}
@@ -0,0 +1,203 @@
+//! Sums over sliding windows `[start, start + window)` of a long progression.
+//!
+//! [`WindowApSumCircuit`] lays out the full table and exposes the window sum
+//! `sum_{start+window-1} - sum_{start-1}`, constrained by a difference gate over copies
+//! of the two running sums. The first term stays public, so every window proof is tied
+//! to the same progression. The window position shapes the copy constraints, so each
+//! window has its own verifying key; [`prove_windows`] generates them from shared params.
+//!
+//! Instance layout: `[first, window_sum]`.
+
+use crate::{
+    ap_sum,
+    proof::{prove, Params, Proof},
+    ApSumChip, ApSumConfig, ApSumError,
+};
+use halo2_proofs::{
+    arithmetic::FieldExt, circuit::*, halo2curves::pasta::Fp, plonk::*, poly::Rotation,
+};
+use rand_core::{CryptoRng, RngCore};
+
+#[derive(Clone, Debug)]
+pub struct WindowApSumConfig {
+    ap_sum: ApSumConfig,
+    // [hi, lo, diff]
+    difference: [Column<Advice>; 3],
+    s_diff: Selector,
+}
+
+#[derive(Clone, Default)]
+pub struct WindowApSumCircuit<const STEP: u128, const COUNT: usize> {
+    /// Index of the first term in the window.
+    pub start: usize,
+    /// Number of terms in the window.
+    pub window: usize,
+}
+
+impl<F: FieldExt, const STEP: u128, const COUNT: usize> Circuit<F>
+    for WindowApSumCircuit<STEP, COUNT>
+{
+    type Config = WindowApSumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        // The window shapes the copy constraints, not the witness.
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let ap_sum = ApSumChip::<_, STEP, COUNT>::configure_sum_only(meta, advice, instance);
+
+        let difference = [(); 3].map(|_| meta.advice_column());
+        for column in difference {
+            meta.enable_equality(column);
+        }
+        let s_diff = meta.selector();
+
+        // |  hi  |  lo  | diff | s_diff
+        // -------------------------------
+        // |  h   |  l   |  d   |   s       d == h - l
+        meta.create_gate("window difference", |meta| {
+            let hi = meta.query_advice(difference[0], Rotation::cur());
+            let lo = meta.query_advice(difference[1], Rotation::cur());
+            let diff = meta.query_advice(difference[2], Rotation::cur());
+            let s = meta.query_selector(s_diff);
+            vec![s * (hi - lo - diff)]
+        });
+
+        WindowApSumConfig {
+            ap_sum,
+            difference,
+            s_diff,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        if self.window == 0 || self.start + self.window > COUNT {
+            return Err(Error::Synthesis);
+        }
+
+        let chip = ApSumChip::<_, STEP, COUNT>::construct(config.ap_sum);
+        let cells = chip.assign(layouter.namespace(|| "AP sum table"))?;
+        let hi = &cells.partial_sums[self.start + self.window - 1];
+        let window_sum = match self.start.checked_sub(1) {
+            // A window from the first term is a running sum itself.
+            None => hi.clone(),
+            Some(before) => {
+                let lo = &cells.partial_sums[before];
+                layouter.assign_region(
+                    || "window difference",
+                    |mut region| {
+                        config.s_diff.enable(&mut region, 0)?;
+                        let [hi_column, lo_column, diff_column] = config.difference;
+                        let hi = hi.copy_advice(|| "hi", &mut region, hi_column, 0)?;
+                        let lo = lo.copy_advice(|| "lo", &mut region, lo_column, 0)?;
+                        let diff = hi.value().copied() - lo.value().copied();
+                        region.assign_advice(|| "diff", diff_column, 0, || diff)
+                    },
+                )?
+            }
+        };
+        chip.expose_public(layouter.namespace(|| "window sum"), &window_sum, 1)?;
+        Ok(())
+    }
+}
+
+/// Computes the integer sum of the `window` terms from index `start` of the progression
+/// `first, first + step, ...`.
+pub fn window_sum(
+    first: u128,
+    step: u128,
+    start: usize,
+    window: usize,
+) -> Result<u128, ApSumError> {
+    let window_first = u128::try_from(start)
+        .ok()
+        .and_then(|start| start.checked_mul(step))
+        .and_then(|offset| offset.checked_add(first))
+        .ok_or(ApSumError::Overflow)?;
+    ap_sum(window_first, step, window)
+}
+
+/// Proves the sum of every window of `window` consecutive terms of the `COUNT`-term
+/// progression from `first`, returning `(start, proof)` pairs in order of `start`.
+///
+/// Each proof is made for `WindowApSumCircuit { start, window }` under a proving key
+/// generated from `params`, and verifies against `[first, window_sum(first, STEP, start,
+/// window)]` with that circuit's verifying key.
+pub fn prove_windows<const STEP: u128, const COUNT: usize>(
+    params: &Params,
+    first: u128,
+    window: usize,
+    mut rng: impl RngCore + CryptoRng,
+) -> Result<Vec<(usize, Proof)>, ApSumError> {
+    if window == 0 || window > COUNT {
+        return Err(ApSumError::Proving(Error::Synthesis));
+    }
+
+    (0..=COUNT - window)
+        .map(|start| {
+            let circuit = WindowApSumCircuit::<STEP, COUNT> { start, window };
+            let vk = keygen_vk(params, &circuit).map_err(ApSumError::Proving)?;
+            let pk = keygen_pk(params, vk, &circuit).map_err(ApSumError::Proving)?;
+            let sum = window_sum(first, STEP, start, window)?;
+            let instance = [Fp::from_u128(first), Fp::from_u128(sum)];
+            let proof =
+                prove(params, &pk, circuit, &instance, &mut rng).map_err(ApSumError::Proving)?;
+            Ok((start, proof))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proof::verify;
+    use halo2_proofs::{dev::MockProver, poly::commitment::ParamsProver};
+    use rand_core::OsRng;
+
+    const K: u32 = 5;
+
+    #[test]
+    fn windows_of_three_over_six_terms_hold() {
+        // 1..=6: windows 1+2+3, 2+3+4, 3+4+5, 4+5+6
+        let sums: Vec<_> = (0..4)
+            .map(|start| window_sum(1, 1, start, 3).unwrap())
+            .collect();
+        assert_eq!(sums, [6, 9, 12, 15]);
+
+        for (start, sum) in sums.into_iter().enumerate() {
+            let circuit = WindowApSumCircuit::<1, 6> { start, window: 3 };
+            let instance = vec![Fp::from(1), Fp::from_u128(sum)];
+            let prover = MockProver::run(K, &circuit, vec![instance]).unwrap();
+            prover.assert_satisfied();
+
+            let instance = vec![Fp::from(1), Fp::from_u128(sum + 1)];
+            let prover = MockProver::run(K, &circuit, vec![instance]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+    }
+
+    #[test]
+    fn prove_windows_emits_one_proof_per_window() {
+        let params = Params::new(K);
+        let proofs = prove_windows::<1, 6>(&params, 1, 3, OsRng).unwrap();
+        let starts: Vec<_> = proofs.iter().map(|(start, _)| *start).collect();
+        assert_eq!(starts, [0, 1, 2, 3]);
+
+        let (start, proof) = &proofs[0];
+        let circuit = WindowApSumCircuit::<1, 6> {
+            start: *start,
+            window: 3,
+        };
+        let vk = keygen_vk(&params, &circuit).unwrap();
+        assert!(verify(&params, &vk, proof, &[Fp::from(1), Fp::from(6)]));
+        assert!(!verify(&params, &vk, proof, &[Fp::from(1), Fp::from(9)]));
+    }
+}
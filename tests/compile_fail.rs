@@ -0,0 +1,5 @@
+#[test]
+fn invalid_parameters_fail_to_compile() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,16 @@
+//! Rendering through plotters, built only with the `dev-graph` feature.
+//!
+//! This is the only test target that names plotters; a default `cargo test` compiles it
+//! to nothing, so plotters is never built without the feature.
+#![cfg(feature = "dev-graph")]
+
+use arithmetic_progression_sum::{render::render_to_buffer, ApSumCircuit};
+use halo2_proofs::halo2curves::pasta::Fp;
+use plotters::style::{Color, WHITE};
+
+#[test]
+fn layout_background_is_plotters_white() {
+    let (buffer, _) = render_to_buffer::<Fp, _>(5, &ApSumCircuit::<1, 5>, 5).unwrap();
+    let (r, g, b) = WHITE.rgb();
+    assert_eq!(&buffer[..3], &[r, g, b]);
+}
@@ -0,0 +1,28 @@
+//! Composition of the AP sum with `GreaterThanChip`, through the public circuit.
+
+use arithmetic_progression_sum::exceeds::ExceedsThresholdCircuit;
+use halo2_proofs::{
+    dev::{MockProver, VerifyFailure},
+    halo2curves::pasta::Fp,
+};
+
+fn run(threshold: u64) -> Result<(), Vec<VerifyFailure>> {
+    let k = 9;
+    // 1 + 2 + 3 + 4 + 5 = 15
+    let instance = vec![Fp::from(1), Fp::from(threshold)];
+    MockProver::run(k, &ExceedsThresholdCircuit::<1, 5>, vec![instance])
+        .unwrap()
+        .verify()
+}
+
+#[test]
+fn sum_fifteen_exceeds_ten() {
+    assert_eq!(run(10), Ok(()));
+}
+
+#[test]
+fn sum_fifteen_does_not_exceed_twenty() {
+    assert!(run(20).is_err());
+    // strictly greater
+    assert!(run(15).is_err());
+}
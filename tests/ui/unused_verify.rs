@@ -0,0 +1,14 @@
+#![deny(unused_must_use)]
+
+use arithmetic_progression_sum::proof::{verify, Params, Proof};
+use halo2_proofs::{
+    halo2curves::pasta::{EqAffine, Fp},
+    plonk::VerifyingKey,
+};
+
+#[allow(dead_code)]
+fn check(params: &Params, vk: &VerifyingKey<EqAffine>, proof: &Proof, instance: &[Fp]) {
+    verify(params, vk, proof, instance);
+}
+
+fn main() {}